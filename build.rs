@@ -0,0 +1,67 @@
+/// Generates one test case per entry of the example manifests in `data/examples/NN.json`.
+/// The generated files are included by the `solution!` macro, see `src/template/examples.rs`.
+use std::{collections::HashMap, env, fs, path::Path, str::FromStr};
+
+use tinyjson::JsonValue;
+
+fn test_name(index: usize, name: Option<&str>) -> String {
+    let mut test_name = format!("example_{}", index + 1);
+
+    if let Some(name) = name {
+        test_name.push('_');
+        test_name.extend(name.chars().map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        }));
+    }
+
+    test_name
+}
+
+fn example_names(manifest: &str) -> Result<Vec<Option<String>>, String> {
+    let json = JsonValue::from_str(manifest).or(Err("not valid JSON file."))?;
+
+    let examples = json
+        .get::<HashMap<String, JsonValue>>()
+        .and_then(|json| json.get("examples"))
+        .and_then(|examples| examples.get::<Vec<JsonValue>>())
+        .ok_or("expected `json.examples` to be an array.")?;
+
+    Ok(examples
+        .iter()
+        .map(|example| {
+            example
+                .get::<HashMap<String, JsonValue>>()
+                .and_then(|example| example.get("name"))
+                .and_then(|name| name.get::<String>())
+                .cloned()
+        })
+        .collect())
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed=data/examples");
+
+    let out_dir = Path::new(&env::var("OUT_DIR").unwrap()).join("examples");
+    fs::create_dir_all(&out_dir).unwrap();
+
+    for day in 1..=25 {
+        let manifest_path = format!("data/examples/{day:02}.json");
+
+        // every day gets a file, so the `include!` in `solution!` never fails.
+        let tests = match fs::read_to_string(&manifest_path) {
+            Ok(manifest) => example_names(&manifest)
+                .unwrap_or_else(|e| panic!("invalid example manifest \"{manifest_path}\": {e}"))
+                .iter()
+                .enumerate()
+                .map(|(i, name)| format!("example!({i}, {});\n", test_name(i, name.as_deref())))
+                .collect::<String>(),
+            Err(_) => String::new(),
+        };
+
+        fs::write(out_dir.join(format!("{day}.rs")), tests).unwrap();
+    }
+}
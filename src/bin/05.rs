@@ -1,21 +1,9 @@
-use std::{cmp::Ordering, collections::HashMap};
-
+use advent_of_code::template::graph::Graph;
 use itertools::Itertools;
 
 advent_of_code::solution!(5);
 
-#[derive(PartialEq, Eq)]
-enum Order {
-    Before,
-    After,
-}
-
-fn parse(
-    input: &str,
-) -> (
-    HashMap<(u32, u32), Order>,
-    impl Iterator<Item = Vec<u32>> + '_,
-) {
+fn parse(input: &str) -> (Graph<u32>, impl Iterator<Item = Vec<u32>> + '_) {
     let (rules, pages) = input.split_once("\n\n").unwrap();
 
     let rules = rules
@@ -26,12 +14,7 @@ fn parse(
                 .next_tuple()
                 .unwrap()
         })
-        .fold(HashMap::new(), |mut map, (left, right)| {
-            map.insert((left, right), Order::Before);
-            map.insert((right, left), Order::After);
-
-            map
-        });
+        .collect();
 
     let pages = pages.lines().map(|line| {
         line.split(",")
@@ -47,48 +30,32 @@ pub fn part_one(input: &str) -> Option<u32> {
 
     Some(
         pages
-            .filter(|line| {
-                line.iter().enumerate().all(|(i, left)| {
-                    line[i + 1..]
-                        .iter()
-                        .flat_map(|right| rules.get(&(*left, *right)))
-                        .all(|order| *order == Order::Before)
-                })
-            })
+            .filter(|line| rules.is_ordered(line))
             .map(|line| line[line.len() / 2])
             .sum(),
     )
 }
 
+/// Only the rules between the pages of an update apply to it, and they may allow several orders.
+/// The middle page is known if it is the same in all of them. There is no answer if the rules of an
+/// update are cyclic, or leave its middle page open.
 pub fn part_two(input: &str) -> Option<u32> {
     let (rules, pages) = parse(input);
 
-    Some(
-        pages
-            .filter(|line| {
-                !line.iter().enumerate().all(|(i, left)| {
-                    line[i + 1..]
-                        .iter()
-                        .flat_map(|right| rules.get(&(*left, *right)))
-                        .all(|order| *order == Order::Before)
-                })
-            })
-            .map(|mut line| {
-                line.sort_unstable_by(|a, b| match rules.get(&(*a, *b)) {
-                    Some(Order::Before) => Ordering::Less,
-                    Some(Order::After) => Ordering::Greater,
-                    None => Ordering::Equal,
-                });
-                line
-            })
-            .map(|line| line[line.len() / 2])
-            .sum(),
-    )
+    pages
+        .filter(|line| !rules.is_ordered(line))
+        .map(|line| {
+            let update = rules.induced(&line);
+            update.topological_sort().ok()?;
+            update.fixed_at(line.len() / 2).copied()
+        })
+        .sum()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use advent_of_code::template::graph::check_comparator;
 
     #[test]
     fn test_part_one() {
@@ -101,4 +68,32 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(123));
     }
+
+    #[test]
+    fn test_example_rules_are_total() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let (rules, pages) = parse(&input);
+
+        for line in pages {
+            assert_eq!(check_comparator(&line, |a, b| rules.compare(a, b)), Ok(()));
+        }
+    }
+
+    #[test]
+    fn test_partial_rules() {
+        // 1 comes before 4 only through 2 and 3, which a comparator does not see.
+        let chain = "1|2\n2|3\n3|4\n4|5\n\n4,1,5,3,2";
+        let (rules, mut pages) = parse(chain);
+        let line = pages.next().unwrap();
+        assert!(check_comparator(&line, |a, b| rules.compare(a, b)).is_err());
+        assert_eq!(part_two(chain), Some(3));
+
+        // 1 and 2 are unordered, but 3 is in the middle either way.
+        assert_eq!(part_two("1|3\n2|3\n3|4\n3|5\n\n4,3,5,2,1"), Some(3));
+        // the middle of 3,2,1 could be 2 or 3.
+        assert_eq!(part_two("1|2\n1|3\n4|5\n\n3,2,1\n5,4"), None);
+        assert_eq!(part_two("1|2\n1|3\n4|5\n\n1,3\n5,4"), Some(5));
+        assert_eq!(part_two("1|2\n2|1\n\n2,1"), None);
+        assert_eq!(part_one("1|2\n2|1\n\n3,4,5"), Some(4));
+    }
 }
@@ -1,7 +1,22 @@
 use std::collections::HashSet;
 
+use advent_of_code::template::visualize::{self, Frame, Palette, Rgb, Style};
+
 advent_of_code::solution!(6);
 
+const PALETTE: Palette = Palette::new(
+    Rgb(20, 20, 20),
+    &[
+        (b'.', Style::new('·', Rgb(70, 70, 70))),
+        (b'#', Style::new('#', Rgb(200, 120, 40))),
+        (b'X', Style::new('•', Rgb(60, 140, 230))),
+        (b'^', Style::new('^', Rgb(255, 230, 0)).on(Rgb(120, 20, 20))),
+        (b'>', Style::new('>', Rgb(255, 230, 0)).on(Rgb(120, 20, 20))),
+        (b'v', Style::new('v', Rgb(255, 230, 0)).on(Rgb(120, 20, 20))),
+        (b'<', Style::new('<', Rgb(255, 230, 0)).on(Rgb(120, 20, 20))),
+    ],
+);
+
 #[derive(Copy, Clone, Debug, Default, Hash, PartialEq, Eq)]
 enum Direction {
     #[default]
@@ -22,6 +37,17 @@ impl Direction {
     }
 }
 
+impl From<Direction> for u8 {
+    fn from(dir: Direction) -> Self {
+        match dir {
+            Direction::Up => b'^',
+            Direction::Right => b'>',
+            Direction::Down => b'v',
+            Direction::Left => b'<',
+        }
+    }
+}
+
 impl From<Direction> for (i32, i32) {
     fn from(dir: Direction) -> Self {
         match dir {
@@ -134,9 +160,16 @@ pub fn part_one(input: &str) -> Option<u32> {
 
     Some(
         Walker::new(pos, map)
-            .fold(HashSet::new(), |mut visited, (pos, _)| {
+            .fold(HashSet::new(), |mut visited, (pos, direction)| {
                 visited.insert(pos);
 
+                visualize::record(|| {
+                    let mut frame = Frame::from_rows(&input.replace('^', "."), &PALETTE);
+                    frame.overlay(visited.iter().map(|&(x, y)| (x as usize, y as usize)), b'X');
+                    frame.set(pos.0 as usize, pos.1 as usize, direction.into());
+                    frame.with_caption(format!("{} positions visited", visited.len()))
+                });
+
                 visited
             })
             .len() as u32,
@@ -208,6 +241,7 @@ pub fn part_two(input: &str) -> Option<u32> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use advent_of_code::template::property::Grid;
 
     #[test]
     fn test_part_one() {
@@ -220,4 +254,76 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(6));
     }
+
+    #[test]
+    fn walk_matches_brute_force() {
+        use advent_of_code::template::property::{self, grid, int, Strategy};
+
+        // like in the puzzle, the guard has to leave the lab. it also has to be able to leave its
+        // starting position, otherwise it turns forever.
+        let lab = (grid(1..=8, 1..=8, ".#"), int(0..=63))
+            .filter(|(grid, start)| place_guard(grid, *start).is_some());
+
+        property::assert_equivalent(
+            &lab,
+            |(grid, start)| brute_force(&place_guard(grid, *start).unwrap()),
+            |(grid, start)| part_two(&place_guard(grid, *start).unwrap()),
+        );
+    }
+
+    /// Places the guard on the grid, if the guard can leave the lab from there.
+    fn place_guard(grid: &Grid, start: i64) -> Option<String> {
+        let mut grid = grid.clone();
+        let start = start as usize % (grid.width() * grid.height());
+        let (x, y) = (start % grid.width(), start / grid.width());
+        grid.cells[y][x] = '^';
+
+        let walls = [(0, -1), (1, 0), (0, 1), (-1, 0)]
+            .iter()
+            .filter(|(dx, dy)| {
+                let cell = y
+                    .checked_add_signed(*dy)
+                    .zip(x.checked_add_signed(*dx))
+                    .and_then(|(y, x)| grid.cells.get(y)?.get(x));
+                cell == Some(&'#')
+            })
+            .count();
+
+        if walls >= 3 {
+            return None;
+        }
+
+        let input = grid.to_string();
+        let (start_pos, map) = parse(&input);
+        let mut visited = HashSet::new();
+
+        Walker::new(start_pos, map)
+            .all(|step| visited.insert(step))
+            .then_some(input)
+    }
+
+    /// Tries every free position for the new obstruction.
+    fn brute_force(input: &str) -> Option<u32> {
+        let (start_pos, map) = parse(input);
+        let mut loops = 0;
+
+        for (y, row) in map.iter().enumerate() {
+            for (x, free) in row.iter().enumerate() {
+                let wall_pos = (x as u32, y as u32);
+                if !free || wall_pos == start_pos {
+                    continue;
+                }
+
+                let mut visited = HashSet::new();
+                if !Walker::new(start_pos, map.clone())
+                    .with_wall(wall_pos)
+                    .all(|step| visited.insert(step))
+                {
+                    loops += 1;
+                }
+            }
+        }
+
+        Some(loops)
+    }
 }
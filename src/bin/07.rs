@@ -1,72 +1,25 @@
-use std::collections::VecDeque;
+use advent_of_code::template::expression::{self, Operator, Search, ADD, CONCAT, MUL};
 
 advent_of_code::solution!(7);
 
-fn check_mul(n: u128, total: u128) -> Option<u128> {
-    (n != 0 && total % n == 0).then(|| total / n)
-}
-
-fn check_add(n: u128, total: u128) -> Option<u128> {
-    total.checked_sub(n)
-}
-
-fn check_append(n: u128, total: u128) -> Option<u128> {
-    // Build a base 10 mask of the number
-    //   12  ->  100 (tens)
-    //   123 -> 1000 (hundreds)
-    // This mask can be used to extract a portion of the total.
-    let mask = 10u128.pow((n as f64).log10() as u32 + 1);
-
-    (total % mask == n).then_some(total / mask)
-}
-
-fn solve(input: &str, operator_checks: impl AsRef<[fn(u128, u128) -> Option<u128>]>) -> u128 {
-    input
-        .lines()
-        .map(|line| line.split_once(": ").unwrap())
-        .map(|(total, numbers)| {
-            (
-                total.parse().unwrap(),
-                numbers
-                    .split_whitespace()
-                    .map(|n| n.parse().unwrap())
-                    // Operators are applied left to right, so reverse them right to left
-                    .rev(),
-            )
-        })
-        .filter_map(|(original_total, numbers)| {
-            let mut search = VecDeque::from_iter([(original_total, numbers)]);
+/// The sum of the targets that can be reached with the given operators.
+fn solve(input: &str, operators: &[Operator<u128>]) -> u128 {
+    let equations = expression::parse_equations::<u128>(input).unwrap();
 
-            while let Some((total, mut numbers)) = search.pop_front() {
-                let Some(n) = numbers.next() else {
-                    // Total has been found!
-                    if total == 0 {
-                        return Some(original_total);
-                    }
-
-                    continue;
-                };
-
-                search.extend(
-                    operator_checks
-                        .as_ref()
-                        .iter()
-                        .flat_map(|check| check(n, total))
-                        .map(|total| (total, numbers.clone())),
-                );
-            }
-
-            None
-        })
+    Search::new(operators)
+        .find_all(&equations)
+        .into_iter()
+        .zip(&equations)
+        .filter_map(|(witness, equation)| witness.map(|_| equation.target))
         .sum()
 }
 
 pub fn part_one(input: &str) -> Option<u128> {
-    Some(solve(input, [check_mul, check_add]))
+    Some(solve(input, &[ADD, MUL]))
 }
 
 pub fn part_two(input: &str) -> Option<u128> {
-    Some(solve(input, [check_mul, check_add, check_append]))
+    Some(solve(input, &[ADD, MUL, CONCAT]))
 }
 
 #[cfg(test)]
@@ -84,4 +37,15 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(11387));
     }
+
+    #[test]
+    fn test_part_two_concatenation() {
+        assert_eq!(part_two("12345: 12 345"), Some(12345));
+        assert_eq!(part_two("120: 12 0"), Some(120));
+        // rounds to 10^18 as a float, which used to give a mask with one digit too many.
+        assert_eq!(
+            part_two("1999999999999999999: 1 999999999999999999"),
+            Some(1_999_999_999_999_999_999)
+        );
+    }
 }
@@ -1,44 +1,30 @@
-use std::collections::HashMap;
+use advent_of_code::template::multiset::{Count, Engine, Multiset};
 
 advent_of_code::solution!(11);
 
-fn blinker(input: &str, n: usize) -> u64 {
-    let mut stones = input
-        .split_whitespace()
-        .map(|c| c.parse::<u64>().unwrap())
-        .fold(HashMap::<u64, u64>::new(), |mut stones, stone| {
-            *stones.entry(stone).or_default() += 1;
-
-            stones
-        });
-
-    for _ in 0..n {
-        stones = stones
-            .into_iter()
-            .flat_map(|(stone, count)| {
-                if stone == 0 {
-                    return [Some((1, count)), None];
-                }
+/// What a stone turns into when blinking.
+fn blink(stone: &u64) -> Vec<u64> {
+    if *stone == 0 {
+        return vec![1];
+    }
 
-                let num_digits = stone.ilog10() + 1;
-                if num_digits % 2 == 0 {
-                    return [
-                        Some((stone / 10u64.pow(num_digits / 2), count)),
-                        Some((stone % 10u64.pow(num_digits / 2), count)),
-                    ];
-                }
+    let num_digits = stone.ilog10() + 1;
+    if num_digits.is_multiple_of(2) {
+        let half = 10u64.pow(num_digits / 2);
+        return vec![stone / half, stone % half];
+    }
 
-                [Some((stone * 2024, count)), None]
-            })
-            .flatten()
-            .fold(HashMap::new(), |mut stones, (stone, count)| {
-                *stones.entry(stone).or_default() += count;
+    vec![stone * 2024]
+}
 
-                stones
-            });
-    }
+/// The number of stones after blinking `n` times, counted with `C`.
+fn blinker<C: Count>(input: &str, n: usize) -> C {
+    let stones: Multiset<u64, C> = input
+        .split_whitespace()
+        .map(|c| c.parse::<u64>().unwrap())
+        .collect();
 
-    stones.values().sum()
+    Engine::new(blink).run(&stones, n).total()
 }
 
 pub fn part_one(input: &str) -> Option<u64> {
@@ -52,6 +38,7 @@ pub fn part_two(input: &str) -> Option<u64> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use advent_of_code::template::bigint::BigUint;
 
     #[test]
     fn test_part_one() {
@@ -64,4 +51,21 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(65601038650482));
     }
+
+    #[test]
+    fn test_large_counts() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let small = blinker::<u128>(&input, 75);
+        assert_eq!(small, 65601038650482);
+
+        // more stones than fit into a u64.
+        let large = blinker::<u128>(&input, 150);
+        assert!(large > u128::from(u64::MAX));
+        assert!(std::panic::catch_unwind(|| blinker::<u64>(&input, 150)).is_err());
+
+        // and more than fit into a u128.
+        let huge = blinker::<BigUint>(&input, 300);
+        assert_eq!(blinker::<BigUint>(&input, 150).to_u128(), Some(large));
+        assert_eq!(huge.to_u128(), None);
+    }
 }
@@ -1,9 +1,29 @@
 use std::collections::{HashSet, VecDeque};
 
+use advent_of_code::template::visualize::{self, Frame, Palette, Rgb, Style};
 use itertools::Itertools;
 
 advent_of_code::solution!(12);
 
+/// Unvisited plots keep their plant letter, regions are coloured by the order they are found in.
+const PALETTE: Palette = Palette::new(
+    Rgb(20, 20, 20),
+    &[
+        (0, Style::new('█', Rgb(230, 85, 70))),
+        (1, Style::new('█', Rgb(240, 160, 50))),
+        (2, Style::new('█', Rgb(235, 215, 80))),
+        (3, Style::new('█', Rgb(140, 200, 70))),
+        (4, Style::new('█', Rgb(60, 170, 90))),
+        (5, Style::new('█', Rgb(70, 200, 180))),
+        (6, Style::new('█', Rgb(60, 150, 220))),
+        (7, Style::new('█', Rgb(90, 100, 220))),
+        (8, Style::new('█', Rgb(150, 90, 210))),
+        (9, Style::new('█', Rgb(215, 90, 180))),
+        (10, Style::new('█', Rgb(170, 120, 80))),
+        (11, Style::new('█', Rgb(200, 200, 200))),
+    ],
+);
+
 pub fn part_one(input: &str) -> Option<u32> {
     let map = input
         .lines()
@@ -12,6 +32,7 @@ pub fn part_one(input: &str) -> Option<u32> {
 
     let mut visited = HashSet::new();
     let mut fences = Vec::new();
+    let mut frame = visualize::enabled().then(|| Frame::from_rows(input, &PALETTE));
 
     for (x, y) in (0..map.len()).flat_map(|y| (0..map[y].len()).map(move |x| (x, y))) {
         if visited.contains(&(x, y)) {
@@ -19,6 +40,7 @@ pub fn part_one(input: &str) -> Option<u32> {
         }
 
         let c = map[y][x];
+        let kind = (fences.len() % PALETTE.styles.len()) as u8;
 
         let mut area = 0;
         let mut perimeter = 0;
@@ -33,6 +55,10 @@ pub fn part_one(input: &str) -> Option<u32> {
             // Mark this location as visited
             visited.insert((x, y));
 
+            if let Some(frame) = &mut frame {
+                frame.set(x, y, kind);
+            }
+
             // Increase the area of this region
             area += 1;
 
@@ -57,6 +83,14 @@ pub fn part_one(input: &str) -> Option<u32> {
         }
 
         fences.push((area, perimeter));
+
+        if let Some(frame) = &frame {
+            visualize::record(|| {
+                frame
+                    .clone()
+                    .with_caption(format!("region {}", fences.len()))
+            });
+        }
     }
 
     Some(
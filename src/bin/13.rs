@@ -75,52 +75,62 @@ pub fn part_one(input: &str) -> Option<u32> {
 }
 
 pub fn part_two(input: &str) -> Option<u64> {
-    Some(
-        input
-            .split("\n\n")
-            .map(|machine| {
-                let (button_a, button_b, prize) = machine
-                    .lines()
-                    .map(|line| {
-                        line.split_once(": ")
-                            .unwrap()
-                            .1
-                            .split(", ")
-                            .map(|n| n[2..].parse::<u64>().unwrap())
-                            .collect_tuple()
-                            .unwrap()
-                    })
-                    .collect_tuple()
-                    .unwrap();
-
-                Machine {
-                    button_a,
-                    button_b,
-                    prize: (prize.0 + 10000000000000, prize.1 + 10000000000000),
-                }
-            })
-            .filter_map(|machine| {
-                let a = machine.button_a.0 as i64;
-                let b = machine.button_b.0 as i64;
-                let c = machine.prize.0 as i64;
-                let d = machine.button_a.1 as i64;
-                let e = machine.button_b.1 as i64;
-                let f = machine.prize.1 as i64;
-
-                let xa = c * e - b * f;
-                let xb = a * e - b * d;
-
-                let ya = -c * d + a * f;
-                let yb = a * e - b * d;
-
-                if xa % xb != 0 || ya % yb != 0 {
-                    return None;
-                }
+    Some(min_tokens(input, 10000000000000))
+}
 
-                Some(((xa / xb) * 3 + ya / yb) as u64)
-            })
-            .sum(),
-    )
+/// Solves every machine in closed form, with the prizes moved by `offset`.
+fn min_tokens(input: &str, offset: u64) -> u64 {
+    input
+        .split("\n\n")
+        .map(|machine| {
+            let (button_a, button_b, prize) = machine
+                .lines()
+                .map(|line| {
+                    line.split_once(": ")
+                        .unwrap()
+                        .1
+                        .split(", ")
+                        .map(|n| n[2..].parse::<u64>().unwrap())
+                        .collect_tuple()
+                        .unwrap()
+                })
+                .collect_tuple()
+                .unwrap();
+
+            Machine {
+                button_a,
+                button_b,
+                prize: (prize.0 + offset, prize.1 + offset),
+            }
+        })
+        .filter_map(|machine| {
+            let a = machine.button_a.0 as i64;
+            let b = machine.button_b.0 as i64;
+            let c = machine.prize.0 as i64;
+            let d = machine.button_a.1 as i64;
+            let e = machine.button_b.1 as i64;
+            let f = machine.prize.1 as i64;
+
+            let xa = c * e - b * f;
+            let xb = a * e - b * d;
+
+            let ya = -c * d + a * f;
+            let yb = a * e - b * d;
+
+            if xa % xb != 0 || ya % yb != 0 {
+                return None;
+            }
+
+            let (presses_a, presses_b) = (xa / xb, ya / yb);
+
+            // Buttons can't be pressed a negative number of times
+            if presses_a < 0 || presses_b < 0 {
+                return None;
+            }
+
+            Some((presses_a * 3 + presses_b) as u64)
+        })
+        .sum()
 }
 
 #[cfg(test)]
@@ -138,4 +148,49 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(875318608908));
     }
+
+    #[test]
+    fn test_part_two_negative_presses() {
+        // only reachable with a negative number of B presses.
+        let result = part_two("Button A: X+2, Y+1\nButton B: X+3, Y+1\nPrize: X=1, Y=1");
+        assert_eq!(result, Some(0));
+    }
+
+    #[test]
+    fn closed_form_matches_brute_force() {
+        use advent_of_code::template::property::{self, blocks, int, Strategy};
+
+        let button = (int(1..=20), int(1..=20));
+        let presses = (int(0..=100), int(0..=100));
+
+        // a machine with independent buttons, defined by its buttons, the presses needed to win
+        // and an offset that moves the prize off the solution.
+        let machine = (button.clone(), button, presses, int(0..=1))
+            .filter(|(a, b, ..)| a.0 * b.1 != a.1 * b.0);
+
+        property::assert_equivalent(
+            &blocks(machine, 1..=5),
+            |machines| part_one(&to_input(machines)).map(u64::from),
+            |machines| Some(min_tokens(&to_input(machines), 0)),
+        );
+    }
+
+    type MachineSpec = ((i64, i64), (i64, i64), (i64, i64), i64);
+
+    /// Describes the machines as puzzle input.
+    fn to_input(machines: &[MachineSpec]) -> String {
+        let blocks: Vec<String> = machines
+            .iter()
+            .map(|&(a, b, presses, offset)| {
+                let prize_x = a.0 * presses.0 + b.0 * presses.1 + offset;
+                let prize_y = a.1 * presses.0 + b.1 * presses.1;
+                format!(
+                    "Button A: X+{}, Y+{}\nButton B: X+{}, Y+{}\nPrize: X={prize_x}, Y={prize_y}",
+                    a.0, a.1, b.0, b.1
+                )
+            })
+            .collect();
+
+        advent_of_code::template::property::blocks_to_input(&blocks)
+    }
 }
@@ -1,89 +1,43 @@
-use std::collections::HashMap;
-
 use itertools::Itertools;
 
-advent_of_code::solution!(14);
+use advent_of_code::template::params::{Params, RawParams};
+use advent_of_code::template::picture::{self, Metric};
+use advent_of_code::template::visualize::{self, Frame, Palette, Rgb, Style};
+
+advent_of_code::solution!(14, params: Size);
 
 const DURATION: u64 = 100;
 
-#[cfg(not(test))]
-mod size {
-    pub const HEIGHT: u64 = 103;
-    pub const WIDTH: u64 = 101;
+const PALETTE: Palette = Palette::new(
+    Rgb(10, 10, 30),
+    &[
+        (b'.', Style::new('·', Rgb(40, 40, 70))),
+        (b'#', Style::new('█', Rgb(0, 200, 80))),
+    ],
+);
+
+/// Size of the room. The example uses a smaller room than the real input.
+pub struct Size {
+    width: u64,
+    height: u64,
 }
 
-#[cfg(test)]
-mod size {
-    pub const HEIGHT: u64 = 7;
-    pub const WIDTH: u64 = 11;
+impl Params for Size {
+    fn from_params(params: &RawParams) -> Result<Self, String> {
+        Ok(Self {
+            width: params.get_or("width", 101)?,
+            height: params.get_or("height", 103)?,
+        })
+    }
 }
 
-pub fn part_one(input: &str) -> Option<u32> {
-    Some(
-        input
-            .lines()
-            .map(|line| {
-                let (p, v) = line
-                    .split_whitespace()
-                    .map(|section| {
-                        section[2..]
-                            .split(",")
-                            .map(|n| n.parse::<i64>().unwrap())
-                            .collect_tuple::<(_, _)>()
-                            .unwrap()
-                    })
-                    .collect_tuple()
-                    .unwrap();
-
-                ((p.0 as u64, p.1 as u64), v)
-            })
-            .map(|(mut position, velocity)| {
-                (0..DURATION).for_each(|_| {
-                    position = (
-                        (position.0 + size::WIDTH)
-                            .checked_add_signed(velocity.0)
-                            .unwrap()
-                            % size::WIDTH,
-                        (position.1 + size::HEIGHT)
-                            .checked_add_signed(velocity.1)
-                            .unwrap()
-                            % size::HEIGHT,
-                    );
-                });
-
-                position
-            })
-            .flat_map(|(x, y)| {
-                if size::WIDTH % 2 == 1 && x == size::WIDTH / 2 {
-                    return None;
-                }
-
-                if size::HEIGHT % 2 == 1 && y == size::HEIGHT / 2 {
-                    return None;
-                }
-
-                // Determine the quadrant
-                let x_quad = (x < size::WIDTH / 2) as usize;
-                let y_quad = (y < size::HEIGHT / 2) as usize;
-
-                Some((x_quad << 1) + y_quad)
-            })
-            .fold([0; 4], |mut quads, quad| {
-                quads[quad] += 1;
-
-                quads
-            })
-            .into_iter()
-            .product::<u32>(),
-    )
-}
+type Robot = ((i64, i64), (i64, i64));
 
-pub fn part_two(input: &str) -> Option<u32> {
-    let mut items = input
+fn parse(input: &str) -> Vec<Robot> {
+    input
         .lines()
         .map(|line| {
-            let (p, v) = line
-                .split_whitespace()
+            line.split_whitespace()
                 .map(|section| {
                     section[2..]
                         .split(",")
@@ -92,67 +46,71 @@ pub fn part_two(input: &str) -> Option<u32> {
                         .unwrap()
                 })
                 .collect_tuple()
-                .unwrap();
+                .unwrap()
+        })
+        .collect()
+}
 
-            ((p.0 as u64, p.1 as u64), v)
+/// Positions of the robots after `seconds`, wrapping around the edges of the room.
+fn positions_at(robots: &[Robot], size: &Size, seconds: u64) -> Vec<(usize, usize)> {
+    let (width, height) = (size.width as i64, size.height as i64);
+    let seconds = seconds as i64;
+
+    robots
+        .iter()
+        .map(|((x, y), (vx, vy))| {
+            (
+                (x + vx * seconds).rem_euclid(width) as usize,
+                (y + vy * seconds).rem_euclid(height) as usize,
+            )
         })
-        .collect::<Vec<_>>();
-
-    for i in 1.. {
-        items = items
-            .into_iter()
-            .map(|(mut position, velocity)| {
-                position = (
-                    (position.0 + size::WIDTH)
-                        .checked_add_signed(velocity.0)
-                        .unwrap()
-                        % size::WIDTH,
-                    (position.1 + size::HEIGHT)
-                        .checked_add_signed(velocity.1)
-                        .unwrap()
-                        % size::HEIGHT,
-                );
-
-                (position, velocity)
-            })
-            .collect();
-
-        let tree = (0..size::HEIGHT)
-            .map(|y| {
-                (0..size::WIDTH)
-                    .map(|x| (x, y))
-                    .skip_while(|pos| !items.iter().any(|(p, _)| pos == p))
-                    .take_while(|pos| items.iter().any(|(p, _)| pos == p))
-                    .count()
-            })
-            .filter(|&full| full >= 5)
-            .count()
-            >= 2;
-
-        if tree {
-            let mut grid = vec![vec![false; size::WIDTH as usize]; size::HEIGHT as usize];
-
-            for ((x, y), _) in items {
-                grid[y as usize][x as usize] = true;
-            }
-
-            println!("i: {i}");
-
-            println!(
-                "{}",
-                grid.into_iter()
-                    .map(|line| line
-                        .into_iter()
-                        .map(|c| if c { '#' } else { '.' })
-                        .collect::<String>())
-                    .join("\n")
-            );
-
-            return Some(i);
-        }
+        .collect()
+}
+
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
     }
+}
 
-    None
+pub fn part_one(input: &str, size: &Size) -> Option<u32> {
+    let positions = positions_at(&parse(input), size, DURATION);
+
+    Some(picture::safety_factor(&positions, size.width as usize, size.height as usize) as u32)
+}
+
+pub fn part_two(input: &str, size: &Size) -> Option<u32> {
+    let robots = parse(input);
+    let (width, height) = (size.width as usize, size.height as usize);
+
+    // every robot is back at its start after a multiple of both the width and the height.
+    let period = size.width / gcd(size.width, size.height) * size.height;
+
+    let frame = |positions: &[(usize, usize)]| {
+        let mut frame = Frame::new(width, height, b'.', &PALETTE);
+        frame.overlay(positions.iter().copied(), b'#');
+        frame
+    };
+
+    // the robots of the picture are packed together, all other steps are noise.
+    let detection = picture::find_picture(period as usize, Metric::Variance, |step| {
+        let positions = positions_at(&robots, size, step as u64);
+        visualize::record(|| frame(&positions).with_caption(format!("{step} seconds")));
+        (positions, width, height)
+    })?;
+
+    if !detection.is_clear() {
+        return None;
+    }
+
+    visualize::record_key_frame(|| {
+        frame(&positions_at(&robots, size, detection.step as u64))
+            .with_caption(format!("{} seconds: picture found", detection.step))
+    });
+
+    Some(detection.step as u32)
 }
 
 #[cfg(test)]
@@ -161,13 +119,21 @@ mod tests {
 
     #[test]
     fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        let size = Size {
+            width: 11,
+            height: 7,
+        };
+        let result = part_one(&advent_of_code::template::read_file("examples", DAY), &size);
         assert_eq!(result, Some(12));
     }
 
-    // #[test]
-    // fn test_part_two() {
-    //     let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-    //     assert_eq!(result, None);
-    // }
+    #[test]
+    fn test_part_two() {
+        let size = Size {
+            width: 11,
+            height: 7,
+        };
+        let result = part_two(&advent_of_code::template::read_file("examples", DAY), &size);
+        assert_eq!(result, None);
+    }
 }
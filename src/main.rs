@@ -1,4 +1,6 @@
-use advent_of_code::template::commands::{all, download, read, scaffold, solve, time};
+use advent_of_code::template::commands::{
+    all, download, fuzz, read, scaffold, solve, status, time,
+};
 use args::{parse, AppArguments};
 
 #[cfg(feature = "today")]
@@ -7,8 +9,8 @@ use advent_of_code::template::Day;
 use std::process;
 
 mod args {
-    use advent_of_code::template::Day;
-    use std::process;
+    use advent_of_code::template::{fuzz, image, input::InputSource, visualize, Day};
+    use std::{process, time::Duration};
 
     pub enum AppArguments {
         Download {
@@ -16,25 +18,43 @@ mod args {
         },
         Read {
             day: Day,
+            part: Option<u8>,
+            refresh: bool,
         },
         Scaffold {
             day: Day,
             download: bool,
             overwrite: bool,
+            dry_run: bool,
+            template: Option<String>,
         },
         Solve {
             day: Day,
             release: bool,
             dhat: bool,
             submit: Option<u8>,
+            params: Vec<String>,
+            input: InputSource,
+            visualize: Option<visualize::Options>,
         },
         All {
             release: bool,
         },
+        Status {
+            test: bool,
+            answers: bool,
+        },
+        Fuzz {
+            day: Day,
+            config: fuzz::Config,
+            params: Vec<String>,
+        },
         Time {
             all: bool,
             day: Option<Day>,
             store: bool,
+            memory: bool,
+            counters: bool,
         },
         #[cfg(feature = "today")]
         Today,
@@ -47,33 +67,149 @@ mod args {
             Some("all") => AppArguments::All {
                 release: args.contains("--release"),
             },
+            Some("status") => AppArguments::Status {
+                test: args.contains("--test"),
+                answers: args.contains("--answers"),
+            },
+            Some("fuzz") => {
+                let defaults = fuzz::Config::default();
+                let config = fuzz::Config {
+                    cases: args
+                        .opt_value_from_str("--cases")?
+                        .unwrap_or(defaults.cases),
+                    seed: args.opt_value_from_str("--seed")?,
+                    timeout: args
+                        .opt_value_from_fn("--timeout", |s| {
+                            s.parse::<f64>()
+                                .ok()
+                                .filter(|seconds| *seconds > 0.0)
+                                .map(Duration::from_secs_f64)
+                                .ok_or("expected a positive number of seconds")
+                        })?
+                        .unwrap_or(defaults.timeout),
+                    puzzle_input: args.contains("--puzzle-input"),
+                };
+                let params = args.values_from_str("--param")?;
+
+                AppArguments::Fuzz {
+                    day: args.free_from_str()?,
+                    config,
+                    params,
+                }
+            }
             Some("time") => {
                 let all = args.contains("--all");
                 let store = args.contains("--store");
+                let memory = args.contains("--memory");
+                let counters = args.contains("--counters");
 
                 AppArguments::Time {
                     all,
                     day: args.opt_free_from_str()?,
                     store,
+                    memory,
+                    counters,
                 }
             }
             Some("download") => AppArguments::Download {
                 day: args.free_from_str()?,
             },
-            Some("read") => AppArguments::Read {
-                day: args.free_from_str()?,
-            },
-            Some("scaffold") => AppArguments::Scaffold {
-                day: args.free_from_str()?,
-                download: args.contains("--download"),
-                overwrite: args.contains("--overwrite"),
-            },
-            Some("solve") => AppArguments::Solve {
-                day: args.free_from_str()?,
-                release: args.contains("--release"),
-                submit: args.opt_value_from_str("--submit")?,
-                dhat: args.contains("--dhat"),
-            },
+            Some("read") => {
+                let part = args.opt_value_from_str("--part")?;
+                let refresh = args.contains("--refresh");
+
+                AppArguments::Read {
+                    day: args.free_from_str()?,
+                    part,
+                    refresh,
+                }
+            }
+            Some("scaffold") => {
+                let download = args.contains("--download");
+                let overwrite = args.contains("--overwrite");
+                let dry_run = args.contains("--dry-run");
+                let template = args.opt_value_from_str("--template")?;
+
+                AppArguments::Scaffold {
+                    day: args.free_from_str()?,
+                    download,
+                    overwrite,
+                    dry_run,
+                    template,
+                }
+            }
+            Some("solve") => {
+                let day = args.free_from_str()?;
+                let release = args.contains("--release");
+                let submit = args.opt_value_from_str("--submit")?;
+                let dhat = args.contains("--dhat");
+                let params = args.values_from_str("--param")?;
+                let visualize = args.contains("--visualize");
+                let fps = args.opt_value_from_fn("--fps", |s| {
+                    s.parse::<f64>()
+                        .ok()
+                        .filter(|fps| *fps > 0.0)
+                        .ok_or("expected a positive number of frames per second")
+                })?;
+                let render = args.opt_value_from_str("--render")?;
+                let scale = args.opt_value_from_fn("--scale", |s| {
+                    s.parse::<usize>()
+                        .ok()
+                        .filter(|scale| *scale > 0)
+                        .ok_or("expected a positive number of pixels per cell")
+                })?;
+
+                let mut sources = vec![];
+                if let Some(path) = args.opt_value_from_str("--input")? {
+                    sources.push(InputSource::File(path));
+                }
+                if args.contains("--stdin") {
+                    sources.push(InputSource::Stdin);
+                }
+                // parsed last, so the optional example number is the only remaining free argument.
+                if args.contains("--example") {
+                    let number = args.opt_free_from_str::<usize>()?.unwrap_or(1);
+                    if number == 0 {
+                        return Err("example numbers start at 1.".into());
+                    }
+                    sources.push(InputSource::Example(number));
+                }
+
+                let input = match sources.len() {
+                    0 => InputSource::Puzzle,
+                    1 => sources.remove(0),
+                    _ => {
+                        return Err("only one of --input, --stdin and --example can be used.".into())
+                    }
+                };
+
+                if submit.is_some() && !input.is_puzzle() {
+                    return Err("--submit can only be used with the puzzle input.".into());
+                }
+
+                if fps.is_some() && !visualize && render.is_none() {
+                    return Err("--fps can only be used with --visualize or --render.".into());
+                }
+
+                if scale.is_some() && render.is_none() {
+                    return Err("--scale can only be used with --render.".into());
+                }
+
+                AppArguments::Solve {
+                    day,
+                    release,
+                    dhat,
+                    submit,
+                    params,
+                    input,
+                    visualize: (visualize || render.is_some()).then(|| visualize::Options {
+                        play: visualize,
+                        render,
+                        scale: scale.unwrap_or(image::DEFAULT_SCALE),
+                        fps: fps.unwrap_or(visualize::DEFAULT_FPS),
+                    }),
+                }
+            }
             #[cfg(feature = "today")]
             Some("today") => AppArguments::Today,
             Some(x) => {
@@ -103,32 +239,51 @@ fn main() {
         }
         Ok(args) => match args {
             AppArguments::All { release } => all::handle(release),
-            AppArguments::Time { day, all, store } => time::handle(day, all, store),
+            AppArguments::Time {
+                day,
+                all,
+                store,
+                memory,
+                counters,
+            } => time::handle(day, all, store, memory, counters),
+            AppArguments::Status { test, answers } => status::handle(test, answers),
+            AppArguments::Fuzz {
+                day,
+                config,
+                params,
+            } => fuzz::handle(day, &config, &params),
             AppArguments::Download { day } => download::handle(day),
-            AppArguments::Read { day } => read::handle(day),
+            AppArguments::Read { day, part, refresh } => read::handle(day, part, refresh),
             AppArguments::Scaffold {
                 day,
                 download,
                 overwrite,
-            } => {
-                scaffold::handle(day, overwrite);
-                if download {
-                    download::handle(day);
-                }
-            }
+                dry_run,
+                template,
+            } => scaffold::handle(day, overwrite, dry_run, download, template.as_deref()),
             AppArguments::Solve {
                 day,
                 release,
                 dhat,
                 submit,
-            } => solve::handle(day, release, dhat, submit),
+                params,
+                input,
+                visualize,
+            } => solve::handle(
+                day,
+                release,
+                dhat,
+                submit,
+                &params,
+                &input,
+                visualize.as_ref(),
+            ),
             #[cfg(feature = "today")]
             AppArguments::Today => {
                 match Day::today() {
                     Some(day) => {
-                        scaffold::handle(day, false);
-                        download::handle(day);
-                        read::handle(day)
+                        scaffold::handle(day, false, false, true, None);
+                        read::handle(day, None, false)
                     }
                     None => {
                         eprintln!(
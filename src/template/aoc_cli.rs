@@ -1,10 +1,11 @@
 /// Wrapper module around the "aoc-cli" command-line.
 use std::{
     fmt::Display,
+    path::Path,
     process::{Command, Output, Stdio},
 };
 
-use crate::template::Day;
+use crate::template::{encryption, Day};
 
 #[derive(Debug)]
 pub enum AocCommandError {
@@ -25,8 +26,13 @@ impl Display for AocCommandError {
     }
 }
 
+/// The aoc-cli executable. Can be overridden with `AOC_CLI`, e.g. to test against a mock.
+fn get_command() -> String {
+    std::env::var("AOC_CLI").unwrap_or_else(|_| "aoc".into())
+}
+
 pub fn check() -> Result<(), AocCommandError> {
-    Command::new("aoc")
+    Command::new(get_command())
         .arg("-V")
         .output()
         .map_err(|_| AocCommandError::CommandNotFound)?;
@@ -69,6 +75,38 @@ pub fn download(day: Day) -> Result<Output, AocCommandError> {
     println!("---");
     println!("🎄 Successfully wrote input to \"{}\".", &input_path);
     println!("🎄 Successfully wrote puzzle to \"{}\".", &puzzle_path);
+
+    match encryption::store_encrypted(Path::new(&input_path)) {
+        Ok(Some(encrypted_path)) => {
+            println!(
+                "🎄 Successfully wrote encrypted input to \"{}\".",
+                encrypted_path.display()
+            );
+        }
+        Ok(None) => {}
+        Err(e) => eprintln!("Failed to encrypt input: {e}"),
+    }
+
+    Ok(output)
+}
+
+/// Downloads the puzzle description only, e.g. to pick up part two once it is unlocked.
+pub fn download_puzzle(day: Day) -> Result<Output, AocCommandError> {
+    let puzzle_path = get_puzzle_path(day);
+
+    let args = build_args(
+        "download",
+        &[
+            "--overwrite".into(),
+            "--puzzle-only".into(),
+            "--puzzle-file".into(),
+            puzzle_path.to_string(),
+        ],
+        day,
+    );
+
+    let output = call_aoc_cli(&args)?;
+    println!("🎄 Successfully wrote puzzle to \"{}\".", &puzzle_path);
     Ok(output)
 }
 
@@ -77,18 +115,19 @@ pub fn submit(day: Day, part: u8, result: &str) -> Result<Output, AocCommandErro
     let mut args = build_args("submit", &[], day);
     args.push(part.to_string());
     args.push(result.to_string());
-    call_aoc_cli(&args)
+    // the output is captured so the runner can record the verdict.
+    call_aoc_cli_captured(&args)
 }
 
 fn get_input_path(day: Day) -> String {
     format!("data/inputs/{day}.txt")
 }
 
-fn get_puzzle_path(day: Day) -> String {
+pub fn get_puzzle_path(day: Day) -> String {
     format!("data/puzzles/{day}.md")
 }
 
-fn get_year() -> Option<u16> {
+pub fn get_year() -> Option<u16> {
     match std::env::var("AOC_YEAR") {
         Ok(x) => x.parse().ok().or(None),
         Err(_) => None,
@@ -110,7 +149,7 @@ fn build_args(command: &str, args: &[String], day: Day) -> Vec<String> {
 
 fn call_aoc_cli(args: &[String]) -> Result<Output, AocCommandError> {
     // println!("Calling >aoc with: {}", args.join(" "));
-    let output = Command::new("aoc")
+    let output = Command::new(get_command())
         .args(args)
         .stdout(Stdio::inherit())
         .stderr(Stdio::inherit())
@@ -123,3 +162,21 @@ fn call_aoc_cli(args: &[String]) -> Result<Output, AocCommandError> {
         Err(AocCommandError::BadExitStatus(output))
     }
 }
+
+/// Like [`call_aoc_cli`], but captures stdout and echoes it once the command exits.
+fn call_aoc_cli_captured(args: &[String]) -> Result<Output, AocCommandError> {
+    let output = Command::new(get_command())
+        .args(args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .output()
+        .map_err(|_| AocCommandError::CommandNotCallable)?;
+
+    print!("{}", String::from_utf8_lossy(&output.stdout));
+
+    if output.status.success() {
+        Ok(output)
+    } else {
+        Err(AocCommandError::BadExitStatus(output))
+    }
+}
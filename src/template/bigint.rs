@@ -0,0 +1,468 @@
+/// Arbitrary-precision unsigned integers, for answers that overflow `u128` and for exact digit
+/// manipulation without going through floating point.
+///
+/// Numbers are stored as base 10^9 limbs, so parsing, printing and decimal digit operations are
+/// cheap. Arithmetic is schoolbook, which is plenty for puzzle-sized numbers:
+///
+/// ```ignore
+/// use advent_of_code::template::bigint::BigUint;
+///
+/// let total: BigUint = numbers.iter().map(|n| BigUint::from(*n)).product();
+/// let (high, low) = total.split_digits(3);
+/// ```
+use std::{
+    cmp::Ordering,
+    fmt::{Debug, Display},
+    iter::{Product, Sum},
+    ops::{Add, Div, Mul, Rem, Sub},
+    str::FromStr,
+};
+
+const BASE: u64 = 1_000_000_000;
+const BASE_DIGITS: u32 = 9;
+
+/// An unsigned integer of any size.
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+pub struct BigUint {
+    /// Least significant limb first, without trailing zero limbs. Zero has no limbs.
+    limbs: Vec<u32>,
+}
+
+impl BigUint {
+    pub fn zero() -> Self {
+        Self::default()
+    }
+
+    pub fn one() -> Self {
+        Self::from(1u64)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    fn from_limbs(mut limbs: Vec<u32>) -> Self {
+        while limbs.last() == Some(&0) {
+            limbs.pop();
+        }
+        Self { limbs }
+    }
+
+    /// `10^exponent`.
+    pub fn pow10(exponent: u32) -> Self {
+        let mut limbs = vec![0; (exponent / BASE_DIGITS) as usize];
+        limbs.push(10u32.pow(exponent % BASE_DIGITS));
+        Self::from_limbs(limbs)
+    }
+
+    pub fn pow(&self, mut exponent: u32) -> Self {
+        let mut result = Self::one();
+        let mut base = self.clone();
+
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result = &result * &base;
+            }
+            base = &base * &base;
+            exponent >>= 1;
+        }
+
+        result
+    }
+
+    /// The number of decimal digits, 1 for zero.
+    pub fn digits(&self) -> u32 {
+        match self.limbs.last() {
+            None => 1,
+            Some(last) => {
+                (self.limbs.len() as u32 - 1) * BASE_DIGITS + last.checked_ilog10().unwrap_or(0) + 1
+            }
+        }
+    }
+
+    /// Splits off the lowest `count` decimal digits: `(self / 10^count, self % 10^count)`.
+    pub fn split_digits(&self, count: u32) -> (Self, Self) {
+        self.div_rem(&Self::pow10(count))
+    }
+
+    /// Appends the decimal digits of `other`, e.g. `12 || 345 = 12345`.
+    pub fn concat(&self, other: &Self) -> Self {
+        &(self * &Self::pow10(other.digits())) + other
+    }
+
+    pub fn checked_sub(&self, other: &Self) -> Option<Self> {
+        if self < other {
+            return None;
+        }
+
+        let mut limbs = Vec::with_capacity(self.limbs.len());
+        let mut borrow = 0i64;
+        for (i, &limb) in self.limbs.iter().enumerate() {
+            let mut value = i64::from(limb) - borrow - i64::from(*other.limbs.get(i).unwrap_or(&0));
+            borrow = i64::from(value < 0);
+            if value < 0 {
+                value += BASE as i64;
+            }
+            limbs.push(value as u32);
+        }
+
+        Some(Self::from_limbs(limbs))
+    }
+
+    fn mul_small(&self, factor: u32) -> Self {
+        let mut limbs = Vec::with_capacity(self.limbs.len() + 1);
+        let mut carry = 0u64;
+        for &limb in &self.limbs {
+            let value = u64::from(limb) * u64::from(factor) + carry;
+            limbs.push((value % BASE) as u32);
+            carry = value / BASE;
+        }
+        limbs.push(carry as u32);
+        Self::from_limbs(limbs)
+    }
+
+    /// The quotient and remainder. Panics if `divisor` is zero.
+    pub fn div_rem(&self, divisor: &Self) -> (Self, Self) {
+        assert!(!divisor.is_zero(), "attempt to divide by zero");
+
+        if let [single] = divisor.limbs[..] {
+            let mut quotient = vec![0; self.limbs.len()];
+            let mut remainder = 0u64;
+            for (i, &limb) in self.limbs.iter().enumerate().rev() {
+                let value = remainder * BASE + u64::from(limb);
+                quotient[i] = (value / u64::from(single)) as u32;
+                remainder = value % u64::from(single);
+            }
+            return (Self::from_limbs(quotient), Self::from(remainder));
+        }
+
+        // long division, finding each quotient limb with a binary search.
+        let mut quotient = vec![0; self.limbs.len()];
+        let mut remainder = Self::zero();
+        for (i, &limb) in self.limbs.iter().enumerate().rev() {
+            remainder.limbs.insert(0, limb);
+            remainder = Self::from_limbs(remainder.limbs);
+
+            let (mut low, mut high) = (0u32, BASE as u32 - 1);
+            while low < high {
+                let mid = low + (high - low).div_ceil(2);
+                if divisor.mul_small(mid) <= remainder {
+                    low = mid;
+                } else {
+                    high = mid - 1;
+                }
+            }
+
+            quotient[i] = low;
+            remainder = remainder.checked_sub(&divisor.mul_small(low)).unwrap();
+        }
+
+        (Self::from_limbs(quotient), remainder)
+    }
+
+    pub fn is_multiple_of(&self, other: &Self) -> bool {
+        if other.is_zero() {
+            return self.is_zero();
+        }
+        self.div_rem(other).1.is_zero()
+    }
+
+    pub fn to_u128(&self) -> Option<u128> {
+        self.limbs.iter().rev().try_fold(0u128, |value, &limb| {
+            value
+                .checked_mul(u128::from(BASE))?
+                .checked_add(u128::from(limb))
+        })
+    }
+}
+
+impl From<u64> for BigUint {
+    fn from(value: u64) -> Self {
+        Self::from(u128::from(value))
+    }
+}
+
+impl From<u128> for BigUint {
+    fn from(mut value: u128) -> Self {
+        let mut limbs = vec![];
+        while value > 0 {
+            limbs.push((value % u128::from(BASE)) as u32);
+            value /= u128::from(BASE);
+        }
+        Self { limbs }
+    }
+}
+
+impl FromStr for BigUint {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(format!("invalid number `{s}`."));
+        }
+
+        let limbs = s
+            .as_bytes()
+            .rchunks(BASE_DIGITS as usize)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .fold(0u32, |limb, digit| limb * 10 + u32::from(digit - b'0'))
+            })
+            .collect();
+
+        Ok(Self::from_limbs(limbs))
+    }
+}
+
+impl Display for BigUint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Some((last, rest)) = self.limbs.split_last() else {
+            return f.pad_integral(true, "", "0");
+        };
+
+        let mut digits = last.to_string();
+        for limb in rest.iter().rev() {
+            digits.push_str(&format!("{limb:09}"));
+        }
+        f.pad_integral(true, "", &digits)
+    }
+}
+
+impl Debug for BigUint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.limbs
+            .len()
+            .cmp(&other.limbs.len())
+            .then_with(|| self.limbs.iter().rev().cmp(other.limbs.iter().rev()))
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Add for &BigUint {
+    type Output = BigUint;
+
+    fn add(self, other: &BigUint) -> BigUint {
+        let len = self.limbs.len().max(other.limbs.len());
+        let mut limbs = Vec::with_capacity(len + 1);
+        let mut carry = 0u64;
+        for i in 0..len {
+            let value = u64::from(*self.limbs.get(i).unwrap_or(&0))
+                + u64::from(*other.limbs.get(i).unwrap_or(&0))
+                + carry;
+            limbs.push((value % BASE) as u32);
+            carry = value / BASE;
+        }
+        limbs.push(carry as u32);
+        BigUint::from_limbs(limbs)
+    }
+}
+
+impl Sub for &BigUint {
+    type Output = BigUint;
+
+    fn sub(self, other: &BigUint) -> BigUint {
+        self.checked_sub(other)
+            .expect("attempt to subtract with overflow")
+    }
+}
+
+impl Mul for &BigUint {
+    type Output = BigUint;
+
+    fn mul(self, other: &BigUint) -> BigUint {
+        if self.is_zero() || other.is_zero() {
+            return BigUint::zero();
+        }
+
+        let mut limbs = vec![0u64; self.limbs.len() + other.limbs.len()];
+        for (i, &a) in self.limbs.iter().enumerate() {
+            let mut carry = 0u64;
+            for (j, &b) in other.limbs.iter().enumerate() {
+                let value = limbs[i + j] + u64::from(a) * u64::from(b) + carry;
+                limbs[i + j] = value % BASE;
+                carry = value / BASE;
+            }
+            limbs[i + other.limbs.len()] += carry;
+        }
+
+        BigUint::from_limbs(limbs.into_iter().map(|limb| limb as u32).collect())
+    }
+}
+
+impl Div for &BigUint {
+    type Output = BigUint;
+
+    fn div(self, other: &BigUint) -> BigUint {
+        self.div_rem(other).0
+    }
+}
+
+impl Rem for &BigUint {
+    type Output = BigUint;
+
+    fn rem(self, other: &BigUint) -> BigUint {
+        self.div_rem(other).1
+    }
+}
+
+/// Forwards the operators on owned values to the ones on references.
+macro_rules! forward_ops {
+    ($($op:ident::$method:ident),*) => {
+        $(
+            impl $op for BigUint {
+                type Output = BigUint;
+
+                fn $method(self, other: BigUint) -> BigUint {
+                    (&self).$method(&other)
+                }
+            }
+
+            impl $op<&BigUint> for BigUint {
+                type Output = BigUint;
+
+                fn $method(self, other: &BigUint) -> BigUint {
+                    (&self).$method(other)
+                }
+            }
+        )*
+    };
+}
+
+forward_ops!(Add::add, Sub::sub, Mul::mul, Div::div, Rem::rem);
+
+impl Sum for BigUint {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::zero(), |total, n| total + n)
+    }
+}
+
+impl Product for BigUint {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::one(), |total, n| total * n)
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::BigUint;
+
+    fn big(s: &str) -> BigUint {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn parses_and_displays() {
+        for s in ["0", "7", "1000000000", "123456789012345678901234567890"] {
+            assert_eq!(big(s).to_string(), s);
+        }
+        assert_eq!(big("000123").to_string(), "123");
+        assert_eq!(format!("{:>6}", big("42")), "    42");
+        assert!("12a".parse::<BigUint>().is_err());
+        assert!("".parse::<BigUint>().is_err());
+
+        assert_eq!(BigUint::from(u128::MAX).to_string(), u128::MAX.to_string());
+        assert_eq!(BigUint::from(u128::MAX).to_u128(), Some(u128::MAX));
+        assert_eq!((BigUint::from(u128::MAX) + BigUint::one()).to_u128(), None);
+    }
+
+    #[test]
+    fn computes_arithmetic() {
+        let a = big("340282366920938463463374607431768211455");
+        let b = big("98765432109876543210");
+
+        assert_eq!(
+            (&a + &b).to_string(),
+            "340282366920938463562140039541644754665"
+        );
+        assert_eq!(
+            (&a - &b).to_string(),
+            "340282366920938463364609175321891668245"
+        );
+        assert_eq!(b.checked_sub(&a), None);
+        assert_eq!(
+            (&a * &b).to_string(),
+            "33608135008318047381965979015313484234113890572639724470550"
+        );
+
+        let (quotient, remainder) = a.div_rem(&b);
+        assert_eq!(quotient.to_string(), "3445358964686899059");
+        assert_eq!(remainder.to_string(), "3465190854646372065");
+        assert_eq!(&(&quotient * &b) + &remainder, a);
+        assert_eq!(
+            (&a / &big("7")).to_string(),
+            "48611766702991209066196372490252601636"
+        );
+        assert_eq!((&a % &big("7")).to_string(), "3");
+
+        assert_eq!(
+            big("2").pow(200).to_string(),
+            (0..200).map(|_| big("2")).product::<BigUint>().to_string()
+        );
+        assert_eq!(
+            [big("1"), big("2"), big("3")].into_iter().sum::<BigUint>(),
+            big("6")
+        );
+        assert!(big("0") < big("1") && big("999999999") < big("1000000000"));
+    }
+
+    #[test]
+    fn matches_u128_arithmetic() {
+        let mut state = 0x2545_f491_4f6c_dd1du128;
+        let mut next = || {
+            state = state.wrapping_mul(0x5851_f42d_4c95_7f2d_1405_7b7e_f767_814f) + 1;
+            // vary the magnitude, so that divisors with one and several limbs are covered.
+            state >> (state % 120)
+        };
+
+        for _ in 0..500 {
+            let (a, b) = (next(), next().max(1));
+            let (big_a, big_b) = (BigUint::from(a), BigUint::from(b));
+
+            if let Some(sum) = a.checked_add(b) {
+                assert_eq!(&big_a + &big_b, BigUint::from(sum));
+            }
+            if let Some(product) = a.checked_mul(b) {
+                assert_eq!(&big_a * &big_b, BigUint::from(product));
+            }
+            assert_eq!(
+                big_a.checked_sub(&big_b),
+                a.checked_sub(b).map(BigUint::from)
+            );
+            assert_eq!(
+                big_a.div_rem(&big_b),
+                (BigUint::from(a / b), BigUint::from(a % b))
+            );
+            assert_eq!(big_a.digits(), a.checked_ilog10().unwrap_or(0) + 1);
+        }
+    }
+
+    #[test]
+    fn manipulates_digits() {
+        assert_eq!(big("0").digits(), 1);
+        assert_eq!(big("999999999").digits(), 9);
+        assert_eq!(big("1000000000").digits(), 10);
+        assert_eq!(big("999999999999999999").digits(), 18);
+
+        assert_eq!(BigUint::pow10(12).to_string(), "1000000000000");
+        assert_eq!(big("12").concat(&big("345")), big("12345"));
+        assert_eq!(big("12").concat(&big("0")), big("120"));
+        assert_eq!(
+            big("123456789012").split_digits(10),
+            (big("12"), big("3456789012"))
+        );
+        assert!(big("1000000000000000000000").is_multiple_of(&big("1000")));
+        assert!(!big("1001").is_multiple_of(&big("1000")));
+    }
+}
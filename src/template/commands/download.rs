@@ -2,13 +2,18 @@ use crate::template::{aoc_cli, Day};
 use std::process;
 
 pub fn handle(day: Day) {
-    if aoc_cli::check().is_err() {
-        eprintln!("command \"aoc\" not found or not callable. Try running \"cargo install aoc-cli\" to install it.");
+    if let Err(e) = try_handle(day) {
+        eprintln!("{e}");
         process::exit(1);
     }
+}
 
-    if let Err(e) = aoc_cli::download(day) {
-        eprintln!("failed to call aoc-cli: {e}");
-        process::exit(1);
-    };
+/// Downloads input and puzzle description, returning the error instead of exiting.
+pub fn try_handle(day: Day) -> Result<(), String> {
+    if aoc_cli::check().is_err() {
+        return Err("command \"aoc\" not found or not callable. Try running \"cargo install aoc-cli\" to install it.".into());
+    }
+
+    aoc_cli::download(day).map_err(|e| format!("failed to call aoc-cli: {e}"))?;
+    Ok(())
 }
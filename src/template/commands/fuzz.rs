@@ -0,0 +1,54 @@
+use std::{
+    fs,
+    process::{self, Command, Stdio},
+};
+
+use crate::template::{
+    fuzz::{get_current_case_path, get_fuzz_dir, Config},
+    Day,
+};
+
+/// Fuzzes a solution in a debug build, so arithmetic overflows are detected.
+pub fn handle(day: Day, config: &Config, params: &[String]) {
+    let mut cmd_args = vec![
+        "run".to_string(),
+        "--quiet".to_string(),
+        "--bin".to_string(),
+        day.to_string(),
+        "--".to_string(),
+        "--fuzz".to_string(),
+    ];
+
+    cmd_args.extend(config.to_args());
+
+    for param in params {
+        cmd_args.push("--param".to_string());
+        cmd_args.push(param.clone());
+    }
+
+    let status = Command::new("cargo")
+        .args(&cmd_args)
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .status()
+        .unwrap();
+
+    // the solution removes the current input when it finishes. if it is still there,
+    // the process was aborted while running it, e.g. by a stack overflow.
+    let current = get_current_case_path(day);
+    if current.exists() {
+        let path = get_fuzz_dir(day).join("abort.txt");
+        match fs::rename(&current, &path) {
+            Ok(()) => eprintln!(
+                "The solution aborted while running \"{}\" (e.g. a stack overflow).",
+                path.display()
+            ),
+            Err(e) => eprintln!("The solution aborted, the input could not be saved: {e}"),
+        }
+        process::exit(1);
+    }
+
+    if !status.success() {
+        process::exit(status.code().unwrap_or(1));
+    }
+}
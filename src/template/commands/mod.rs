@@ -1,6 +1,8 @@
 pub mod all;
 pub mod download;
+pub mod fuzz;
 pub mod read;
 pub mod scaffold;
 pub mod solve;
+pub mod status;
 pub mod time;
@@ -1,15 +1,55 @@
-use std::process;
+use std::{fs, process};
 
-use crate::template::{aoc_cli, Day};
+use crate::template::{aoc_cli, markdown, puzzle, Day};
 
-pub fn handle(day: Day) {
+fn read_local(day: Day) -> Option<String> {
+    fs::read_to_string(aoc_cli::get_puzzle_path(day)).ok()
+}
+
+fn fetch(day: Day) -> Option<String> {
     if aoc_cli::check().is_err() {
         eprintln!("command \"aoc\" not found or not callable. Try running \"cargo install aoc-cli\" to install it.");
         process::exit(1);
     }
 
-    if let Err(e) = aoc_cli::read(day) {
+    if let Err(e) = aoc_cli::download_puzzle(day) {
         eprintln!("failed to call aoc-cli: {e}");
         process::exit(1);
     };
+
+    read_local(day)
+}
+
+/// Renders the puzzle description saved in `data/puzzles`. The description is only fetched if it
+/// is missing, if `refresh` is set, or if part two was requested but is not part of the saved copy.
+pub fn handle(day: Day, part: Option<u8>, refresh: bool) {
+    if part.is_some_and(|part| !(1..=2).contains(&part)) {
+        eprintln!("`--part` must be 1 or 2.");
+        process::exit(1);
+    }
+
+    let description = match read_local(day) {
+        Some(description) if !refresh => description,
+        _ => fetch(day).unwrap_or_default(),
+    };
+
+    let description = match part {
+        Some(2) if puzzle::part(&description, 2).is_none() && !refresh => {
+            fetch(day).unwrap_or_default()
+        }
+        _ => description,
+    };
+
+    let text = match part {
+        Some(part) => match puzzle::part(&description, part) {
+            Some(text) => text,
+            None => {
+                eprintln!("Part {part} of day {day} is not unlocked yet.");
+                process::exit(1);
+            }
+        },
+        None => &description,
+    };
+
+    print!("{}", markdown::render(text, markdown::terminal_width()));
 }
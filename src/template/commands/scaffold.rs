@@ -1,79 +1,477 @@
 use std::{
-    fs::{File, OpenOptions},
+    fs::{self, OpenOptions},
     io::Write,
+    path::{Path, PathBuf},
     process,
 };
 
-use crate::template::Day;
+use crate::template::{aoc_cli, puzzle, Day};
 
-const MODULE_TEMPLATE: &str =
-    include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/src/template.txt"));
+/// Templates that ship with the repository, by name.
+/// Templates in the `./templates` directory take precedence over these.
+const BUILTIN_TEMPLATES: [(&str, &str); 5] = [
+    (
+        "plain",
+        include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/src/template.txt")),
+    ),
+    (
+        "parse",
+        include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/src/template/scaffolds/parse.txt"
+        )),
+    ),
+    (
+        "grid",
+        include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/src/template/scaffolds/grid.txt"
+        )),
+    ),
+    (
+        "result",
+        include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/src/template/scaffolds/result.txt"
+        )),
+    ),
+    (
+        "single",
+        include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/src/template/scaffolds/single.txt"
+        )),
+    ),
+];
 
-fn safe_create_file(path: &str, overwrite: bool) -> Result<File, std::io::Error> {
-    let mut file = OpenOptions::new();
-    if overwrite {
-        file.create(true);
+const USER_TEMPLATE_DIR: &str = "templates";
+
+/// Day 25 only has a single part.
+fn default_template(day: Day) -> &'static str {
+    if day.into_inner() == 25 {
+        "single"
     } else {
-        file.create_new(true);
+        "plain"
+    }
+}
+
+fn get_user_template_path(name: &str) -> String {
+    format!("{USER_TEMPLATE_DIR}/{name}.txt")
+}
+
+/// Names of all built-in and user templates, sorted.
+fn available_templates() -> Vec<String> {
+    let mut names: Vec<String> = BUILTIN_TEMPLATES
+        .iter()
+        .map(|(name, _)| (*name).to_string())
+        .collect();
+
+    if let Ok(entries) = fs::read_dir(USER_TEMPLATE_DIR) {
+        names.extend(entries.filter_map(|entry| {
+            let path = entry.ok()?.path();
+            if path.extension()? != "txt" {
+                return None;
+            }
+            Some(path.file_stem()?.to_str()?.to_string())
+        }));
+    }
+
+    names.sort_unstable();
+    names.dedup();
+    names
+}
+
+fn load_template(name: &str) -> Result<String, String> {
+    let user_path = get_user_template_path(name);
+
+    match fs::read_to_string(&user_path) {
+        Ok(template) => return Ok(template),
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+            return Err(format!("could not read template \"{user_path}\": {e}"));
+        }
+        Err(_) => {}
     }
-    file.truncate(true).write(true).open(path)
+
+    BUILTIN_TEMPLATES
+        .iter()
+        .find(|(builtin, _)| *builtin == name)
+        .map(|(_, template)| (*template).to_string())
+        .ok_or(format!(
+            "unknown template `{name}`. Available templates: {}.",
+            available_templates().join(", ")
+        ))
 }
 
-fn create_file(path: &str) -> Result<File, std::io::Error> {
-    OpenOptions::new()
-        .write(true)
-        .create(true)
-        .truncate(true)
-        .open(path)
+fn render_answer(answer: Option<u64>) -> String {
+    match answer {
+        Some(answer) => format!("Some({answer}) /* guessed from the puzzle, please review */"),
+        None => "None".into(),
+    }
 }
 
-pub fn handle(day: Day, overwrite: bool) {
-    let input_path = format!("data/inputs/{day}.txt");
-    let example_path = format!("data/examples/{day}.txt");
-    let module_path = format!("src/bin/{day}.rs");
+/// Replaces the placeholders of a template:
+///  - `%DAY_NUMBER%`: the day without padding, e.g. `1`.
+///  - `%DAY%`: the day padded to two digits, e.g. `01`.
+///  - `%YEAR%`: the value of `AOC_YEAR`.
+///  - `%DATE%`: the release date of the puzzle, e.g. `2024-12-01`.
+///  - `%TITLE%`: the puzzle title if the description was downloaded, `Day N` otherwise.
+///  - `%PART_ONE_ANSWER%`, `%PART_TWO_ANSWER%`: the example answers guessed from the description, `None` otherwise.
+fn render(
+    template: &str,
+    day: Day,
+    year: Option<u16>,
+    description: Option<&str>,
+) -> Result<String, String> {
+    let uses_year = template.contains("%YEAR%") || template.contains("%DATE%");
 
-    let mut file = match safe_create_file(&module_path, overwrite) {
-        Ok(file) => file,
-        Err(e) => {
-            eprintln!("Failed to create module file: {e}");
-            process::exit(1);
+    let year = match year {
+        Some(year) => year.to_string(),
+        None if uses_year => {
+            return Err("template uses %YEAR% or %DATE%, but `AOC_YEAR` is not set.".into())
         }
+        None => String::new(),
     };
 
-    match file.write_all(
-        MODULE_TEMPLATE
-            .replace("%DAY_NUMBER%", &day.into_inner().to_string())
-            .as_bytes(),
-    ) {
-        Ok(()) => {
-            println!("Created module file \"{}\"", &module_path);
+    let title = description
+        .and_then(puzzle::title)
+        .unwrap_or_else(|| format!("Day {}", day.into_inner()));
+
+    let guess = description.map(puzzle::guess).unwrap_or_default();
+
+    Ok(template
+        .replace("%DAY_NUMBER%", &day.into_inner().to_string())
+        .replace("%DAY%", &day.to_string())
+        .replace("%YEAR%", &year)
+        .replace("%DATE%", &format!("{year}-12-{day}"))
+        .replace("%TITLE%", &title)
+        .replace("%PART_ONE_ANSWER%", &render_answer(guess.part_one))
+        .replace("%PART_TWO_ANSWER%", &render_answer(guess.part_two)))
+}
+
+/// What scaffolding does with a single file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Action {
+    Create,
+    Overwrite,
+    Keep,
+}
+
+/// A planned file operation. All operations are planned before the first file is written.
+#[derive(Debug)]
+struct Operation {
+    kind: &'static str,
+    path: PathBuf,
+    contents: String,
+    action: Action,
+    note: Option<&'static str>,
+}
+
+fn check_module(path: &Path, overwrite: bool) -> Result<(), String> {
+    if path.exists() && !overwrite {
+        return Err(format!(
+            "module file \"{}\" already exists. Use `--overwrite` to replace it.",
+            path.display()
+        ));
+    }
+    Ok(())
+}
+
+impl Operation {
+    /// An existing module is only replaced with `--overwrite`.
+    fn module(path: PathBuf, contents: String, overwrite: bool) -> Result<Self, String> {
+        check_module(&path, overwrite)?;
+
+        Ok(Self {
+            kind: "module",
+            action: if path.exists() {
+                Action::Overwrite
+            } else {
+                Action::Create
+            },
+            path,
+            contents,
+            note: None,
+        })
+    }
+
+    /// Data files with contents are never touched, empty files may be filled.
+    fn data(kind: &'static str, path: PathBuf, contents: String) -> Self {
+        let action = match fs::read_to_string(&path).ok().as_deref() {
+            None => Action::Create,
+            Some("") if !contents.is_empty() => Action::Overwrite,
+            Some(_) => Action::Keep,
+        };
+
+        Self {
+            kind,
+            path,
+            contents,
+            action,
+            note: None,
         }
-        Err(e) => {
-            eprintln!("Failed to write module contents: {e}");
-            process::exit(1);
+    }
+
+    #[must_use]
+    fn with_note(mut self, note: &'static str) -> Self {
+        if self.action != Action::Keep {
+            self.note = Some(note);
         }
+        self
     }
 
-    match create_file(&input_path) {
-        Ok(_) => {
-            println!("Created empty input file \"{}\"", &input_path);
+    fn describe(&self, dry_run: bool) -> String {
+        let verb = match (self.action, dry_run) {
+            (Action::Create, false) => "Created",
+            (Action::Create, true) => "Would create",
+            (Action::Overwrite, false) => "Overwrote",
+            (Action::Overwrite, true) => "Would overwrite",
+            (Action::Keep, false) => "Kept existing",
+            (Action::Keep, true) => "Would keep existing",
+        };
+
+        let empty = if self.contents.is_empty() && self.action != Action::Keep {
+            "empty "
+        } else {
+            ""
+        };
+
+        format!(
+            "{verb} {empty}{} file \"{}\"{}",
+            self.kind,
+            self.path.display(),
+            self.note.map(|note| format!(" {note}")).unwrap_or_default()
+        )
+    }
+
+    /// Writes the file, returning its previous contents for a rollback.
+    fn apply(&self) -> Result<Option<Vec<u8>>, std::io::Error> {
+        match self.action {
+            Action::Create => {
+                OpenOptions::new()
+                    .write(true)
+                    .create_new(true)
+                    .open(&self.path)?
+                    .write_all(self.contents.as_bytes())?;
+                Ok(None)
+            }
+            Action::Overwrite => {
+                let previous = fs::read(&self.path)?;
+                fs::write(&self.path, &self.contents)?;
+                Ok(Some(previous))
+            }
+            Action::Keep => Ok(None),
+        }
+    }
+
+    fn revert(&self, previous: Option<&[u8]>) -> Result<(), std::io::Error> {
+        match (self.action, previous) {
+            (Action::Create, _) => fs::remove_file(&self.path),
+            (Action::Overwrite, Some(previous)) => fs::write(&self.path, previous),
+            _ => Ok(()),
         }
-        Err(e) => {
-            eprintln!("Failed to create input file: {e}");
-            process::exit(1);
+    }
+}
+
+/// Applies all operations. If one fails, the files written so far are restored.
+fn apply(operations: &[Operation]) -> Result<(), String> {
+    let mut applied = vec![];
+
+    for operation in operations {
+        match operation.apply() {
+            Ok(previous) => applied.push((operation, previous)),
+            Err(e) => {
+                for (operation, previous) in applied.iter().rev() {
+                    if let Err(e) = operation.revert(previous.as_deref()) {
+                        eprintln!(
+                            "Failed to roll back {} file \"{}\": {e}",
+                            operation.kind,
+                            operation.path.display()
+                        );
+                    }
+                }
+
+                return Err(format!(
+                    "could not write {} file \"{}\": {e}. All changes were rolled back.",
+                    operation.kind,
+                    operation.path.display()
+                ));
+            }
         }
     }
 
-    match create_file(&example_path) {
-        Ok(_) => {
-            println!("Created empty example file \"{}\"", &example_path);
+    Ok(())
+}
+
+fn download(day: Day) -> Result<(), String> {
+    aoc_cli::check().map_err(|_| {
+        "command \"aoc\" not found or not callable. Try running \"cargo install aoc-cli\" to install it."
+            .to_string()
+    })?;
+
+    aoc_cli::download(day).map_err(|e| format!("failed to call aoc-cli: {e}"))?;
+    Ok(())
+}
+
+fn scaffold(
+    day: Day,
+    overwrite: bool,
+    dry_run: bool,
+    download_puzzle: bool,
+    template: Option<&str>,
+) -> Result<(), String> {
+    let module_path = PathBuf::from(format!("src/bin/{day}.rs"));
+    let input_path = PathBuf::from(format!("data/inputs/{day}.txt"));
+    let example_path = PathBuf::from(format!("data/examples/{day}.txt"));
+
+    // fail before downloading anything if the day can not be scaffolded.
+    check_module(&module_path, overwrite)?;
+    let template = load_template(template.unwrap_or(default_template(day)))?;
+
+    // download first, so the scaffold can use the puzzle description.
+    if download_puzzle {
+        if dry_run {
+            println!("Would download input and puzzle description for day {day}");
+        } else {
+            download(day)?;
         }
-        Err(e) => {
-            eprintln!("Failed to create example file: {e}");
-            process::exit(1);
+    }
+
+    let description = fs::read_to_string(aoc_cli::get_puzzle_path(day)).ok();
+    let contents = render(&template, day, aoc_cli::get_year(), description.as_deref())?;
+
+    let example = Operation::data(
+        "example",
+        example_path,
+        description
+            .as_deref()
+            .and_then(|description| puzzle::guess(description).example)
+            .unwrap_or_default(),
+    );
+    let example = if example.contents.is_empty() {
+        example
+    } else {
+        example.with_note("with an example guessed from the puzzle, please review")
+    };
+
+    let operations = [
+        Operation::module(module_path, contents, overwrite)?,
+        Operation::data("input", input_path, String::new()),
+        example,
+    ];
+
+    if !dry_run {
+        apply(&operations)?;
+    }
+
+    for operation in &operations {
+        println!("{}", operation.describe(dry_run));
+    }
+
+    if !dry_run {
+        println!("---");
+        println!("🎄 Type `cargo solve {day}` to run your solution.");
+    }
+
+    Ok(())
+}
+
+pub fn handle(day: Day, overwrite: bool, dry_run: bool, download: bool, template: Option<&str>) {
+    if let Err(e) = scaffold(day, overwrite, dry_run, download, template) {
+        eprintln!("Failed to scaffold day {day}: {e}");
+        process::exit(1);
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::{env, fs, path::PathBuf, process};
+
+    use super::{apply, load_template, render, Action, Operation};
+    use crate::day;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("aoc-scaffold-{name}-{}", process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn renders_placeholders() {
+        let template = "%DAY_NUMBER% %DAY% %YEAR% %DATE% %TITLE%";
+        assert_eq!(
+            render(
+                template,
+                day!(5),
+                Some(2024),
+                Some("--- Day 5: Print Queue ---")
+            ),
+            Ok("5 05 2024 2024-12-05 Print Queue".into())
+        );
+        assert_eq!(render("%TITLE%", day!(5), None, None), Ok("Day 5".into()));
+        assert!(render("%DATE%", day!(5), None, None).is_err());
+    }
+
+    #[test]
+    fn renders_guessed_answers() {
+        let description =
+            "<pre><code>1\n2\n</code></pre>\n<p>The sum is <code><em>3</em></code>.</p>";
+        assert_eq!(
+            render(
+                "%PART_ONE_ANSWER% %PART_TWO_ANSWER%",
+                day!(1),
+                None,
+                Some(description)
+            ),
+            Ok("Some(3) /* guessed from the puzzle, please review */ None".into())
+        );
+    }
+
+    #[test]
+    fn loads_builtin_templates() {
+        for name in ["plain", "parse", "grid", "result", "single"] {
+            assert!(load_template(name).unwrap().contains("%DAY_NUMBER%"));
         }
+        assert!(load_template("unknown").is_err());
+    }
+
+    #[test]
+    fn plans_data_files() {
+        let dir = temp_dir("plan");
+        fs::write(dir.join("input.txt"), "1 2 3").unwrap();
+        fs::write(dir.join("example.txt"), "").unwrap();
+
+        let missing = Operation::data("input", dir.join("missing.txt"), String::new());
+        let existing = Operation::data("input", dir.join("input.txt"), String::new());
+        let empty = Operation::data("example", dir.join("example.txt"), "1".into());
+
+        assert_eq!(missing.action, Action::Create);
+        assert_eq!(existing.action, Action::Keep);
+        assert_eq!(empty.action, Action::Overwrite);
+        assert!(Operation::module(dir.join("input.txt"), String::new(), false).is_err());
+
+        fs::remove_dir_all(dir).unwrap();
     }
 
-    println!("---");
-    println!("🎄 Type `cargo solve {day}` to run your solution.");
+    #[test]
+    fn rolls_back_on_failure() {
+        let dir = temp_dir("rollback");
+        fs::write(dir.join("module.rs"), "old").unwrap();
+
+        let operations = [
+            Operation::module(dir.join("module.rs"), "new".into(), true).unwrap(),
+            Operation::data("input", dir.join("input.txt"), String::new()),
+            Operation::data(
+                "example",
+                dir.join("missing").join("example.txt"),
+                String::new(),
+            ),
+        ];
+
+        assert!(apply(&operations).is_err());
+        assert_eq!(fs::read_to_string(dir.join("module.rs")).unwrap(), "old");
+        assert!(!dir.join("input.txt").exists());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
 }
@@ -1,8 +1,16 @@
 use std::process::{Command, Stdio};
 
-use crate::template::Day;
+use crate::template::{input::InputSource, visualize, Day};
 
-pub fn handle(day: Day, release: bool, dhat: bool, submit_part: Option<u8>) {
+pub fn handle(
+    day: Day,
+    release: bool,
+    dhat: bool,
+    submit_part: Option<u8>,
+    params: &[String],
+    input: &InputSource,
+    visualize: Option<&visualize::Options>,
+) {
     let mut cmd_args = vec!["run".to_string(), "--bin".to_string(), day.to_string()];
 
     if dhat {
@@ -23,6 +31,17 @@ pub fn handle(day: Day, release: bool, dhat: bool, submit_part: Option<u8>) {
         cmd_args.push(submit_part.to_string());
     }
 
+    cmd_args.extend(input.to_args());
+
+    if let Some(options) = visualize {
+        cmd_args.extend(options.to_args());
+    }
+
+    for param in params {
+        cmd_args.push("--param".to_string());
+        cmd_args.push(param.clone());
+    }
+
     let mut cmd = Command::new("cargo")
         .args(&cmd_args)
         .stdout(Stdio::inherit())
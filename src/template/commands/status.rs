@@ -0,0 +1,237 @@
+use std::{
+    fs,
+    path::Path,
+    process::{Command, Stdio},
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::template::{
+    all_days, aoc_cli, examples::get_manifest_path, puzzle, run_multi::get_path_for_bin,
+    submissions::Submissions, timings::Timings, Day, ANSI_BOLD, ANSI_RESET,
+};
+
+/// Tests that run longer than this are reported as timed out, e.g. a search that never terminates.
+const TEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum TestStatus {
+    Passed,
+    Failed,
+    TimedOut,
+}
+
+/// Everything known about a single day.
+struct DayStatus {
+    day: Day,
+    scaffolded: bool,
+    input: bool,
+    examples: bool,
+    tests: Option<TestStatus>,
+    stars: u8,
+    /// The number of benchmarked parts.
+    benchmarked: u8,
+    answers: [Option<String>; 2],
+}
+
+fn has_contents(path: impl AsRef<Path>) -> bool {
+    fs::metadata(path).is_ok_and(|metadata| metadata.len() > 0)
+}
+
+/// Builds the test binary of a day and runs it directly, so it can be killed on timeout.
+fn run_tests(day: Day) -> TestStatus {
+    let Ok(build) = Command::new("cargo")
+        .args(["test", "--no-run", "--bin", &day.to_string()])
+        .stdout(Stdio::null())
+        .output()
+    else {
+        return TestStatus::Failed;
+    };
+
+    // cargo prints e.g. `Executable unittests src/bin/01.rs (target/debug/deps/01-1a2b3c)`.
+    let stderr = String::from_utf8_lossy(&build.stderr);
+    let executable = stderr
+        .lines()
+        .find(|line| line.trim_start().starts_with("Executable"))
+        .and_then(|line| line.rsplit_once('(')?.1.strip_suffix(')'));
+
+    let Some(mut child) = executable.and_then(|executable| {
+        Command::new(executable)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .ok()
+    }) else {
+        return TestStatus::Failed;
+    };
+
+    let deadline = Instant::now() + TEST_TIMEOUT;
+
+    loop {
+        match child.try_wait() {
+            Ok(Some(status)) if status.success() => return TestStatus::Passed,
+            Ok(Some(_)) | Err(_) => return TestStatus::Failed,
+            Ok(None) if Instant::now() < deadline => thread::sleep(Duration::from_millis(50)),
+            Ok(None) => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return TestStatus::TimedOut;
+            }
+        }
+    }
+}
+
+fn collect(day: Day, timings: &Timings, submissions: &Submissions, test: bool) -> DayStatus {
+    let scaffolded = Path::new(&get_path_for_bin(day)).exists();
+
+    let answers = [1, 2].map(|part| submissions.correct_answer(day, part).map(String::from));
+
+    let solved_in_description = fs::read_to_string(aoc_cli::get_puzzle_path(day))
+        .map(|description| puzzle::solved_parts(&description))
+        .unwrap_or(0);
+
+    let solved_in_log = answers.iter().filter(|answer| answer.is_some()).count();
+
+    let benchmarked = timings
+        .data
+        .iter()
+        .find(|timing| timing.day == day)
+        .map_or(0, |timing| {
+            u8::from(timing.part_1.is_some()) + u8::from(timing.part_2.is_some())
+        });
+
+    DayStatus {
+        day,
+        scaffolded,
+        input: has_contents(format!("data/inputs/{day}.txt")),
+        examples: has_contents(format!("data/examples/{day}.txt"))
+            || Path::new(&get_manifest_path(day)).exists(),
+        tests: (test && scaffolded).then(|| run_tests(day)),
+        stars: solved_in_description.max(u8::try_from(solved_in_log).unwrap_or(2)),
+        benchmarked,
+        answers,
+    }
+}
+
+fn check(value: bool) -> &'static str {
+    if value {
+        "✓"
+    } else {
+        "·"
+    }
+}
+
+fn format_row(status: &DayStatus, show_answers: bool) -> String {
+    let tests = match status.tests {
+        Some(TestStatus::Passed) => "pass",
+        Some(TestStatus::Failed) => "FAIL",
+        Some(TestStatus::TimedOut) => "time",
+        None => "-",
+    };
+
+    let stars = match status.stars {
+        0 => "··",
+        1 => "★·",
+        _ => "★★",
+    };
+
+    let benchmarked = match status.benchmarked {
+        0 => "·",
+        1 => "½",
+        _ => "✓",
+    };
+
+    let mut row = format!(
+        "{:>3}  {:^8}  {:^5}  {:^8}  {:^5}  {:^5}  {:^5}",
+        status.day.to_string(),
+        check(status.scaffolded),
+        check(status.input),
+        check(status.examples),
+        tests,
+        stars,
+        benchmarked,
+    );
+
+    if show_answers {
+        let [part_one, part_two] = &status.answers;
+        row.push_str(&format!(
+            "  {} / {}",
+            part_one.as_deref().unwrap_or("-"),
+            part_two.as_deref().unwrap_or("-")
+        ));
+    }
+
+    row.trim_end().to_string()
+}
+
+pub fn handle(test: bool, show_answers: bool) {
+    let timings = Timings::read_from_file();
+    let submissions = Submissions::read_from_file();
+
+    let statuses: Vec<DayStatus> = all_days()
+        .map(|day| collect(day, &timings, &submissions, test))
+        .collect();
+
+    match aoc_cli::get_year() {
+        Some(year) => println!("{ANSI_BOLD}Advent of Code {year}{ANSI_RESET}"),
+        None => println!("{ANSI_BOLD}Advent of Code{ANSI_RESET}"),
+    }
+    println!();
+
+    let mut header = String::from("Day  Scaffold  Input  Examples  Tests  Stars  Bench");
+    if show_answers {
+        header.push_str("  Answers");
+    }
+    println!("{ANSI_BOLD}{header}{ANSI_RESET}");
+
+    for status in &statuses {
+        println!("{}", format_row(status, show_answers));
+    }
+
+    let stars: u32 = statuses.iter().map(|s| u32::from(s.stars)).sum();
+    let scaffolded = statuses.iter().filter(|s| s.scaffolded).count();
+    let benchmarked = statuses.iter().filter(|s| s.benchmarked == 2).count();
+
+    println!();
+    println!(
+        "★ {stars}/50 stars, {scaffolded}/25 days scaffolded, {benchmarked}/25 days benchmarked."
+    );
+
+    if test {
+        let failing: Vec<String> = statuses
+            .iter()
+            .filter(|s| s.tests.is_some_and(|t| t != TestStatus::Passed))
+            .map(|s| s.day.to_string())
+            .collect();
+
+        if !failing.is_empty() {
+            println!("Failing tests: {}", failing.join(", "));
+        }
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{format_row, DayStatus, TestStatus};
+    use crate::day;
+
+    #[test]
+    fn formats_rows() {
+        let status = DayStatus {
+            day: day!(7),
+            scaffolded: true,
+            input: true,
+            examples: false,
+            tests: Some(TestStatus::Passed),
+            stars: 1,
+            benchmarked: 2,
+            answers: [Some("3749".into()), None],
+        };
+
+        assert_eq!(
+            format_row(&status, false),
+            " 07     ✓        ✓       ·      pass    ★·      ✓"
+        );
+        assert!(format_row(&status, true).ends_with("  3749 / -"));
+    }
+}
@@ -0,0 +1,429 @@
+/// A disk allocator simulator for compaction puzzles: files are moved to free space further left,
+/// the rightmost file first, as decided by a pluggable [`Strategy`].
+///
+/// ```ignore
+/// use advent_of_code::template::disk::{Disk, WholeFile};
+///
+/// let mut disk = Disk::from(input);
+/// let trace = disk.compact(&mut WholeFile);
+/// let checksum = disk.checksum();
+/// ```
+///
+/// Free space is indexed by size, with a min-heap of start positions per size, so finding the
+/// leftmost span that fits looks at one candidate per distinct size instead of every span.
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, BinaryHeap},
+    fmt::Display,
+};
+
+/// A run of consecutive blocks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Extent {
+    pub start: usize,
+    pub len: usize,
+}
+
+impl Extent {
+    pub fn end(&self) -> usize {
+        self.start + self.len
+    }
+}
+
+/// Free spans, indexed by their size.
+#[derive(Clone, Debug, Default)]
+pub struct FreeSpace {
+    by_size: BTreeMap<usize, BinaryHeap<Reverse<usize>>>,
+}
+
+impl FreeSpace {
+    pub fn insert(&mut self, span: Extent) {
+        if span.len > 0 {
+            self.by_size
+                .entry(span.len)
+                .or_default()
+                .push(Reverse(span.start));
+        }
+    }
+
+    /// Removes the leftmost span with at least `len` blocks that starts before `before`.
+    pub fn take_first_fit(&mut self, len: usize, before: usize) -> Option<Extent> {
+        let (&size, _) = self
+            .by_size
+            .range(len.max(1)..)
+            .filter_map(|(size, heap)| Some((size, heap.peek()?.0)))
+            .filter(|(_, start)| *start < before)
+            .min_by_key(|(_, start)| *start)?;
+
+        let heap = self.by_size.get_mut(&size).unwrap();
+        let Reverse(start) = heap.pop().unwrap();
+        if heap.is_empty() {
+            self.by_size.remove(&size);
+        }
+
+        Some(Extent { start, len: size })
+    }
+
+    /// Removes the leftmost span that starts before `before`.
+    pub fn take_leftmost(&mut self, before: usize) -> Option<Extent> {
+        self.take_first_fit(1, before)
+    }
+
+    /// The number of free blocks.
+    pub fn total(&self) -> usize {
+        self.by_size
+            .iter()
+            .map(|(size, heap)| size * heap.len())
+            .sum()
+    }
+}
+
+/// Blocks of a file moved to a new position.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Move {
+    pub id: usize,
+    pub from: usize,
+    pub to: usize,
+    pub len: usize,
+}
+
+impl Move {
+    /// Applies the move to a block layout, see [`Disk::blocks`].
+    pub fn apply_to(&self, blocks: &mut [Option<usize>]) {
+        for i in 0..self.len {
+            blocks[self.from + i] = None;
+            blocks[self.to + i] = Some(self.id);
+        }
+    }
+}
+
+/// Decides where the blocks of a file go.
+pub trait Strategy {
+    /// Moves for the file `id` currently at `file`, taking the space used from `free`. Only spans
+    /// left of the file are considered, space freed by moves is not reused.
+    fn place(&mut self, free: &mut FreeSpace, id: usize, file: Extent) -> Vec<Move>;
+}
+
+/// Moves single blocks from the end of the file to the leftmost free blocks, fragmenting files.
+pub struct BlockWise;
+
+impl Strategy for BlockWise {
+    fn place(&mut self, free: &mut FreeSpace, id: usize, file: Extent) -> Vec<Move> {
+        let mut moves = vec![];
+        let mut end = file.end();
+
+        while end > file.start {
+            let Some(span) = free.take_leftmost(file.start) else {
+                break;
+            };
+
+            let len = span.len.min(end - file.start);
+            end -= len;
+            moves.push(Move {
+                id,
+                from: end,
+                to: span.start,
+                len,
+            });
+
+            free.insert(Extent {
+                start: span.start + len,
+                len: span.len - len,
+            });
+        }
+
+        moves
+    }
+}
+
+/// Moves whole files to the leftmost span they fit in, or leaves them in place.
+pub struct WholeFile;
+
+impl Strategy for WholeFile {
+    fn place(&mut self, free: &mut FreeSpace, id: usize, file: Extent) -> Vec<Move> {
+        let Some(span) = free.take_first_fit(file.len, file.start) else {
+            return vec![];
+        };
+
+        free.insert(Extent {
+            start: span.start + file.len,
+            len: span.len - file.len,
+        });
+
+        vec![Move {
+            id,
+            from: file.start,
+            to: span.start,
+            len: file.len,
+        }]
+    }
+}
+
+/// Files and free space, parsed from a disk map of alternating file and free space sizes.
+#[derive(Clone, Debug, Default)]
+pub struct Disk {
+    /// The extents of each file, indexed by id.
+    files: Vec<Vec<Extent>>,
+    free: FreeSpace,
+    len: usize,
+}
+
+impl Disk {
+    /// Moves every file once, from the highest id to the lowest. Returns the moves made.
+    pub fn compact(&mut self, strategy: &mut impl Strategy) -> Vec<Move> {
+        let mut trace = vec![];
+
+        for id in (0..self.files.len()).rev() {
+            let Some(&file) = self.files[id].first() else {
+                continue;
+            };
+
+            for step in strategy.place(&mut self.free, id, file) {
+                self.apply(&step);
+                trace.push(step);
+            }
+        }
+
+        trace
+    }
+
+    fn apply(&mut self, step: &Move) {
+        let extents = &mut self.files[step.id];
+        let index = extents
+            .iter()
+            .position(|e| e.start <= step.from && step.from + step.len <= e.end())
+            .expect("moved blocks must belong to the file");
+        let extent = extents.remove(index);
+
+        extents.extend(
+            [
+                Extent {
+                    start: extent.start,
+                    len: step.from - extent.start,
+                },
+                Extent {
+                    start: step.from + step.len,
+                    len: extent.end() - step.from - step.len,
+                },
+                Extent {
+                    start: step.to,
+                    len: step.len,
+                },
+            ]
+            .into_iter()
+            .filter(|e| e.len > 0),
+        );
+    }
+
+    /// The file id of every block, `None` for free blocks.
+    pub fn blocks(&self) -> Vec<Option<usize>> {
+        let mut blocks = vec![None; self.len];
+        for (id, extents) in self.files.iter().enumerate() {
+            for extent in extents {
+                blocks[extent.start..extent.end()].fill(Some(id));
+            }
+        }
+        blocks
+    }
+
+    /// The sum of each block's position multiplied by its file id.
+    pub fn checksum(&self) -> u64 {
+        self.files
+            .iter()
+            .enumerate()
+            .flat_map(|(id, extents)| extents.iter().map(move |extent| (id, extent)))
+            .map(|(id, extent)| id as u64 * (extent.start..extent.end()).sum::<usize>() as u64)
+            .sum()
+    }
+
+    pub fn free_space(&self) -> &FreeSpace {
+        &self.free
+    }
+}
+
+impl<S: AsRef<str>> From<S> for Disk {
+    fn from(map: S) -> Self {
+        let mut disk = Self::default();
+        // free space separated by empty files is a single span.
+        let mut gap = Extent { start: 0, len: 0 };
+
+        let sizes = map.as_ref().chars().filter_map(|c| c.to_digit(10));
+        for (i, size) in sizes.enumerate() {
+            let extent = Extent {
+                start: disk.len,
+                len: size as usize,
+            };
+            disk.len += extent.len;
+
+            if i % 2 == 1 {
+                gap.len += extent.len;
+            } else if size == 0 {
+                disk.files.push(vec![]);
+            } else {
+                disk.free.insert(gap);
+                gap = Extent {
+                    start: disk.len,
+                    len: 0,
+                };
+                disk.files.push(vec![extent]);
+            }
+        }
+        disk.free.insert(gap);
+
+        disk
+    }
+}
+
+impl Display for Disk {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.blocks().into_iter().try_for_each(|block| match block {
+            Some(id) => write!(f, "{id}"),
+            None => write!(f, "."),
+        })
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{BlockWise, Disk, Extent, FreeSpace, Strategy, WholeFile};
+    use crate::template::property::{self, int, vec_of};
+
+    /// Compacts with a plain block array.
+    fn reference(map: &str, whole_files: bool) -> u64 {
+        let mut blocks = Disk::from(map).blocks();
+        let files = blocks.iter().flatten().max().map_or(0, |id| id + 1);
+
+        for id in (0..files).rev() {
+            let Some(start) = blocks.iter().position(|b| *b == Some(id)) else {
+                continue;
+            };
+            let end = blocks.iter().rposition(|b| *b == Some(id)).unwrap() + 1;
+
+            if whole_files {
+                let len = end - start;
+                let target = (0..start)
+                    .find(|&i| i + len <= start && blocks[i..i + len].iter().all(Option::is_none));
+                if let Some(target) = target {
+                    blocks[target..target + len].fill(Some(id));
+                    blocks[start..end].fill(None);
+                }
+            } else {
+                for i in (start..end).rev() {
+                    let Some(free) = blocks[..start].iter().position(Option::is_none) else {
+                        break;
+                    };
+                    blocks.swap(free, i);
+                }
+            }
+        }
+
+        blocks
+            .iter()
+            .enumerate()
+            .filter_map(|(i, id)| Some((i * (*id)?) as u64))
+            .sum()
+    }
+
+    fn compacted(map: &str, strategy: &mut impl Strategy) -> (Disk, u64) {
+        let mut disk = Disk::from(map);
+        disk.compact(strategy);
+        let checksum = disk.checksum();
+        (disk, checksum)
+    }
+
+    fn disk_map(digits: &[i64]) -> String {
+        digits.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn indexes_free_space() {
+        let mut free = FreeSpace::default();
+        for (start, len) in [(10, 3), (2, 1), (5, 2), (20, 5)] {
+            free.insert(Extent { start, len });
+        }
+        assert_eq!(free.total(), 11);
+
+        assert_eq!(
+            free.take_first_fit(2, 100),
+            Some(Extent { start: 5, len: 2 })
+        );
+        assert_eq!(free.take_first_fit(4, 20), None);
+        assert_eq!(free.take_leftmost(100), Some(Extent { start: 2, len: 1 }));
+        assert_eq!(free.take_leftmost(5), None);
+        assert_eq!(free.total(), 8);
+    }
+
+    #[test]
+    fn compacts_example() {
+        let map = "2333133121414131402";
+
+        let (disk, checksum) = compacted(map, &mut BlockWise);
+        assert_eq!(
+            disk.to_string(),
+            "0099811188827773336446555566.............."
+        );
+        assert_eq!(checksum, 1928);
+
+        let (disk, checksum) = compacted(map, &mut WholeFile);
+        assert_eq!(
+            disk.to_string(),
+            "00992111777.44.333....5555.6666.....8888.."
+        );
+        assert_eq!(checksum, 2858);
+    }
+
+    #[test]
+    fn traces_moves() {
+        let map = "12345";
+        let mut disk = Disk::from(map);
+        let trace = disk.compact(&mut BlockWise);
+
+        let mut blocks = Disk::from(map).blocks();
+        for step in &trace {
+            step.apply_to(&mut blocks);
+        }
+        assert_eq!(blocks, disk.blocks());
+        assert_eq!(disk.to_string(), "022111222......");
+        assert_eq!(trace.len(), 2);
+    }
+
+    #[test]
+    fn matches_reference_on_random_maps() {
+        let maps = vec_of(int(0..=9), 1..=40);
+
+        property::assert_equivalent(
+            &maps,
+            |digits| reference(&disk_map(digits), false),
+            |digits| compacted(&disk_map(digits), &mut BlockWise).1,
+        );
+        property::assert_equivalent(
+            &maps,
+            |digits| reference(&disk_map(digits), true),
+            |digits| compacted(&disk_map(digits), &mut WholeFile).1,
+        );
+    }
+
+    #[test]
+    fn compares_strategies() {
+        property::check(&vec_of(int(0..=9), 1..=40), |digits| {
+            let map = disk_map(digits);
+            let (block_wise, _) = compacted(&map, &mut BlockWise);
+            let (whole_file, _) = compacted(&map, &mut WholeFile);
+            let used = |disk: &Disk| disk.blocks().iter().flatten().count();
+
+            // both keep every block, block-wise leaves no gaps between files.
+            let blocks = block_wise.blocks();
+            let last_used = blocks
+                .iter()
+                .rposition(Option::is_some)
+                .map_or(0, |i| i + 1);
+            if used(&block_wise) != used(&whole_file) {
+                return Err("strategies lost blocks".into());
+            }
+            if blocks[..last_used].contains(&None) {
+                return Err(format!("gaps after block-wise compaction: {block_wise}"));
+            }
+            Ok(())
+        });
+    }
+}
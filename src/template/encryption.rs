@@ -0,0 +1,539 @@
+/// Encrypted puzzle inputs, so inputs can be committed without publishing them.
+/// An input `data/inputs/NN.txt` is stored as `data/inputs/NN.txt.enc`, encrypted with a key
+/// from the `AOC_INPUT_KEY` environment variable or the `.aoc-input-key` file.
+///
+/// The cipher is ChaCha20 with an HMAC-SHA256 tag (encrypt-then-MAC), implemented here so it works
+/// on any platform without dependencies. Keys are derived from the passphrase with PBKDF2 and a
+/// random salt stored in the file, so the committed files can't be attacked with precomputed tables.
+/// Re-encrypting an input keeps its salt and the nonce is derived from the plaintext, so encrypting
+/// the same input twice yields the same file and does not produce spurious diffs.
+use std::{
+    collections::hash_map::RandomState,
+    env, fs,
+    hash::{BuildHasher, Hasher},
+    io,
+    path::{Path, PathBuf},
+};
+
+const KEY_ENV: &str = "AOC_INPUT_KEY";
+const KEY_FILE: &str = ".aoc-input-key";
+const MAGIC: &[u8; 8] = b"AOCENC2\n";
+const SALT_LEN: usize = 16;
+const HEADER_LEN: usize = MAGIC.len() + 4 + SALT_LEN;
+const NONCE_LEN: usize = 12;
+const TAG_LEN: usize = 32;
+
+/// PBKDF2 iterations for newly encrypted files. Tests decrypt inputs in unoptimized builds too, which
+/// keeps this below the usual recommendations. Files store their iteration count, so changing it does
+/// not break existing files.
+pub const ITERATIONS: u32 = 100_000;
+
+/// Keys derived from the configured passphrase and a salt.
+pub struct Key {
+    salt: [u8; SALT_LEN],
+    iterations: u32,
+    encryption: [u8; 32],
+    authentication: [u8; 32],
+    nonce: [u8; 32],
+}
+
+impl Key {
+    /// Derives the keys from a passphrase of any length.
+    pub fn new(passphrase: &str, salt: [u8; SALT_LEN], iterations: u32) -> Self {
+        let master = pbkdf2_sha256(passphrase.trim().as_bytes(), &salt, iterations);
+        Self {
+            salt,
+            iterations,
+            encryption: hmac_sha256(&master, b"encryption"),
+            authentication: hmac_sha256(&master, b"authentication"),
+            nonce: hmac_sha256(&master, b"nonce"),
+        }
+    }
+
+    /// Derives the keys that `data` was encrypted with.
+    pub fn for_file(passphrase: &str, data: &[u8]) -> Result<Self, String> {
+        let (iterations, salt) = parse_header(data)?;
+        Ok(Self::new(passphrase, salt, iterations))
+    }
+
+    pub fn encrypt(&self, plaintext: &[u8]) -> Vec<u8> {
+        let nonce: [u8; NONCE_LEN] = hmac_sha256(&self.nonce, plaintext)[..NONCE_LEN]
+            .try_into()
+            .unwrap();
+
+        let mut data = MAGIC.to_vec();
+        data.extend_from_slice(&self.iterations.to_be_bytes());
+        data.extend_from_slice(&self.salt);
+        data.extend_from_slice(&nonce);
+        data.extend(chacha20(&self.encryption, &nonce, plaintext));
+
+        let tag = hmac_sha256(&self.authentication, &data);
+        data.extend_from_slice(&tag);
+        data
+    }
+
+    pub fn decrypt(&self, data: &[u8]) -> Result<Vec<u8>, String> {
+        let body = data
+            .strip_prefix(MAGIC)
+            .filter(|body| body.len() >= HEADER_LEN - MAGIC.len() + NONCE_LEN + TAG_LEN)
+            .ok_or("not an encrypted input.")?;
+        // the header is authenticated with the rest of the file, keys with another salt are rejected.
+        let body = &body[HEADER_LEN - MAGIC.len()..];
+
+        let (authenticated, tag) = data.split_at(data.len() - TAG_LEN);
+        if !constant_time_eq(&hmac_sha256(&self.authentication, authenticated), tag) {
+            return Err("wrong key, or the file was modified.".into());
+        }
+
+        let (nonce, ciphertext) = body[..body.len() - TAG_LEN].split_at(NONCE_LEN);
+        Ok(chacha20(
+            &self.encryption,
+            nonce.try_into().unwrap(),
+            ciphertext,
+        ))
+    }
+}
+
+/// The PBKDF2 iterations and salt stored in an encrypted file.
+fn parse_header(data: &[u8]) -> Result<(u32, [u8; SALT_LEN]), String> {
+    let header = data
+        .get(..HEADER_LEN)
+        .filter(|header| header.starts_with(MAGIC))
+        .ok_or("not an encrypted input.")?;
+
+    let (iterations, salt) = header[MAGIC.len()..].split_at(4);
+    match u32::from_be_bytes(iterations.try_into().unwrap()) {
+        0 => Err("not an encrypted input.".into()),
+        iterations => Ok((iterations, salt.try_into().unwrap())),
+    }
+}
+
+/// Reads the passphrase from `AOC_INPUT_KEY`, or from `.aoc-input-key` if the variable is not set.
+fn load_passphrase() -> Option<String> {
+    env::var(KEY_ENV)
+        .ok()
+        .or_else(|| fs::read_to_string(KEY_FILE).ok())
+        .filter(|passphrase| !passphrase.trim().is_empty())
+}
+
+/// A salt for a newly encrypted file. Salts only have to be unique, and std seeds its hasher keys from
+/// the operating system's random number generator.
+fn random_salt() -> [u8; SALT_LEN] {
+    let mut salt = [0; SALT_LEN];
+    for (i, chunk) in salt.chunks_exact_mut(8).enumerate() {
+        let mut hasher = RandomState::new().build_hasher();
+        hasher.write_usize(i);
+        chunk.copy_from_slice(&hasher.finish().to_le_bytes());
+    }
+    salt
+}
+
+#[must_use]
+pub fn get_encrypted_path(path: &Path) -> PathBuf {
+    let mut encrypted = path.as_os_str().to_owned();
+    encrypted.push(".enc");
+    PathBuf::from(encrypted)
+}
+
+/// Reads a file, falling back to its encrypted copy (`<path>.enc`) if the file does not exist or is
+/// empty, like the placeholder input created by `cargo scaffold`.
+pub fn read_to_string(path: &Path) -> io::Result<String> {
+    let result = fs::read_to_string(path);
+    match &result {
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+        Ok(text) if text.trim().is_empty() => {}
+        _ => return result,
+    }
+
+    let encrypted_path = get_encrypted_path(path);
+    let Ok(data) = fs::read(&encrypted_path) else {
+        return result;
+    };
+
+    let passphrase = load_passphrase().ok_or_else(|| {
+        io::Error::other(format!(
+            "\"{}\" is encrypted, set `{KEY_ENV}` or create `{KEY_FILE}` to decrypt it",
+            encrypted_path.display()
+        ))
+    })?;
+
+    let plaintext = Key::for_file(&passphrase, &data)
+        .and_then(|key| key.decrypt(&data))
+        .map_err(|e| {
+            io::Error::other(format!(
+                "could not decrypt \"{}\": {e}",
+                encrypted_path.display()
+            ))
+        })?;
+
+    String::from_utf8(plaintext).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// Writes an encrypted copy of the file if a key is configured.
+/// Returns the path of the encrypted file, or `None` if encryption is not enabled.
+pub fn store_encrypted(path: &Path) -> io::Result<Option<PathBuf>> {
+    let Some(passphrase) = load_passphrase() else {
+        return Ok(None);
+    };
+
+    let encrypted_path = get_encrypted_path(path);
+    // keep the salt of an existing file, so unchanged inputs are written byte for byte the same.
+    let salt = fs::read(&encrypted_path)
+        .ok()
+        .and_then(|data| parse_header(&data).ok())
+        .map_or_else(random_salt, |(_, salt)| salt);
+
+    let key = Key::new(&passphrase, salt, ITERATIONS);
+    fs::write(&encrypted_path, key.encrypt(&fs::read(path)?))?;
+    Ok(Some(encrypted_path))
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// ChaCha20 as specified in RFC 8439, with the block counter starting at 1.
+fn chacha20(key: &[u8; 32], nonce: &[u8; NONCE_LEN], data: &[u8]) -> Vec<u8> {
+    let mut state = [0u32; 16];
+    state[..4].copy_from_slice(&[0x6170_7865, 0x3320_646e, 0x7962_2d32, 0x6b20_6574]);
+    for (word, chunk) in state[4..12].iter_mut().zip(key.chunks_exact(4)) {
+        *word = u32::from_le_bytes(chunk.try_into().unwrap());
+    }
+    for (word, chunk) in state[13..].iter_mut().zip(nonce.chunks_exact(4)) {
+        *word = u32::from_le_bytes(chunk.try_into().unwrap());
+    }
+
+    data.chunks(64)
+        .zip(1u32..)
+        .flat_map(|(chunk, counter)| {
+            state[12] = counter;
+            let keystream = chacha20_block(&state);
+            chunk
+                .iter()
+                .zip(keystream)
+                .map(|(byte, key)| byte ^ key)
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+fn chacha20_block(state: &[u32; 16]) -> [u8; 64] {
+    fn quarter_round(x: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize) {
+        x[a] = x[a].wrapping_add(x[b]);
+        x[d] = (x[d] ^ x[a]).rotate_left(16);
+        x[c] = x[c].wrapping_add(x[d]);
+        x[b] = (x[b] ^ x[c]).rotate_left(12);
+        x[a] = x[a].wrapping_add(x[b]);
+        x[d] = (x[d] ^ x[a]).rotate_left(8);
+        x[c] = x[c].wrapping_add(x[d]);
+        x[b] = (x[b] ^ x[c]).rotate_left(7);
+    }
+
+    let mut x = *state;
+    for _ in 0..10 {
+        quarter_round(&mut x, 0, 4, 8, 12);
+        quarter_round(&mut x, 1, 5, 9, 13);
+        quarter_round(&mut x, 2, 6, 10, 14);
+        quarter_round(&mut x, 3, 7, 11, 15);
+        quarter_round(&mut x, 0, 5, 10, 15);
+        quarter_round(&mut x, 1, 6, 11, 12);
+        quarter_round(&mut x, 2, 7, 8, 13);
+        quarter_round(&mut x, 3, 4, 9, 14);
+    }
+
+    let mut block = [0u8; 64];
+    for (i, chunk) in block.chunks_exact_mut(4).enumerate() {
+        chunk.copy_from_slice(&x[i].wrapping_add(state[i]).to_le_bytes());
+    }
+    block
+}
+
+/* -------------------------------------------------------------------------- */
+
+const SHA256_K: [u32; 64] = [
+    0x428a_2f98,
+    0x7137_4491,
+    0xb5c0_fbcf,
+    0xe9b5_dba5,
+    0x3956_c25b,
+    0x59f1_11f1,
+    0x923f_82a4,
+    0xab1c_5ed5,
+    0xd807_aa98,
+    0x1283_5b01,
+    0x2431_85be,
+    0x550c_7dc3,
+    0x72be_5d74,
+    0x80de_b1fe,
+    0x9bdc_06a7,
+    0xc19b_f174,
+    0xe49b_69c1,
+    0xefbe_4786,
+    0x0fc1_9dc6,
+    0x240c_a1cc,
+    0x2de9_2c6f,
+    0x4a74_84aa,
+    0x5cb0_a9dc,
+    0x76f9_88da,
+    0x983e_5152,
+    0xa831_c66d,
+    0xb003_27c8,
+    0xbf59_7fc7,
+    0xc6e0_0bf3,
+    0xd5a7_9147,
+    0x06ca_6351,
+    0x1429_2967,
+    0x27b7_0a85,
+    0x2e1b_2138,
+    0x4d2c_6dfc,
+    0x5338_0d13,
+    0x650a_7354,
+    0x766a_0abb,
+    0x81c2_c92e,
+    0x9272_2c85,
+    0xa2bf_e8a1,
+    0xa81a_664b,
+    0xc24b_8b70,
+    0xc76c_51a3,
+    0xd192_e819,
+    0xd699_0624,
+    0xf40e_3585,
+    0x106a_a070,
+    0x19a4_c116,
+    0x1e37_6c08,
+    0x2748_774c,
+    0x34b0_bcb5,
+    0x391c_0cb3,
+    0x4ed8_aa4a,
+    0x5b9c_ca4f,
+    0x682e_6ff3,
+    0x748f_82ee,
+    0x78a5_636f,
+    0x84c8_7814,
+    0x8cc7_0208,
+    0x90be_fffa,
+    0xa450_6ceb,
+    0xbef9_a3f7,
+    0xc671_78f2,
+];
+
+const SHA256_H: [u32; 8] = [
+    0x6a09_e667,
+    0xbb67_ae85,
+    0x3c6e_f372,
+    0xa54f_f53a,
+    0x510e_527f,
+    0x9b05_688c,
+    0x1f83_d9ab,
+    0x5be0_cd19,
+];
+
+/// SHA-256 as specified in FIPS 180-4.
+fn sha256(data: &[u8]) -> [u8; 32] {
+    let mut h = SHA256_H;
+
+    let mut message = data.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&(data.len() as u64 * 8).to_be_bytes());
+
+    for block in message.chunks_exact(64) {
+        sha256_compress(&mut h, block);
+    }
+
+    sha256_digest(h)
+}
+
+/// Processes one 64 byte block of a SHA-256 message.
+fn sha256_compress(h: &mut [u32; 8], block: &[u8]) {
+    let mut w = [0u32; 64];
+    for (i, word) in block.chunks_exact(4).enumerate() {
+        w[i] = u32::from_be_bytes(word.try_into().unwrap());
+    }
+    for i in 16..64 {
+        let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+        let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+        w[i] = w[i - 16]
+            .wrapping_add(s0)
+            .wrapping_add(w[i - 7])
+            .wrapping_add(s1);
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut hh] = *h;
+    for i in 0..64 {
+        let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+        let ch = (e & f) ^ (!e & g);
+        let t1 = hh
+            .wrapping_add(s1)
+            .wrapping_add(ch)
+            .wrapping_add(SHA256_K[i])
+            .wrapping_add(w[i]);
+        let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+        let maj = (a & b) ^ (a & c) ^ (b & c);
+        let t2 = s0.wrapping_add(maj);
+
+        hh = g;
+        g = f;
+        f = e;
+        e = d.wrapping_add(t1);
+        d = c;
+        c = b;
+        b = a;
+        a = t1.wrapping_add(t2);
+    }
+
+    for (state, value) in h.iter_mut().zip([a, b, c, d, e, f, g, hh]) {
+        *state = state.wrapping_add(value);
+    }
+}
+
+fn sha256_digest(h: [u32; 8]) -> [u8; 32] {
+    let mut digest = [0u8; 32];
+    for (chunk, word) in digest.chunks_exact_mut(4).zip(h) {
+        chunk.copy_from_slice(&word.to_be_bytes());
+    }
+    digest
+}
+
+/// HMAC-SHA256 as specified in RFC 2104.
+fn hmac_sha256(key: &[u8], data: &[u8]) -> [u8; 32] {
+    let mut block = [0u8; 64];
+    if key.len() > 64 {
+        block[..32].copy_from_slice(&sha256(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+
+    let mut inner: Vec<u8> = block.iter().map(|b| b ^ 0x36).collect();
+    inner.extend_from_slice(data);
+
+    let mut outer: Vec<u8> = block.iter().map(|b| b ^ 0x5c).collect();
+    outer.extend_from_slice(&sha256(&inner));
+
+    sha256(&outer)
+}
+
+/// PBKDF2-HMAC-SHA256 as specified in RFC 8018, for a single 32 byte block.
+fn pbkdf2_sha256(password: &[u8], salt: &[u8], iterations: u32) -> [u8; 32] {
+    let mut block = [0u8; 64];
+    if password.len() > 64 {
+        block[..32].copy_from_slice(&sha256(password));
+    } else {
+        block[..password.len()].copy_from_slice(password);
+    }
+
+    // the padded keys are the same for every iteration, so their blocks are only hashed once.
+    let mut inner = SHA256_H;
+    sha256_compress(&mut inner, &block.map(|b| b ^ 0x36));
+    let mut outer = SHA256_H;
+    sha256_compress(&mut outer, &block.map(|b| b ^ 0x5c));
+
+    // hashes a 32 byte message that follows one of the padded keys.
+    let hash = |state: &[u32; 8], message: &[u8; 32]| {
+        let mut block = [0u8; 64];
+        block[..32].copy_from_slice(message);
+        block[32] = 0x80;
+        block[56..].copy_from_slice(&((64 + 32) as u64 * 8).to_be_bytes());
+
+        let mut state = *state;
+        sha256_compress(&mut state, &block);
+        sha256_digest(state)
+    };
+
+    let mut u = hmac_sha256(password, &[salt, &1u32.to_be_bytes()].concat());
+    let mut output = u;
+    for _ in 1..iterations {
+        u = hash(&outer, &hash(&inner, &u));
+        for (output, u) in output.iter_mut().zip(u) {
+            *output ^= u;
+        }
+    }
+    output
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{chacha20, hmac_sha256, pbkdf2_sha256, sha256, Key};
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{b:02x}")).collect()
+    }
+
+    #[test]
+    fn hashes_sha256() {
+        assert_eq!(
+            hex(&sha256(b"abc")),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(
+            hex(&sha256(b"")),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+    }
+
+    #[test]
+    fn hashes_hmac_sha256() {
+        // RFC 4231, test case 2.
+        assert_eq!(
+            hex(&hmac_sha256(b"Jefe", b"what do ya want for nothing?")),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn derives_pbkdf2_sha256() {
+        assert_eq!(
+            hex(&pbkdf2_sha256(b"passwd", b"salt", 1)),
+            "55ac046e56e3089fec1691c22544b605f94185216dde0465e68b9d57c20dacbc"
+        );
+        assert_eq!(
+            hex(&pbkdf2_sha256(b"password", b"salt", 4096)),
+            "c5e478d59288c841aa530db6845c4c8d962893a001ce4e11a4963873aa98134a"
+        );
+    }
+
+    #[test]
+    fn encrypts_chacha20() {
+        // RFC 8439, section 2.4.2.
+        let key: [u8; 32] = std::array::from_fn(|i| i as u8);
+        let nonce = [0, 0, 0, 0, 0, 0, 0, 0x4a, 0, 0, 0, 0];
+        let plaintext = b"Ladies and Gentlemen of the class of '99: If I could offer you only one tip for the future, sunscreen would be it.";
+
+        let ciphertext = chacha20(&key, &nonce, plaintext);
+        assert_eq!(hex(&ciphertext[..16]), "6e2e359a2568f98041ba0728dd0d6981");
+        assert_eq!(chacha20(&key, &nonce, &ciphertext), plaintext);
+    }
+
+    #[test]
+    fn roundtrips_inputs() {
+        let passphrase = "correct horse battery staple";
+        let key = Key::new(passphrase, [7; 16], 10);
+        let input = b"3   4\n4   3\n2   5\n";
+
+        let encrypted = key.encrypt(input);
+        assert_eq!(encrypted, key.encrypt(input));
+        assert_eq!(key.decrypt(&encrypted).unwrap(), input);
+
+        let key = Key::for_file(passphrase, &encrypted).unwrap();
+        assert_eq!(key.decrypt(&encrypted).unwrap(), input);
+    }
+
+    #[test]
+    fn rejects_wrong_keys_and_tampering() {
+        let key = |passphrase| Key::new(passphrase, [7; 16], 10);
+        let encrypted = key("key").encrypt(b"1 2 3\n");
+        assert!(key("other").decrypt(&encrypted).is_err());
+        assert!(Key::new("key", [8; 16], 10).decrypt(&encrypted).is_err());
+
+        let mut tampered = encrypted.clone();
+        let last = tampered.len() - 40;
+        tampered[last] ^= 1;
+        assert!(key("key").decrypt(&tampered).is_err());
+        assert!(key("key").decrypt(b"3   4").is_err());
+        assert!(Key::for_file("key", b"3   4").is_err());
+    }
+}
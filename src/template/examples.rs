@@ -0,0 +1,282 @@
+/// Example manifests: a list of example inputs with expected answers for a day.
+/// Manifests live next to the example files as `data/examples/NN.json`:
+///
+/// ```json
+/// {
+///     "examples": [
+///         { "file": "12.txt", "part_one": 1930, "part_two": 1206 },
+///         { "name": "small", "input": "AAAA\nBBCD\nBBCC\nEEEC", "part_one": 140, "part_two": 80 },
+///         { "name": "e_shape", "file": "12-2.txt", "part_two": 236 }
+///     ]
+/// }
+/// ```
+///
+/// The `solution!` macro generates one test per manifest entry and part.
+use std::{collections::HashMap, env, fs, str::FromStr};
+
+use tinyjson::JsonValue;
+
+use crate::template::{input::normalize, params::RawParams, runner::PartResult, Day};
+
+/// A single example input with its expected answers.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Example {
+    pub name: Option<String>,
+    pub input: String,
+    /// `None` if part one is not checked for this example, `Some(None)` if no answer is expected.
+    pub part_one: Option<Option<String>>,
+    /// `None` if part two is not checked for this example, `Some(None)` if no answer is expected.
+    pub part_two: Option<Option<String>>,
+    pub params: RawParams,
+}
+
+impl Example {
+    /// The expected answer for a part, see [`Example::part_one`].
+    pub fn expected(&self, part: u8) -> Option<Option<&str>> {
+        let expected = match part {
+            1 => self.part_one.as_ref(),
+            2 => self.part_two.as_ref(),
+            _ => None,
+        }?;
+
+        Some(expected.as_deref())
+    }
+
+    /// A human-readable label, e.g. `#2 (small)`.
+    pub fn label(&self, index: usize) -> String {
+        match &self.name {
+            Some(name) => format!("#{} ({name})", index + 1),
+            None => format!("#{}", index + 1),
+        }
+    }
+}
+
+/// All examples of a day.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Manifest {
+    pub examples: Vec<Example>,
+}
+
+#[must_use]
+pub fn get_manifest_path(day: Day) -> String {
+    format!("data/examples/{day}.json")
+}
+
+impl Manifest {
+    /// Reads the manifest for a day. Returns `Ok(None)` if the day has no manifest.
+    pub fn read(day: Day) -> Result<Option<Self>, String> {
+        let cwd = env::current_dir().map_err(|e| e.to_string())?;
+        let path = cwd.join(get_manifest_path(day));
+
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let contents = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+        let manifest = Self::parse(&contents, |file| {
+            fs::read_to_string(cwd.join("data").join("examples").join(file))
+                .map_err(|e| format!("could not read example file `{file}`: {e}"))
+        })?;
+
+        Ok(Some(manifest))
+    }
+
+    /// Parses a manifest, using `read_file` to resolve examples that reference a `file`.
+    pub fn parse(
+        contents: &str,
+        read_file: impl Fn(&str) -> Result<String, String>,
+    ) -> Result<Self, String> {
+        let json = JsonValue::from_str(contents).or(Err("not valid JSON file."))?;
+
+        let examples = json
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("expected JSON document to be an object.")?
+            .get("examples")
+            .ok_or("expected JSON document to have key `examples`.")?
+            .get::<Vec<JsonValue>>()
+            .ok_or("expected `json.examples` to be an array.")?;
+
+        Ok(Self {
+            examples: examples
+                .iter()
+                .map(|example| parse_example(example, &read_file))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+fn parse_example(
+    value: &JsonValue,
+    read_file: impl Fn(&str) -> Result<String, String>,
+) -> Result<Example, String> {
+    let json = value
+        .get::<HashMap<String, JsonValue>>()
+        .ok_or("Expected example to be a JSON object.")?;
+
+    let name = match json.get("name") {
+        Some(name) => Some(
+            name.get::<String>()
+                .ok_or("Expected example.name to be a string.")?
+                .clone(),
+        ),
+        None => None,
+    };
+
+    let input = match (json.get("input"), json.get("file")) {
+        (Some(input), None) => input
+            .get::<String>()
+            .ok_or("Expected example.input to be a string.")?
+            .clone(),
+        (None, Some(file)) => read_file(
+            file.get::<String>()
+                .ok_or("Expected example.file to be a string.")?,
+        )?,
+        _ => return Err("Expected example to have exactly one of `input` or `file`.".into()),
+    };
+
+    let part_one = json
+        .get("part_one")
+        .map(|v| {
+            parse_answer(v).ok_or("Expected example.part_one to be null, a number or a string.")
+        })
+        .transpose()?;
+
+    let part_two = json
+        .get("part_two")
+        .map(|v| {
+            parse_answer(v).ok_or("Expected example.part_two to be null, a number or a string.")
+        })
+        .transpose()?;
+
+    let params = match json.get("params") {
+        Some(params) => params
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("Expected example.params to be an object.")?
+            .iter()
+            .map(|(key, value)| {
+                parse_answer(value)
+                    .flatten()
+                    .map(|value| (key.clone(), value))
+                    .ok_or(format!(
+                        "Expected example.params.{key} to be a number or a string."
+                    ))
+            })
+            .collect::<Result<HashMap<_, _>, _>>()?
+            .into(),
+        None => RawParams::default(),
+    };
+
+    Ok(Example {
+        name,
+        input,
+        part_one,
+        part_two,
+        params,
+    })
+}
+
+/// Answers are compared by their display value, so numbers and strings are both accepted.
+fn parse_answer(value: &JsonValue) -> Option<Option<String>> {
+    match value {
+        JsonValue::Null => Some(None),
+        JsonValue::String(s) => Some(Some(s.clone())),
+        JsonValue::Number(n) if n.fract() == 0.0 && n.abs() < 2f64.powi(53) =>
+        {
+            #[allow(clippy::cast_possible_truncation)]
+            Some(Some((*n as i64).to_string()))
+        }
+        _ => None,
+    }
+}
+
+/// Runs a solution part against a manifest entry and panics if the answer does not match.
+/// Used by the tests generated by the `solution!` macro.
+pub fn check<R: PartResult>(
+    day: Day,
+    index: usize,
+    part: u8,
+    func: impl Fn(&str, &RawParams) -> R,
+) {
+    let manifest = Manifest::read(day)
+        .unwrap_or_else(|e| panic!("could not read example manifest: {e}"))
+        .expect("example manifest was removed");
+
+    let example = manifest
+        .examples
+        .get(index)
+        .unwrap_or_else(|| panic!("example #{} was removed from the manifest", index + 1));
+
+    let Some(expected) = example.expected(part) else {
+        // no answer declared for this part.
+        return;
+    };
+
+    let result = func(&normalize(&example.input), &example.params);
+    let answer = result.answer().map(ToString::to_string);
+
+    assert_eq!(
+        answer.as_deref(),
+        expected,
+        "example {} of day {day} returned a wrong answer for part {part}{}",
+        example.label(index),
+        result
+            .error()
+            .map(|e| format!(" ({e})"))
+            .unwrap_or_default()
+    );
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::Manifest;
+
+    fn read_file(file: &str) -> Result<String, String> {
+        match file {
+            "01.txt" => Ok("1 2\n3 4".into()),
+            _ => Err(format!("missing {file}")),
+        }
+    }
+
+    #[test]
+    fn parses_manifest() {
+        let manifest = Manifest::parse(
+            r#"{ "examples": [
+                { "file": "01.txt", "part_one": 11, "part_two": "abc" },
+                { "name": "inline", "input": "x", "part_two": null, "params": { "width": 11, "mode": "fast" } }
+            ] }"#,
+            read_file,
+        )
+        .unwrap();
+
+        assert_eq!(manifest.examples.len(), 2);
+
+        let first = &manifest.examples[0];
+        assert_eq!(first.input, "1 2\n3 4");
+        assert_eq!(first.expected(1), Some(Some("11")));
+        assert_eq!(first.expected(2), Some(Some("abc")));
+
+        let second = &manifest.examples[1];
+        assert_eq!(second.name.as_deref(), Some("inline"));
+        assert_eq!(second.expected(1), None);
+        assert_eq!(second.expected(2), Some(None));
+        assert_eq!(second.params.get::<u32>("width"), Ok(Some(11)));
+        assert_eq!(second.params.get::<String>("mode"), Ok(Some("fast".into())));
+        assert_eq!(second.label(1), "#2 (inline)");
+    }
+
+    #[test]
+    #[should_panic]
+    fn panics_for_missing_files() {
+        Manifest::parse(r#"{ "examples": [{ "file": "02.txt" }] }"#, read_file).unwrap();
+    }
+
+    #[test]
+    #[should_panic]
+    fn panics_for_ambiguous_inputs() {
+        Manifest::parse(
+            r#"{ "examples": [{ "file": "01.txt", "input": "x" }] }"#,
+            read_file,
+        )
+        .unwrap();
+    }
+}
@@ -0,0 +1,414 @@
+/// Searches for operators that combine a list of operands into a target value, e.g.
+/// `190: 10 19` is solved by `10 * 19`.
+///
+/// Operators are user-defined with a forward function and, optionally, an inverse. Expressions are
+/// either evaluated strictly left to right, or with operator precedence:
+///
+/// ```ignore
+/// use advent_of_code::template::expression::{self, Search, ADD, CONCAT, MUL};
+///
+/// let equations = expression::parse_equations::<u128>(input)?;
+/// let search = Search::new(&[ADD, MUL, CONCAT]);
+/// let total: u128 = search
+///     .find_all(&equations)
+///     .into_iter()
+///     .zip(&equations)
+///     .filter(|(witness, _)| witness.is_some())
+///     .map(|(_, equation)| equation.target)
+///     .sum();
+/// ```
+///
+/// Left to right, when every operator has an inverse, the search starts from the target and undoes
+/// the operators from the last operand to the first, which prunes most branches early. Otherwise
+/// every combination of operators is evaluated.
+use std::{fmt::Display, str::FromStr, thread};
+
+/// A binary operator.
+pub struct Operator<T> {
+    pub symbol: &'static str,
+    /// Higher binds tighter, only used with [`Evaluation::Precedence`].
+    pub precedence: u8,
+    /// `left op right`, `None` if the result is invalid, e.g. on overflow.
+    pub apply: fn(&T, &T) -> Option<T>,
+    /// The only `left` with `left op right == result`, given `right` and `result`, or `None`
+    /// if there is none.
+    pub inverse: Option<fn(&T, &T) -> Option<T>>,
+}
+
+// manual impls, a derive would require `T: Clone`.
+impl<T> Clone for Operator<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Operator<T> {}
+
+/// Addition of `u128`s.
+pub const ADD: Operator<u128> = Operator {
+    symbol: "+",
+    precedence: 1,
+    apply: |left, right| left.checked_add(*right),
+    inverse: Some(|right, result| result.checked_sub(*right)),
+};
+
+/// Multiplication of `u128`s. The inverse assumes operands are not zero, as in the puzzles.
+pub const MUL: Operator<u128> = Operator {
+    symbol: "*",
+    precedence: 2,
+    apply: |left, right| left.checked_mul(*right),
+    inverse: Some(|right, result| {
+        (*right != 0 && result.is_multiple_of(*right)).then(|| result / right)
+    }),
+};
+
+/// Concatenation of the decimal digits of `u128`s, e.g. `12 || 345 = 12345`.
+pub const CONCAT: Operator<u128> = Operator {
+    symbol: "||",
+    precedence: 3,
+    apply: |left, right| {
+        left.checked_mul(10u128.checked_pow(right.checked_ilog10().unwrap_or(0) + 1)?)?
+            .checked_add(*right)
+    },
+    inverse: Some(|right, result| {
+        let mask = 10u128.checked_pow(right.checked_ilog10().unwrap_or(0) + 1)?;
+        (result % mask == *right).then_some(result / mask)
+    }),
+};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Evaluation {
+    /// Operators are applied in order, ignoring their precedence.
+    #[default]
+    LeftToRight,
+    /// Operators with a higher precedence are applied first, equal ones left to right.
+    Precedence,
+}
+
+/// A target value and the operands that should reach it, one line of `target: a b c`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Equation<T> {
+    pub target: T,
+    pub operands: Vec<T>,
+}
+
+/// Parses lines of `target: a b c`.
+pub fn parse_equations<T: FromStr>(input: &str) -> Result<Vec<Equation<T>>, String> {
+    input
+        .lines()
+        .map(|line| {
+            let (target, operands) = line
+                .split_once(": ")
+                .ok_or(format!("expected `target: operands`, got `{line}`."))?;
+            let parse = |s: &str| s.parse().map_err(|_| format!("invalid number `{s}`."));
+
+            Ok(Equation {
+                target: parse(target)?,
+                operands: operands
+                    .split_whitespace()
+                    .map(parse)
+                    .collect::<Result<_, _>>()?,
+            })
+        })
+        .collect()
+}
+
+/// Operands combined with operators, a solution of an [`Equation`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Expression<T> {
+    pub operands: Vec<T>,
+    pub operators: Vec<&'static str>,
+}
+
+impl<T: Display> Display for Expression<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, operand) in self.operands.iter().enumerate() {
+            if i > 0 {
+                write!(f, " {} ", self.operators[i - 1])?;
+            }
+            write!(f, "{operand}")?;
+        }
+        Ok(())
+    }
+}
+
+pub struct Search<'a, T> {
+    operators: &'a [Operator<T>],
+    evaluation: Evaluation,
+}
+
+impl<'a, T: Clone + PartialEq + Send + Sync> Search<'a, T> {
+    /// Searches with the given operators, evaluating left to right.
+    pub fn new(operators: &'a [Operator<T>]) -> Self {
+        Self {
+            operators,
+            evaluation: Evaluation::default(),
+        }
+    }
+
+    pub fn with_evaluation(mut self, evaluation: Evaluation) -> Self {
+        self.evaluation = evaluation;
+        self
+    }
+
+    fn invertible(&self) -> bool {
+        self.evaluation == Evaluation::LeftToRight
+            && self.operators.iter().all(|op| op.inverse.is_some())
+    }
+
+    /// Calls `found` with the operator indices of every solution until it returns `false`.
+    fn search(&self, operands: &[T], target: &T, found: &mut dyn FnMut(&[usize]) -> bool) {
+        if operands.is_empty() {
+            return;
+        }
+
+        let mut chosen = vec![0; operands.len() - 1];
+        if self.invertible() {
+            self.search_inverse(operands, target, operands.len() - 1, &mut chosen, found);
+        } else {
+            self.search_forward(operands, target, 0, &mut chosen, found);
+        }
+    }
+
+    /// Undoes the operator before `operands[index]`, returns `false` to stop the search.
+    fn search_inverse(
+        &self,
+        operands: &[T],
+        result: &T,
+        index: usize,
+        chosen: &mut [usize],
+        found: &mut dyn FnMut(&[usize]) -> bool,
+    ) -> bool {
+        if index == 0 {
+            return operands[0] != *result || found(chosen);
+        }
+
+        for (i, operator) in self.operators.iter().enumerate() {
+            let inverse = operator.inverse.unwrap();
+            if let Some(left) = inverse(&operands[index], result) {
+                chosen[index - 1] = i;
+                if !self.search_inverse(operands, &left, index - 1, chosen, found) {
+                    return false;
+                }
+            }
+        }
+
+        true
+    }
+
+    /// Chooses the operator before `operands[index + 1]`, returns `false` to stop the search.
+    fn search_forward(
+        &self,
+        operands: &[T],
+        target: &T,
+        index: usize,
+        chosen: &mut [usize],
+        found: &mut dyn FnMut(&[usize]) -> bool,
+    ) -> bool {
+        if index == chosen.len() {
+            return self.evaluate(operands, chosen).as_ref() != Some(target) || found(chosen);
+        }
+
+        for i in 0..self.operators.len() {
+            chosen[index] = i;
+            if !self.search_forward(operands, target, index + 1, chosen, found) {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Evaluates operands combined with the operators at the given indices.
+    fn evaluate(&self, operands: &[T], chosen: &[usize]) -> Option<T> {
+        let mut values = vec![operands[0].clone()];
+        let mut pending: Vec<&Operator<T>> = vec![];
+
+        let reduce = |values: &mut Vec<T>, operator: &Operator<T>| {
+            let right = values.pop().unwrap();
+            let left = values.pop().unwrap();
+            values.push((operator.apply)(&left, &right)?);
+            Some(())
+        };
+
+        for (operand, &i) in operands[1..].iter().zip(chosen) {
+            let operator = &self.operators[i];
+            while let Some(&top) = pending.last() {
+                let applies_first = match self.evaluation {
+                    Evaluation::LeftToRight => true,
+                    Evaluation::Precedence => top.precedence >= operator.precedence,
+                };
+                if !applies_first {
+                    break;
+                }
+                pending.pop();
+                reduce(&mut values, top)?;
+            }
+
+            pending.push(operator);
+            values.push(operand.clone());
+        }
+
+        while let Some(top) = pending.pop() {
+            reduce(&mut values, top)?;
+        }
+
+        values.pop()
+    }
+
+    fn expression(&self, operands: &[T], chosen: &[usize]) -> Expression<T> {
+        Expression {
+            operands: operands.to_vec(),
+            operators: chosen.iter().map(|&i| self.operators[i].symbol).collect(),
+        }
+    }
+
+    /// An expression of the operands that evaluates to the target, if there is one.
+    pub fn find(&self, operands: &[T], target: &T) -> Option<Expression<T>> {
+        let mut witness = None;
+        self.search(operands, target, &mut |chosen| {
+            witness = Some(self.expression(operands, chosen));
+            false
+        });
+        witness
+    }
+
+    /// The number of operator combinations that evaluate to the target.
+    pub fn count(&self, operands: &[T], target: &T) -> usize {
+        let mut count = 0;
+        self.search(operands, target, &mut |_| {
+            count += 1;
+            true
+        });
+        count
+    }
+
+    /// [`find`](Self::find) for every equation, spread over all cores.
+    pub fn find_all(&self, equations: &[Equation<T>]) -> Vec<Option<Expression<T>>> {
+        parallel_map(equations, |equation| {
+            self.find(&equation.operands, &equation.target)
+        })
+    }
+
+    /// [`count`](Self::count) for every equation, spread over all cores.
+    pub fn count_all(&self, equations: &[Equation<T>]) -> Vec<usize> {
+        parallel_map(equations, |equation| {
+            self.count(&equation.operands, &equation.target)
+        })
+    }
+}
+
+/// Maps the items in chunks on scoped threads, keeping their order.
+fn parallel_map<I: Sync, O: Send>(items: &[I], f: impl Fn(&I) -> O + Sync) -> Vec<O> {
+    let threads = thread::available_parallelism().map_or(1, |n| n.get());
+    let chunk_size = items.len().div_ceil(threads).max(1);
+
+    thread::scope(|scope| {
+        let handles: Vec<_> = items
+            .chunks(chunk_size)
+            .map(|chunk| scope.spawn(|| chunk.iter().map(&f).collect::<Vec<_>>()))
+            .collect();
+
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().unwrap())
+            .collect()
+    })
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{parse_equations, Evaluation, Operator, Search, ADD, CONCAT, MUL};
+
+    const EXAMPLE: &str = "190: 10 19\n3267: 81 40 27\n83: 17 5\n7290: 6 8 6 15\n292: 11 6 16 20";
+
+    /// Subtraction without an inverse, so the forward search is used.
+    const SUB: Operator<u128> = Operator {
+        symbol: "-",
+        precedence: 1,
+        apply: |left, right| left.checked_sub(*right),
+        inverse: None,
+    };
+
+    #[test]
+    fn parses_equations() {
+        let equations = parse_equations::<u128>(EXAMPLE).unwrap();
+        assert_eq!(equations.len(), 5);
+        assert_eq!(equations[1].target, 3267);
+        assert_eq!(equations[1].operands, vec![81, 40, 27]);
+
+        assert!(parse_equations::<u128>("12 3").is_err());
+        assert!(parse_equations::<u128>("12: 3 x").is_err());
+    }
+
+    #[test]
+    fn finds_witnesses() {
+        let search = Search::new(&[ADD, MUL]);
+        let witness = search.find(&[81, 40, 27], &3267).unwrap();
+        assert_eq!(witness.to_string(), "81 * 40 + 27");
+        assert_eq!(search.count(&[81, 40, 27], &3267), 2);
+        assert_eq!(search.find(&[17, 5], &83), None);
+        assert_eq!(search.find(&[], &0), None);
+        assert_eq!(search.count(&[7], &7), 1);
+
+        let search = Search::new(&[ADD, MUL, CONCAT]);
+        assert_eq!(
+            search.find(&[6, 8, 6, 15], &7290).unwrap().to_string(),
+            "6 * 8 || 6 * 15"
+        );
+    }
+
+    #[test]
+    fn inverts_concatenation() {
+        let inverse = CONCAT.inverse.unwrap();
+        assert_eq!(inverse(&345, &12345), Some(12));
+        assert_eq!(inverse(&0, &120), Some(12));
+        assert_eq!(inverse(&45, &12345), Some(123));
+        assert_eq!(inverse(&5, &12344), None);
+        // rounds to 10^18 as a float, which gives a mask with one digit too many.
+        assert_eq!(
+            inverse(&999_999_999_999_999_999, &1_999_999_999_999_999_999),
+            Some(1)
+        );
+        assert_eq!((CONCAT.apply)(&12, &345), Some(12345));
+        assert_eq!((CONCAT.apply)(&u128::MAX, &1), None);
+    }
+
+    #[test]
+    fn evaluates_with_precedence() {
+        let search = Search::new(&[ADD, MUL]).with_evaluation(Evaluation::Precedence);
+        // left to right, 2 + 3 * 4 would be 20.
+        assert_eq!(
+            search.find(&[2, 3, 4], &14).unwrap().to_string(),
+            "2 + 3 * 4"
+        );
+        assert_eq!(search.find(&[2, 3, 4], &20), None);
+        assert_eq!(search.count(&[1, 1, 1], &2), 2);
+    }
+
+    #[test]
+    fn searches_without_inverses() {
+        let search = Search::new(&[ADD, SUB]);
+        assert_eq!(
+            search.find(&[10, 4, 3], &9).unwrap().to_string(),
+            "10 - 4 + 3"
+        );
+        assert_eq!(search.count(&[5, 5, 5, 5], &10), 3);
+        // 1 - 2 underflows, so it is not a solution.
+        assert_eq!(search.find(&[1, 2, 3], &2), None);
+    }
+
+    #[test]
+    fn searches_in_parallel() {
+        let equations = parse_equations::<u128>(EXAMPLE).unwrap();
+        let search = Search::new(&[ADD, MUL]);
+
+        let found: Vec<bool> = search
+            .find_all(&equations)
+            .iter()
+            .map(Option::is_some)
+            .collect();
+        assert_eq!(found, vec![true, true, false, false, true]);
+        assert_eq!(search.count_all(&equations), vec![1, 2, 0, 0, 1]);
+    }
+}
@@ -0,0 +1,751 @@
+/// Fuzzing of solution parts: runs each part against mutated variants of the example inputs and
+/// reports inputs that make it panic, overflow (in debug builds) or hang.
+/// Started by `cargo fuzz-day <day>`, which runs the solution binary with `--fuzz`.
+///
+/// Mutations are structure-aware: besides flipping characters they replace numbers with edge
+/// cases, drop, duplicate and swap lines, make grid rows ragged and splice lines of other examples.
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    env, fs,
+    panic::{self, AssertUnwindSafe},
+    path::PathBuf,
+    process,
+    sync::{mpsc, Arc},
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::template::{
+    input::{self, InputSource},
+    params::RawParams,
+    property::Rng,
+    Day, ANSI_BOLD, ANSI_RESET,
+};
+
+/// A solution part that receives the fuzzed input and the parameters of the example it was
+/// derived from. The answer is discarded.
+pub type Target = Arc<dyn Fn(&str, &RawParams) + Send + Sync>;
+
+/// An input to mutate, with the parameters it is solved with.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Sample {
+    pub input: String,
+    pub params: RawParams,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Config {
+    /// The number of inputs each part is run against.
+    pub cases: usize,
+    /// The seed of the run. A random seed is used if not set.
+    pub seed: Option<u64>,
+    /// Runs exceeding this duration are reported as hangs.
+    pub timeout: Duration,
+    /// Also mutate the puzzle input, not only the examples.
+    pub puzzle_input: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            cases: 1000,
+            seed: None,
+            timeout: Duration::from_secs(5),
+            puzzle_input: false,
+        }
+    }
+}
+
+impl Config {
+    /// Parses the configuration from the arguments passed to the current process.
+    pub fn from_args() -> Result<Self, String> {
+        let args: Vec<String> = env::args().skip(1).collect();
+        Self::parse(&args)
+    }
+
+    /// Parses `--cases <n>`, `--seed <n>`, `--timeout <seconds>` and `--puzzle-input`.
+    pub fn parse(args: &[String]) -> Result<Self, String> {
+        let mut config = Self::default();
+
+        for (i, arg) in args.iter().enumerate() {
+            let value = || {
+                args.get(i + 1)
+                    .ok_or(format!("expected a value after `{arg}`."))
+            };
+
+            match arg.as_str() {
+                "--cases" => {
+                    config.cases = value()?
+                        .parse()
+                        .map_err(|_| "invalid number of cases.".to_string())?;
+                }
+                "--seed" => {
+                    config.seed = Some(value()?.parse().map_err(|_| "invalid seed.".to_string())?);
+                }
+                "--timeout" => {
+                    let seconds: f64 = value()?
+                        .parse()
+                        .ok()
+                        .filter(|seconds: &f64| *seconds > 0.0)
+                        .ok_or("invalid timeout.")?;
+                    config.timeout = Duration::from_secs_f64(seconds);
+                }
+                "--puzzle-input" => config.puzzle_input = true,
+                _ => {}
+            }
+        }
+
+        Ok(config)
+    }
+
+    /// Arguments that select this configuration in a solution binary.
+    pub fn to_args(&self) -> Vec<String> {
+        let mut args = vec![
+            "--cases".into(),
+            self.cases.to_string(),
+            "--timeout".into(),
+            self.timeout.as_secs_f64().to_string(),
+        ];
+
+        if let Some(seed) = self.seed {
+            args.extend(["--seed".into(), seed.to_string()]);
+        }
+
+        if self.puzzle_input {
+            args.push("--puzzle-input".into());
+        }
+
+        args
+    }
+}
+
+#[must_use]
+pub fn get_fuzz_dir(day: Day) -> PathBuf {
+    PathBuf::from(format!("data/fuzz/{day}"))
+}
+
+/// The input currently being run. If the solution aborts the process, e.g. on a stack overflow,
+/// this file is left behind and reported by `cargo fuzz-day`.
+#[must_use]
+pub fn get_current_case_path(day: Day) -> PathBuf {
+    get_fuzz_dir(day).join("current.txt")
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Kind {
+    Panic,
+    Overflow,
+    Hang,
+}
+
+impl Kind {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Panic => "panic",
+            Self::Overflow => "overflow",
+            Self::Hang => "hang",
+        }
+    }
+}
+
+/// The result of running a part once.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Outcome {
+    Ok,
+    Failed {
+        kind: Kind,
+        location: String,
+        message: String,
+    },
+}
+
+/// A distinct failure of a part, identified by its kind and the location of the panic.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Failure {
+    pub part: u8,
+    pub kind: Kind,
+    pub location: String,
+    pub message: String,
+    /// The smallest input found that fails the same way.
+    pub sample: Sample,
+    /// The number of generated inputs that failed this way.
+    pub count: usize,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Report {
+    pub cases: usize,
+    pub failures: Vec<Failure>,
+}
+
+thread_local! {
+    /// Whether this thread runs fuzzed inputs.
+    static IS_WORKER: Cell<bool> = const { Cell::new(false) };
+    /// Location and message of the last panic on this thread, captured by the fuzzing panic hook.
+    static LAST_PANIC: RefCell<Option<(String, String)>> = const { RefCell::new(None) };
+}
+
+/// Captures panics of worker threads instead of printing them. Panics of other threads are
+/// passed on to the previous hook.
+fn install_panic_hook() {
+    let previous = panic::take_hook();
+
+    panic::set_hook(Box::new(move |info| {
+        if !IS_WORKER.with(Cell::get) {
+            previous(info);
+            return;
+        }
+
+        let location = info
+            .location()
+            .map_or("unknown location".into(), ToString::to_string);
+        let payload = info.payload();
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(ToString::to_string)
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_default();
+
+        LAST_PANIC.with(|last| *last.borrow_mut() = Some((location, message)));
+    }));
+}
+
+/// Runs the target on a worker thread. A worker that exceeds the timeout is abandoned.
+fn execute(target: &Target, sample: &Sample, timeout: Duration) -> Outcome {
+    let (sender, receiver) = mpsc::channel();
+    let target = Arc::clone(target);
+    let sample = sample.clone();
+
+    thread::spawn(move || {
+        IS_WORKER.with(|is_worker| is_worker.set(true));
+        let result =
+            panic::catch_unwind(AssertUnwindSafe(|| target(&sample.input, &sample.params)));
+        let _ = sender.send(result.map_err(|_| LAST_PANIC.with(|last| last.borrow_mut().take())));
+    });
+
+    match receiver.recv_timeout(timeout) {
+        Ok(Ok(())) => Outcome::Ok,
+        Ok(Err(panic)) => {
+            let (location, message) = panic.unwrap_or(("unknown location".into(), String::new()));
+            let kind = if message.starts_with("attempt to") && message.contains("overflow") {
+                Kind::Overflow
+            } else {
+                Kind::Panic
+            };
+            Outcome::Failed {
+                kind,
+                location,
+                message,
+            }
+        }
+        Err(_) => Outcome::Failed {
+            kind: Kind::Hang,
+            location: String::new(),
+            message: format!("did not finish within {timeout:.1?}"),
+        },
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Values that commonly break parsers and arithmetic.
+const EDGE_NUMBERS: [&str; 10] = [
+    "0",
+    "1",
+    "-1",
+    "255",
+    "65536",
+    "2147483648",
+    "4294967296",
+    "9223372036854775808",
+    "18446744073709551616",
+    "99999999999999999999999999",
+];
+
+/// Ranges of `-?[0-9]+` in `chars`.
+fn number_spans(chars: &[char]) -> Vec<(usize, usize)> {
+    let mut spans = vec![];
+    let mut i = 0;
+
+    while i < chars.len() {
+        if chars[i].is_ascii_digit() {
+            let start = if i > 0 && chars[i - 1] == '-' {
+                i - 1
+            } else {
+                i
+            };
+            let mut end = i;
+            while end < chars.len() && chars[end].is_ascii_digit() {
+                end += 1;
+            }
+            spans.push((start, end));
+            i = end;
+        } else {
+            i += 1;
+        }
+    }
+
+    spans
+}
+
+fn random_char(rng: &mut Rng, alphabet: &[char]) -> char {
+    const SPECIAL: [char; 7] = [' ', '\n', '-', ',', ':', '0', '#'];
+
+    if alphabet.is_empty() || rng.index(4) == 0 {
+        SPECIAL[rng.index(SPECIAL.len())]
+    } else {
+        alphabet[rng.index(alphabet.len())]
+    }
+}
+
+/// Arbitrary printable text, for inputs that share no structure with the examples.
+fn arbitrary(rng: &mut Rng) -> String {
+    let len = rng.index(64);
+    (0..len)
+        .map(|_| match rng.index(10) {
+            0 => '\n',
+            _ => char::from(b' ' + rng.index(95) as u8),
+        })
+        .collect()
+}
+
+/// Applies a single random mutation to `input`. `corpus` provides lines to splice in.
+pub fn mutate(rng: &mut Rng, input: &str, corpus: &[Sample]) -> String {
+    let mut chars: Vec<char> = input.chars().collect();
+    let mut alphabet = chars.clone();
+    alphabet.sort_unstable();
+    alphabet.dedup();
+
+    let mut lines: Vec<String> = input.lines().map(String::from).collect();
+    let line = rng.index(lines.len().max(1));
+
+    match rng.index(11) {
+        0 => {
+            let spans = number_spans(&chars);
+            if spans.is_empty() {
+                return mutate(rng, input, corpus);
+            }
+            let (start, end) = spans[rng.index(spans.len())];
+            let number = match rng.index(3) {
+                0 => rng.range(&(0..=1000)).to_string(),
+                _ => EDGE_NUMBERS[rng.index(EDGE_NUMBERS.len())].to_string(),
+            };
+            chars.splice(start..end, number.chars());
+            chars.into_iter().collect()
+        }
+        1 if !chars.is_empty() => {
+            chars.remove(rng.index(chars.len()));
+            chars.into_iter().collect()
+        }
+        2 => {
+            let c = random_char(rng, &alphabet);
+            chars.insert(rng.index(chars.len() + 1), c);
+            chars.into_iter().collect()
+        }
+        3 if !chars.is_empty() => {
+            let i = rng.index(chars.len());
+            chars[i] = random_char(rng, &alphabet);
+            chars.into_iter().collect()
+        }
+        4 if !lines.is_empty() => {
+            lines.remove(line);
+            lines.join("\n")
+        }
+        5 if !lines.is_empty() => {
+            lines.insert(line, lines[line].clone());
+            lines.join("\n")
+        }
+        6 if !lines.is_empty() => {
+            let other = rng.index(lines.len());
+            lines.swap(line, other);
+            lines.join("\n")
+        }
+        7 if !chars.is_empty() => chars[..rng.index(chars.len())].iter().collect(),
+        8 if !lines.is_empty() => {
+            // makes a row of a grid shorter than the others.
+            let len = lines[line].chars().count();
+            lines[line] = lines[line].chars().take(rng.index(len.max(1))).collect();
+            lines.join("\n")
+        }
+        9 if !corpus.is_empty() && !lines.is_empty() => {
+            let other: Vec<&str> = corpus[rng.index(corpus.len())].input.lines().collect();
+            if other.is_empty() {
+                return mutate(rng, input, corpus);
+            }
+            lines[line] = other[rng.index(other.len())].to_string();
+            lines.join("\n")
+        }
+        10 => arbitrary(rng),
+        _ => mutate(rng, input, corpus),
+    }
+}
+
+/// Generates the next input to run: one to three mutations of a random corpus entry, normalised
+/// like the runner does. Inputs the runner would reject are skipped.
+fn generate(rng: &mut Rng, corpus: &[Sample]) -> Sample {
+    loop {
+        let mut sample = corpus[rng.index(corpus.len())].clone();
+        for _ in 0..=rng.index(3) {
+            sample.input = mutate(rng, &sample.input, corpus);
+        }
+
+        sample.input = input::normalize(&sample.input);
+        if input::check(&sample.input).is_ok() {
+            return sample;
+        }
+    }
+}
+
+/// Shrinks a failing input by removing lines and chunks of characters, as long as the part
+/// still fails at the same location.
+fn minimize(
+    target: &Target,
+    sample: &Sample,
+    kind: Kind,
+    location: &str,
+    timeout: Duration,
+) -> Sample {
+    const MAX_STEPS: usize = 500;
+
+    let steps = Cell::new(0);
+    let fails = |input: String| {
+        steps.set(steps.get() + 1);
+        let candidate = Sample {
+            input,
+            params: sample.params.clone(),
+        };
+        input::check(&candidate.input).is_ok()
+            && matches!(
+                execute(target, &candidate, timeout),
+                Outcome::Failed { kind: k, location: l, .. } if k == kind && l == location
+            )
+    };
+
+    let mut best = sample.input.clone();
+
+    let mut i = best.lines().count();
+    while i > 0 && steps.get() < MAX_STEPS {
+        i -= 1;
+        let mut lines: Vec<&str> = best.lines().collect();
+        // normalising may have removed more than one line.
+        if i >= lines.len() {
+            continue;
+        }
+        lines.remove(i);
+        let candidate = input::normalize(&lines.join("\n"));
+        if fails(candidate.clone()) {
+            best = candidate;
+        }
+    }
+
+    let mut chunk = best.chars().count() / 2;
+    while chunk > 0 && steps.get() < MAX_STEPS {
+        let mut start = 0;
+        let mut shrunk = false;
+
+        while steps.get() < MAX_STEPS {
+            let chars: Vec<char> = best.chars().collect();
+            if start >= chars.len() {
+                break;
+            }
+
+            let end = (start + chunk).min(chars.len());
+            let candidate: String = chars[..start].iter().chain(&chars[end..]).collect();
+            let candidate = input::normalize(&candidate);
+            if fails(candidate.clone()) {
+                best = candidate;
+                shrunk = true;
+            } else {
+                start += chunk;
+            }
+        }
+
+        if !shrunk {
+            chunk /= 2;
+        }
+    }
+
+    Sample {
+        input: best,
+        params: sample.params.clone(),
+    }
+}
+
+/// Runs a part against `config.cases` generated inputs. `on_case` is called before every run.
+/// Fuzzing stops after the first hang, as the hanging worker can not be stopped.
+pub fn fuzz(
+    config: &Config,
+    seed: u64,
+    corpus: &[Sample],
+    part: u8,
+    target: &Target,
+    mut on_case: impl FnMut(&Sample),
+) -> Report {
+    let mut rng = Rng::new(seed ^ u64::from(part));
+    let mut report = Report::default();
+    let mut index = HashMap::new();
+
+    for _ in 0..config.cases {
+        let sample = generate(&mut rng, corpus);
+        on_case(&sample);
+        report.cases += 1;
+
+        let Outcome::Failed {
+            kind,
+            location,
+            message,
+        } = execute(target, &sample, config.timeout)
+        else {
+            continue;
+        };
+
+        if let Some(&i) = index.get(&(kind, location.clone())) {
+            let failure: &mut Failure = &mut report.failures[i];
+            failure.count += 1;
+            continue;
+        }
+
+        let sample = match kind {
+            Kind::Hang => sample,
+            _ => minimize(target, &sample, kind, &location, config.timeout),
+        };
+
+        index.insert((kind, location.clone()), report.failures.len());
+        report.failures.push(Failure {
+            part,
+            kind,
+            location,
+            message,
+            sample,
+            count: 1,
+        });
+
+        if kind == Kind::Hang {
+            break;
+        }
+    }
+
+    report
+}
+
+/// Inputs to mutate: the examples of a day and, if requested, the puzzle input.
+fn read_corpus(day: Day, puzzle_input: bool) -> Result<Vec<Sample>, String> {
+    let mut corpus: Vec<Sample> = (1..)
+        .map_while(|number| InputSource::Example(number).read(day).ok())
+        .map(|(input, params)| Sample { input, params })
+        .collect();
+
+    if puzzle_input {
+        corpus.push(Sample {
+            input: InputSource::Puzzle.read(day)?.0,
+            params: RawParams::default(),
+        });
+    }
+
+    if corpus.is_empty() {
+        return Err(format!(
+            "day {day} has no examples to mutate. Add one to \"data/examples/{day}.txt\"."
+        ));
+    }
+
+    Ok(corpus)
+}
+
+/// Fuzzes every part of a solution, stores the failing inputs in `data/fuzz/NN/` and exits.
+/// Called by the `solution!` macro when the binary is started with `--fuzz`.
+pub fn run(day: Day, targets: &[(u8, Target)]) -> ! {
+    let result = Config::from_args().and_then(|config| {
+        let corpus = read_corpus(day, config.puzzle_input)?;
+        Ok((config, corpus))
+    });
+
+    let (config, corpus) = result.unwrap_or_else(|e| {
+        eprintln!("Error: {e}");
+        process::exit(1);
+    });
+
+    let seed = config.seed.unwrap_or_else(|| {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_nanos() as u64)
+    });
+
+    let dir = get_fuzz_dir(day);
+    let current = get_current_case_path(day);
+    if let Err(e) = fs::create_dir_all(&dir) {
+        eprintln!("Error: could not create \"{}\": {e}.", dir.display());
+        process::exit(1);
+    }
+
+    println!(
+        "Fuzzing day {day} with {} input(s) per part (seed {seed}, timeout {:.1?}).",
+        config.cases, config.timeout
+    );
+
+    let default_hook = panic::take_hook();
+    install_panic_hook();
+
+    let mut failures = vec![];
+    let mut hung = false;
+
+    for (part, target) in targets {
+        let report = fuzz(&config, seed, &corpus, *part, target, |sample| {
+            let _ = fs::write(&current, &sample.input);
+        });
+
+        println!(
+            "Part {part}: {} input(s), {} distinct failure(s)",
+            report.cases,
+            report.failures.len()
+        );
+
+        hung |= report.failures.iter().any(|f| f.kind == Kind::Hang);
+        failures.extend(report.failures);
+
+        if hung {
+            break;
+        }
+    }
+
+    panic::set_hook(default_hook);
+    let _ = fs::remove_file(&current);
+
+    for (i, failure) in failures.iter().enumerate() {
+        let path = dir.join(format!(
+            "part-{}-{}-{}.txt",
+            failure.part,
+            failure.kind.label(),
+            i + 1
+        ));
+        let saved = match fs::write(&path, &failure.sample.input) {
+            Ok(()) => format!("\"{}\"", path.display()),
+            Err(e) => format!("could not be saved: {e}"),
+        };
+
+        println!();
+        println!(
+            "{ANSI_BOLD}Part {}: {}{ANSI_RESET}{} ({} input(s))",
+            failure.part,
+            failure.kind.label(),
+            match failure.location.as_str() {
+                "" => String::new(),
+                location => format!(" at {location}"),
+            },
+            failure.count
+        );
+        println!("  {}", failure.message);
+        println!("  minimal input: {saved}");
+        if !failure.sample.params.is_empty() {
+            println!(
+                "  parameters: {}",
+                failure.sample.params.to_args().join(" ")
+            );
+        }
+    }
+
+    if hung {
+        println!();
+        println!("Fuzzing stopped at the first hang. Reproduce it with `cargo solve {day} --input <file>`.");
+    }
+
+    if failures.is_empty() {
+        println!("No failures found.");
+        process::exit(0);
+    }
+
+    println!();
+    println!("Rerun with `cargo fuzz-day {day} --seed {seed}`.");
+    process::exit(1);
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::{sync::Arc, time::Duration};
+
+    use super::{fuzz, install_panic_hook, mutate, number_spans, Config, Kind, Sample, Target};
+    use crate::template::{input, property::Rng};
+
+    fn sample(input: &str) -> Sample {
+        Sample {
+            input: input.into(),
+            params: Default::default(),
+        }
+    }
+
+    #[test]
+    fn parses_config() {
+        let args: Vec<String> = ["--cases", "20", "--seed", "7", "--timeout", "0.5"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let config = Config::parse(&args).unwrap();
+
+        assert_eq!(config.cases, 20);
+        assert_eq!(config.seed, Some(7));
+        assert_eq!(config.timeout, Duration::from_millis(500));
+        assert_eq!(Config::parse(&config.to_args()), Ok(config));
+        assert!(Config::parse(&["--timeout".into(), "-1".into()]).is_err());
+    }
+
+    #[test]
+    fn finds_numbers() {
+        let chars: Vec<char> = "p=-12,3 v=4".chars().collect();
+        assert_eq!(number_spans(&chars), vec![(2, 5), (6, 7), (10, 11)]);
+    }
+
+    #[test]
+    fn mutates_deterministically() {
+        let corpus = vec![sample("1 2\n3 4\n"), sample("..#\n#..\n")];
+        let mutations = |seed| {
+            let mut rng = Rng::new(seed);
+            (0..50)
+                .map(|i| mutate(&mut rng, &corpus[i % 2].input, &corpus))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(mutations(3), mutations(3));
+        assert!(mutations(3)
+            .iter()
+            .any(|m| m != &corpus[0].input && m != &corpus[1].input));
+    }
+
+    #[test]
+    fn reports_and_minimizes_failures() {
+        install_panic_hook();
+
+        // overflows for any number above 155.
+        let target: Target = Arc::new(|input: &str, _: &_| {
+            for n in input.lines().filter_map(|n| n.parse::<u8>().ok()) {
+                std::hint::black_box(n + 100);
+            }
+        });
+
+        let config = Config {
+            cases: 200,
+            ..Config::default()
+        };
+        let corpus = vec![sample("1\n2\n3\n4\n5\n6\n7\n8\n")];
+        let report = fuzz(&config, 1, &corpus, 1, &target, |_| {});
+
+        let _ = std::panic::take_hook();
+
+        assert_eq!(report.cases, 200);
+        let overflow = report
+            .failures
+            .iter()
+            .find(|failure| failure.kind == Kind::Overflow)
+            .expect("overflow was not found");
+
+        assert!(overflow.message.contains("overflow"));
+        assert!(input::check(&overflow.sample.input).is_ok());
+        assert_eq!(
+            overflow.sample.input.lines().count(),
+            1,
+            "{:?}",
+            overflow.sample
+        );
+    }
+}
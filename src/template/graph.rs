@@ -0,0 +1,439 @@
+/// Directed graphs of ordering constraints, like day 05's `47|53` page rules, with topological
+/// sorting and cycle reporting:
+///
+/// ```ignore
+/// use advent_of_code::template::graph::Graph;
+///
+/// let rules: Graph<u32> = [(47, 53), (97, 13), (97, 47)].into_iter().collect();
+/// let update = rules.induced(&[53, 97, 47]);
+/// assert_eq!(update.topological_sort(), Ok(vec![97, 47, 53]));
+/// ```
+///
+/// Sorting with a comparator built from constraints is only correct if the constraints form a
+/// total order on the items, which [`check_comparator`] verifies. A topological sort is correct for
+/// any acyclic set of constraints, and [`Graph::has_unique_order`] tells whether there is only one.
+/// Even if there are several, [`Graph::fixed_at`] finds nodes that have the same position in all.
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::{BinaryHeap, HashMap, HashSet},
+    fmt::{Debug, Display},
+    hash::Hash,
+};
+
+/// Nodes with directed edges between them. Nodes are kept in insertion order.
+#[derive(Clone, Debug)]
+pub struct Graph<N> {
+    indices: HashMap<N, usize>,
+    nodes: Vec<N>,
+    successors: Vec<Vec<usize>>,
+    edges: HashSet<(usize, usize)>,
+}
+
+impl<N: Hash + Eq + Clone> Default for Graph<N> {
+    fn default() -> Self {
+        Self {
+            indices: HashMap::new(),
+            nodes: vec![],
+            successors: vec![],
+            edges: HashSet::new(),
+        }
+    }
+}
+
+/// Nodes that depend on each other in a loop, each with an edge to the next and the last to the
+/// first, so they have no topological order.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Cycle<N> {
+    pub nodes: Vec<N>,
+}
+
+impl<N: Display> Display for Cycle<N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "cycle: ")?;
+        for node in &self.nodes {
+            write!(f, "{node} -> ")?;
+        }
+        match self.nodes.first() {
+            Some(first) => write!(f, "{first}"),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<N: Hash + Eq + Clone> Graph<N> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a node without edges, if it does not exist yet.
+    pub fn add_node(&mut self, node: N) -> usize {
+        if let Some(&index) = self.indices.get(&node) {
+            return index;
+        }
+
+        let index = self.nodes.len();
+        self.indices.insert(node.clone(), index);
+        self.nodes.push(node);
+        self.successors.push(vec![]);
+        index
+    }
+
+    /// Adds an edge meaning `from` comes before `to`. Duplicate edges are ignored.
+    pub fn add_edge(&mut self, from: N, to: N) {
+        let (from, to) = (self.add_node(from), self.add_node(to));
+        if self.edges.insert((from, to)) {
+            self.successors[from].push(to);
+        }
+    }
+
+    pub fn contains(&self, node: &N) -> bool {
+        self.indices.contains_key(node)
+    }
+
+    pub fn has_edge(&self, from: &N, to: &N) -> bool {
+        match (self.indices.get(from), self.indices.get(to)) {
+            (Some(&from), Some(&to)) => self.edges.contains(&(from, to)),
+            _ => false,
+        }
+    }
+
+    pub fn nodes(&self) -> &[N] {
+        &self.nodes
+    }
+
+    pub fn successors<'a>(&'a self, node: &N) -> impl Iterator<Item = &'a N> + 'a {
+        let successors = match self.indices.get(node) {
+            Some(&index) => self.successors[index].as_slice(),
+            None => &[],
+        };
+        successors.iter().map(|&i| &self.nodes[i])
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.edges.len()
+    }
+
+    /// The subgraph of `nodes` and the edges between them, with nodes in the given order.
+    /// Nodes that are not in this graph are added without edges.
+    pub fn induced(&self, nodes: &[N]) -> Self {
+        let mut graph = Self::new();
+        for node in nodes {
+            graph.add_node(node.clone());
+        }
+
+        for node in nodes {
+            for successor in self.successors(node) {
+                if graph.contains(successor) {
+                    graph.add_edge(node.clone(), successor.clone());
+                }
+            }
+        }
+
+        graph
+    }
+
+    /// Orders nodes so every edge points forward, with Kahn's algorithm. When several nodes could
+    /// come next, the earliest inserted one does, so the result is deterministic.
+    pub fn topological_sort(&self) -> Result<Vec<N>, Cycle<N>> {
+        let mut in_degrees = vec![0usize; self.nodes.len()];
+        for successors in &self.successors {
+            for &successor in successors {
+                in_degrees[successor] += 1;
+            }
+        }
+
+        let mut ready: BinaryHeap<Reverse<usize>> = (0..self.nodes.len())
+            .filter(|&i| in_degrees[i] == 0)
+            .map(Reverse)
+            .collect();
+        let mut order = Vec::with_capacity(self.nodes.len());
+
+        while let Some(Reverse(index)) = ready.pop() {
+            order.push(index);
+            for &successor in &self.successors[index] {
+                in_degrees[successor] -= 1;
+                if in_degrees[successor] == 0 {
+                    ready.push(Reverse(successor));
+                }
+            }
+        }
+
+        if order.len() < self.nodes.len() {
+            return Err(self.find_cycle(&in_degrees));
+        }
+
+        Ok(order.into_iter().map(|i| self.nodes[i].clone()).collect())
+    }
+
+    /// Every node left with a positive in-degree after Kahn's algorithm has a predecessor that
+    /// is also left, so walking predecessors backwards from any of them must repeat a node.
+    fn find_cycle(&self, in_degrees: &[usize]) -> Cycle<N> {
+        let mut predecessors = vec![None; self.nodes.len()];
+        for (from, successors) in self.successors.iter().enumerate() {
+            for &to in successors {
+                if in_degrees[from] > 0 && in_degrees[to] > 0 {
+                    predecessors[to] = Some(from);
+                }
+            }
+        }
+
+        let mut visited = vec![false; self.nodes.len()];
+        let mut current = in_degrees.iter().position(|&d| d > 0).unwrap();
+        while !visited[current] {
+            visited[current] = true;
+            current = predecessors[current].unwrap();
+        }
+
+        let start = current;
+        let mut cycle = vec![start];
+        current = predecessors[start].unwrap();
+        while current != start {
+            cycle.push(current);
+            current = predecessors[current].unwrap();
+        }
+        cycle.reverse();
+
+        Cycle {
+            nodes: cycle.into_iter().map(|i| self.nodes[i].clone()).collect(),
+        }
+    }
+
+    /// Whether no edge between nodes of `sequence` points backwards.
+    pub fn is_ordered(&self, sequence: &[N]) -> bool {
+        let positions: HashMap<&N, usize> = sequence.iter().zip(0..).collect();
+
+        sequence.iter().enumerate().all(|(i, node)| {
+            self.successors(node)
+                .all(|successor| positions.get(successor).is_none_or(|&j| j > i))
+        })
+    }
+
+    /// Whether `order` is the only topological order, which is the case if every node has an edge
+    /// to the next one.
+    pub fn has_unique_order(&self, order: &[N]) -> bool {
+        self.is_ordered(order)
+            && order
+                .windows(2)
+                .all(|pair| self.has_edge(&pair[0], &pair[1]))
+    }
+
+    /// The node at `position` in every topological order, if there is one. That is a node with
+    /// `position` ancestors and all other nodes as descendants. Assumes the graph is acyclic.
+    pub fn fixed_at(&self, position: usize) -> Option<&N> {
+        let reachable: Vec<Vec<bool>> = (0..self.nodes.len())
+            .map(|index| self.reachable_from(index))
+            .collect();
+
+        (0..self.nodes.len())
+            .find(|&index| {
+                let ancestors = reachable.iter().filter(|from| from[index]).count();
+                let descendants = reachable[index].iter().filter(|&&to| to).count();
+                ancestors == position && ancestors + descendants + 1 == self.nodes.len()
+            })
+            .map(|index| &self.nodes[index])
+    }
+
+    /// The nodes reachable from `start` by following one or more edges.
+    fn reachable_from(&self, start: usize) -> Vec<bool> {
+        let mut reachable = vec![false; self.nodes.len()];
+        let mut stack = vec![start];
+
+        while let Some(index) = stack.pop() {
+            for &successor in &self.successors[index] {
+                if !reachable[successor] {
+                    reachable[successor] = true;
+                    stack.push(successor);
+                }
+            }
+        }
+
+        reachable
+    }
+
+    /// Compares nodes by the edge between them, [`Ordering::Equal`] if there is none.
+    pub fn compare(&self, a: &N, b: &N) -> Ordering {
+        if self.has_edge(a, b) {
+            Ordering::Less
+        } else if self.has_edge(b, a) {
+            Ordering::Greater
+        } else {
+            Ordering::Equal
+        }
+    }
+}
+
+impl<N: Hash + Eq + Clone> FromIterator<(N, N)> for Graph<N> {
+    fn from_iter<T: IntoIterator<Item = (N, N)>>(iter: T) -> Self {
+        let mut graph = Self::new();
+        for (from, to) in iter {
+            graph.add_edge(from, to);
+        }
+        graph
+    }
+}
+
+/// Checks that `compare` is a total order on `items`, as sorting requires: every item is equal to
+/// itself, swapping the arguments reverses the result, and both less and equal are transitive.
+/// Takes cubic time in the number of items.
+pub fn check_comparator<T: Debug>(
+    items: &[T],
+    compare: impl Fn(&T, &T) -> Ordering,
+) -> Result<(), String> {
+    for a in items {
+        if compare(a, a) != Ordering::Equal {
+            return Err(format!("{a:?} is not equal to itself."));
+        }
+    }
+
+    for (i, a) in items.iter().enumerate() {
+        for b in &items[i + 1..] {
+            if compare(a, b) != compare(b, a).reverse() {
+                return Err(format!(
+                    "comparing {a:?} with {b:?} is {:?}, but the reverse is {:?}.",
+                    compare(a, b),
+                    compare(b, a)
+                ));
+            }
+        }
+    }
+
+    for a in items {
+        for b in items {
+            let ab = compare(a, b);
+            if ab == Ordering::Greater {
+                continue;
+            }
+
+            for c in items {
+                let bc = compare(b, c);
+                if bc == ab && compare(a, c) != ab {
+                    return Err(format!(
+                        "{a:?} {ab:?} {b:?} and {b:?} {bc:?} {c:?}, but {a:?} {:?} {c:?}.",
+                        compare(a, c)
+                    ));
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::cmp::Ordering;
+
+    use super::{check_comparator, Cycle, Graph};
+    use crate::template::property::{self, int, vec_of};
+
+    fn example() -> Graph<u32> {
+        [
+            (97, 13),
+            (97, 47),
+            (75, 29),
+            (29, 13),
+            (97, 29),
+            (47, 13),
+            (75, 47),
+            (97, 75),
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    #[test]
+    fn sorts_topologically() {
+        let rules = example();
+        assert_eq!(rules.len(), 5);
+        assert_eq!(rules.edge_count(), 8);
+        assert_eq!(rules.topological_sort(), Ok(vec![97, 75, 47, 29, 13]));
+
+        let update = rules.induced(&[13, 47, 97]);
+        assert_eq!(update.nodes(), [13, 47, 97]);
+        assert_eq!(update.edge_count(), 3);
+        assert_eq!(update.topological_sort(), Ok(vec![97, 47, 13]));
+        assert!(update.has_unique_order(&[97, 47, 13]));
+
+        // 29 and 47 are not ordered relative to each other.
+        let order = rules.topological_sort().unwrap();
+        assert!(rules.is_ordered(&order));
+        assert!(!rules.has_unique_order(&order));
+        assert_eq!(rules.fixed_at(1), Some(&75));
+        assert_eq!(rules.fixed_at(4), Some(&13));
+        assert_eq!(rules.fixed_at(2), None);
+        assert_eq!(rules.fixed_at(5), None);
+        assert!(rules.is_ordered(&[75, 13, 1]));
+        assert!(!rules.is_ordered(&[29, 75]));
+    }
+
+    #[test]
+    fn reports_cycles() {
+        let mut rules = example();
+        rules.add_edge(13, 75);
+        rules.add_edge(1, 97);
+
+        let cycle = rules.topological_sort().unwrap_err().nodes;
+        assert_eq!(cycle.len(), 3, "{cycle:?}");
+        for (i, node) in cycle.iter().enumerate() {
+            assert!(rules.has_edge(node, &cycle[(i + 1) % 3]), "{cycle:?}");
+        }
+        assert_eq!(
+            Cycle { nodes: vec![1, 2] }.to_string(),
+            "cycle: 1 -> 2 -> 1"
+        );
+
+        let looped: Graph<u32> = [(1, 1)].into_iter().collect();
+        assert_eq!(looped.topological_sort(), Err(Cycle { nodes: vec![1] }));
+    }
+
+    #[test]
+    fn checks_comparators() {
+        let rules = example();
+        let compare = |a: &u32, b: &u32| rules.compare(a, b);
+
+        assert_eq!(check_comparator(&[97, 47, 13], compare), Ok(()));
+        assert_eq!(check_comparator(&[1, 2, 3], u32::cmp), Ok(()));
+
+        // 75 is before 29 and 29 before 13, but there is no rule for 75 and 13.
+        assert!(check_comparator(&[29, 47, 13, 75], compare).is_err());
+
+        let rock_paper_scissors: Graph<u32> = [(1, 2), (2, 3), (3, 1)].into_iter().collect();
+        assert!(check_comparator(&[1, 2, 3], |a, b| rock_paper_scissors.compare(a, b)).is_err());
+        assert!(check_comparator(&[1, 2], |_, _| Ordering::Less).is_err());
+    }
+
+    #[test]
+    fn sorts_random_graphs() {
+        let edges = vec_of((int(0..=15), int(0..=15)), 0..=40);
+
+        property::check(&edges, |edges| {
+            let graph: Graph<i64> = edges.iter().copied().collect();
+
+            match graph.topological_sort() {
+                Ok(order) => {
+                    let acyclic = edges.iter().all(|&(a, b)| {
+                        order.iter().position(|&n| n == a) < order.iter().position(|&n| n == b)
+                    });
+                    if !acyclic || order.len() != graph.len() {
+                        return Err(format!("invalid order {order:?}"));
+                    }
+                }
+                Err(Cycle { nodes }) => {
+                    let closed = nodes.iter().zip(nodes.iter().cycle().skip(1));
+                    if nodes.is_empty() || !closed.clone().all(|(a, b)| graph.has_edge(a, b)) {
+                        return Err(format!("invalid cycle {nodes:?}"));
+                    }
+                }
+            }
+            Ok(())
+        });
+    }
+}
@@ -0,0 +1,638 @@
+/// Image export of recorded frames, see [`visualize`](crate::template::visualize).
+/// Frames are written as PNG sequences or animated GIFs to `data/renders/NN/`, each cell
+/// drawn as a square of the colour its palette assigns to the cell type.
+///
+/// Both encoders are self-contained: PNG uses a deflate stream with fixed Huffman codes,
+/// GIF uses LZW and only stores the part of each frame that changed.
+use std::{collections::HashMap, fmt::Display, fs, path::PathBuf, str::FromStr};
+
+use crate::template::{
+    visualize::{Frame, Rgb},
+    Day,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Png,
+    Gif,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "png" => Ok(Self::Png),
+            "gif" => Ok(Self::Gif),
+            _ => Err(format!(
+                "unknown image format `{s}`, expected `png` or `gif`."
+            )),
+        }
+    }
+}
+
+impl Display for Format {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Png => write!(f, "png"),
+            Self::Gif => write!(f, "gif"),
+        }
+    }
+}
+
+/// The edge length of a cell in pixels if `--scale` is not passed.
+pub const DEFAULT_SCALE: usize = 4;
+
+#[must_use]
+pub fn get_render_dir(day: Day) -> PathBuf {
+    PathBuf::from(format!("data/renders/{day}"))
+}
+
+/// An image with one colour per pixel.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<Rgb>,
+}
+
+impl Image {
+    /// Draws every cell as a `scale` x `scale` square.
+    pub fn from_frame(frame: &Frame, scale: usize) -> Self {
+        let width = frame.width() * scale;
+        let height = frame.height() * scale;
+        let palette = frame.palette();
+        let mut pixels = Vec::with_capacity(width * height);
+
+        for y in 0..height {
+            for x in 0..width {
+                let kind = frame.get(x / scale, y / scale).unwrap_or(b' ');
+                pixels.push(palette.fill(kind));
+            }
+        }
+
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+}
+
+/// Writes the frames of a part to `data/renders/NN/`: a numbered PNG per frame in `part-N/`,
+/// or a single animated `part-N.gif` played at `fps`. Returns the path written to.
+pub fn export(
+    day: Day,
+    part: u8,
+    frames: &[Frame],
+    format: Format,
+    scale: usize,
+    fps: f64,
+) -> Result<PathBuf, String> {
+    let dir = get_render_dir(day);
+    let images: Vec<Image> = frames
+        .iter()
+        .map(|frame| Image::from_frame(frame, scale))
+        .collect();
+
+    match format {
+        Format::Png => {
+            let dir = dir.join(format!("part-{part}"));
+            fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+
+            // remove the frames of a previous, possibly longer, recording.
+            for entry in fs::read_dir(&dir).map_err(|e| e.to_string())?.flatten() {
+                if entry.path().extension().is_some_and(|ext| ext == "png") {
+                    fs::remove_file(entry.path()).map_err(|e| e.to_string())?;
+                }
+            }
+
+            for (i, image) in images.iter().enumerate() {
+                fs::write(dir.join(format!("{:04}.png", i + 1)), encode_png(image))
+                    .map_err(|e| e.to_string())?;
+            }
+
+            Ok(dir)
+        }
+        Format::Gif => {
+            fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+            let path = dir.join(format!("part-{part}.gif"));
+
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            let delay = (100.0 / fps).round().clamp(1.0, f64::from(u16::MAX)) as u16;
+            fs::write(&path, encode_gif(&images, delay)?).map_err(|e| e.to_string())?;
+
+            Ok(path)
+        }
+    }
+}
+
+/* ---------------------------------- PNG ----------------------------------- */
+
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for byte in bytes {
+        crc ^= u32::from(*byte);
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+fn adler32(bytes: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for chunk in bytes.chunks(5552) {
+        for byte in chunk {
+            a += u32::from(*byte);
+            b += a;
+        }
+        a %= 65521;
+        b %= 65521;
+    }
+    (b << 16) | a
+}
+
+/// Writes bits least significant bit first, as deflate and GIF expect.
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    buffer: u32,
+    len: u32,
+}
+
+impl BitWriter {
+    fn write(&mut self, value: u32, bits: u32) {
+        self.buffer |= value << self.len;
+        self.len += bits;
+        while self.len >= 8 {
+            self.bytes.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.len -= 8;
+        }
+    }
+
+    /// Huffman codes are defined most significant bit first.
+    fn write_reversed(&mut self, code: u32, bits: u32) {
+        self.write(code.reverse_bits() >> (32 - bits), bits);
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.len > 0 {
+            self.bytes.push(self.buffer as u8);
+        }
+        self.bytes
+    }
+}
+
+const LENGTH_BASES: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+const DISTANCE_BASES: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DISTANCE_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+
+/// Writes a literal/length symbol with the fixed Huffman code of deflate.
+fn write_symbol(writer: &mut BitWriter, symbol: u16) {
+    let symbol = u32::from(symbol);
+    match symbol {
+        0..=143 => writer.write_reversed(0x30 + symbol, 8),
+        144..=255 => writer.write_reversed(0x190 + symbol - 144, 9),
+        256..=279 => writer.write_reversed(symbol - 256, 7),
+        _ => writer.write_reversed(0xc0 + symbol - 280, 8),
+    }
+}
+
+fn write_match(writer: &mut BitWriter, length: usize, distance: usize) {
+    let code = LENGTH_BASES
+        .iter()
+        .rposition(|&base| usize::from(base) <= length)
+        .unwrap();
+    write_symbol(writer, 257 + code as u16);
+    writer.write(
+        (length - usize::from(LENGTH_BASES[code])) as u32,
+        u32::from(LENGTH_EXTRA[code]),
+    );
+
+    let code = DISTANCE_BASES
+        .iter()
+        .rposition(|&base| usize::from(base) <= distance)
+        .unwrap();
+    writer.write_reversed(code as u32, 5);
+    writer.write(
+        (distance - usize::from(DISTANCE_BASES[code])) as u32,
+        u32::from(DISTANCE_EXTRA[code]),
+    );
+}
+
+/// Compresses `data` into a single deflate block with fixed Huffman codes, finding repeats
+/// with a hash of the next three bytes.
+fn deflate(data: &[u8]) -> Vec<u8> {
+    const WINDOW: usize = 32768;
+    const MAX_LENGTH: usize = 258;
+
+    let mut writer = BitWriter::default();
+    // final block, fixed Huffman codes.
+    writer.write(0b011, 3);
+
+    let mut last_seen: HashMap<[u8; 3], usize> = HashMap::new();
+    let mut i = 0;
+
+    while i < data.len() {
+        let mut best = None;
+
+        if i + 3 <= data.len() {
+            let key = [data[i], data[i + 1], data[i + 2]];
+            if let Some(&candidate) = last_seen.get(&key) {
+                if i - candidate <= WINDOW {
+                    let length = data[i..]
+                        .iter()
+                        .zip(&data[candidate..])
+                        .take(MAX_LENGTH)
+                        .take_while(|(a, b)| a == b)
+                        .count();
+                    best = Some((length, i - candidate));
+                }
+            }
+            last_seen.insert(key, i);
+        }
+
+        match best {
+            Some((length, distance)) if length >= 3 => {
+                write_match(&mut writer, length, distance);
+                for j in i + 1..(i + length).min(data.len().saturating_sub(2)) {
+                    last_seen.insert([data[j], data[j + 1], data[j + 2]], j);
+                }
+                i += length;
+            }
+            _ => {
+                write_symbol(&mut writer, u16::from(data[i]));
+                i += 1;
+            }
+        }
+    }
+
+    write_symbol(&mut writer, 256);
+    writer.finish()
+}
+
+fn zlib(data: &[u8]) -> Vec<u8> {
+    let mut output = vec![0x78, 0x01];
+    output.extend(deflate(data));
+    output.extend(adler32(data).to_be_bytes());
+    output
+}
+
+fn write_chunk(output: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    output.extend((data.len() as u32).to_be_bytes());
+    let start = output.len();
+    output.extend(kind);
+    output.extend(data);
+    let crc = crc32(&output[start..]);
+    output.extend(crc.to_be_bytes());
+}
+
+/// Encodes an 8-bit RGB PNG.
+pub fn encode_png(image: &Image) -> Vec<u8> {
+    let mut output = b"\x89PNG\r\n\x1a\n".to_vec();
+
+    let mut header = vec![];
+    header.extend((image.width as u32).to_be_bytes());
+    header.extend((image.height as u32).to_be_bytes());
+    // bit depth 8, colour type RGB, default compression, filter and no interlacing.
+    header.extend([8, 2, 0, 0, 0]);
+    write_chunk(&mut output, b"IHDR", &header);
+
+    let mut raw = Vec::with_capacity((image.width * 3 + 1) * image.height);
+    for row in image.pixels.chunks(image.width.max(1)) {
+        // no filter.
+        raw.push(0);
+        for Rgb(r, g, b) in row {
+            raw.extend([*r, *g, *b]);
+        }
+    }
+    write_chunk(&mut output, b"IDAT", &zlib(&raw));
+    write_chunk(&mut output, b"IEND", &[]);
+
+    output
+}
+
+/* ---------------------------------- GIF ----------------------------------- */
+
+/// Compresses colour indices with the variable-length LZW codes of GIF.
+fn lzw(indices: &[u8], min_code_size: u32) -> Vec<u8> {
+    let clear = 1u16 << min_code_size;
+    let end = clear + 1;
+
+    let mut writer = BitWriter::default();
+    let mut table: HashMap<(u16, u8), u16> = HashMap::new();
+    let mut next = end + 1;
+    let mut size = min_code_size + 1;
+
+    writer.write(u32::from(clear), size);
+
+    let mut prefix: Option<u16> = None;
+    for &index in indices {
+        let Some(current) = prefix else {
+            prefix = Some(u16::from(index));
+            continue;
+        };
+
+        if let Some(&code) = table.get(&(current, index)) {
+            prefix = Some(code);
+            continue;
+        }
+
+        writer.write(u32::from(current), size);
+
+        if next < 4096 {
+            table.insert((current, index), next);
+            next += 1;
+            if u32::from(next) > 1 << size && size < 12 {
+                size += 1;
+            }
+        } else {
+            writer.write(u32::from(clear), size);
+            table.clear();
+            next = end + 1;
+            size = min_code_size + 1;
+        }
+
+        prefix = Some(u16::from(index));
+    }
+
+    if let Some(code) = prefix {
+        writer.write(u32::from(code), size);
+        // the decoder adds an entry for this code too, which may widen the end code.
+        if next < 4096 && u32::from(next + 1) > 1 << size && size < 12 {
+            size += 1;
+        }
+    }
+    writer.write(u32::from(end), size);
+
+    writer.finish()
+}
+
+/// The smallest rectangle containing all pixels that differ from `previous`,
+/// as `(left, top, width, height)`.
+fn changed_area(image: &Image, previous: Option<&Image>) -> Option<(usize, usize, usize, usize)> {
+    let Some(previous) = previous else {
+        return Some((0, 0, image.width, image.height));
+    };
+
+    let (mut left, mut top, mut right, mut bottom) = (usize::MAX, usize::MAX, 0, 0);
+    for y in 0..image.height {
+        for x in 0..image.width {
+            let i = y * image.width + x;
+            if image.pixels[i] != previous.pixels[i] {
+                left = left.min(x);
+                top = top.min(y);
+                right = right.max(x);
+                bottom = bottom.max(y);
+            }
+        }
+    }
+
+    (left != usize::MAX).then(|| (left, top, right - left + 1, bottom - top + 1))
+}
+
+/// Encodes an animated GIF that loops forever. `delay` is the time per frame in hundredths of
+/// a second. All images must have the same size and use at most 256 colours in total.
+pub fn encode_gif(images: &[Image], delay: u16) -> Result<Vec<u8>, String> {
+    let first = images.first().ok_or("no frames to encode.")?;
+    let (width, height) = (first.width, first.height);
+    if images
+        .iter()
+        .any(|image| (image.width, image.height) != (width, height))
+    {
+        return Err("all frames of a GIF need to have the same size.".into());
+    }
+    let dimensions = (u16::try_from(width), u16::try_from(height));
+    let (Ok(width_u16), Ok(height_u16)) = dimensions else {
+        return Err(format!(
+            "{width}x{height} pixels are too large for a GIF, use a smaller scale."
+        ));
+    };
+
+    let mut colors: Vec<Rgb> = vec![];
+    let mut color_indices: HashMap<Rgb, u8> = HashMap::new();
+    for pixel in images.iter().flat_map(|image| &image.pixels) {
+        if !color_indices.contains_key(pixel) {
+            if colors.len() == 256 {
+                return Err("GIFs can use at most 256 colours.".into());
+            }
+            color_indices.insert(*pixel, colors.len() as u8);
+            colors.push(*pixel);
+        }
+    }
+
+    // the colour table has 2^(bits) entries, with at least two bits for LZW.
+    let bits = (usize::BITS - (colors.len().max(4) - 1).leading_zeros()).max(2);
+
+    let mut output = b"GIF89a".to_vec();
+    output.extend(width_u16.to_le_bytes());
+    output.extend(height_u16.to_le_bytes());
+    output.push(0x80 | (7 << 4) | (bits as u8 - 1));
+    output.extend([0, 0]);
+    for i in 0..1 << bits {
+        let Rgb(r, g, b) = colors.get(i).copied().unwrap_or(Rgb(0, 0, 0));
+        output.extend([r, g, b]);
+    }
+
+    // loop forever.
+    output.extend(b"\x21\xff\x0bNETSCAPE2.0\x03\x01\x00\x00\x00");
+
+    let mut previous = None;
+    let mut pending_delay = 0u16;
+
+    for (i, image) in images.iter().enumerate() {
+        pending_delay = pending_delay.saturating_add(delay);
+
+        // frames without changes extend the delay of the previous frame, except the last one.
+        let area =
+            changed_area(image, previous).or((i + 1 == images.len()).then_some((0, 0, 1, 1)));
+        let Some((left, top, area_width, area_height)) = area else {
+            continue;
+        };
+
+        // graphic control extension: keep the previous frame, then wait for the delay.
+        output.extend([0x21, 0xf9, 0x04, 0x04]);
+        output.extend(pending_delay.to_le_bytes());
+        output.extend([0, 0]);
+        pending_delay = 0;
+
+        output.push(0x2c);
+        for value in [left, top, area_width, area_height] {
+            output.extend((value as u16).to_le_bytes());
+        }
+        output.push(0);
+
+        let indices: Vec<u8> = (top..top + area_height)
+            .flat_map(|y| (left..left + area_width).map(move |x| (x, y)))
+            .map(|(x, y)| color_indices[&image.pixels[y * width + x]])
+            .collect();
+
+        output.push(bits as u8);
+        for block in lzw(&indices, bits).chunks(255) {
+            output.push(block.len() as u8);
+            output.extend(block);
+        }
+        output.push(0);
+
+        previous = Some(image);
+    }
+
+    output.push(0x3b);
+    Ok(output)
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{adler32, crc32, encode_gif, encode_png, lzw, Format, Image};
+    use crate::template::visualize::{Frame, Palette, Rgb, Style};
+
+    const PALETTE: Palette = Palette::new(
+        Rgb(0, 0, 0),
+        &[
+            (b'.', Style::new(' ', Rgb(9, 9, 9))),
+            (b'#', Style::new('#', Rgb(255, 0, 0))),
+        ],
+    );
+
+    /// Decodes GIF LZW codes, following the decoder described in the GIF specification.
+    fn unlzw(bytes: &[u8], min_code_size: u32) -> Vec<u8> {
+        let clear = 1usize << min_code_size;
+        let end = clear + 1;
+        let mut table: Vec<Vec<u8>> = vec![];
+        let mut size = min_code_size + 1;
+        let mut previous: Option<Vec<u8>> = None;
+        let mut output = vec![];
+        let (mut buffer, mut len, mut bytes) = (0u32, 0u32, bytes.iter());
+
+        loop {
+            while len < size {
+                buffer |= u32::from(*bytes.next().unwrap()) << len;
+                len += 8;
+            }
+            let code = (buffer & ((1 << size) - 1)) as usize;
+            buffer >>= size;
+            len -= size;
+
+            if code == clear {
+                table = (0..clear).map(|i| vec![i as u8]).collect();
+                table.extend([vec![], vec![]]);
+                size = min_code_size + 1;
+                previous = None;
+                continue;
+            }
+            if code == end {
+                return output;
+            }
+
+            let entry = match (table.get(code), &previous) {
+                (Some(entry), _) => entry.clone(),
+                (None, Some(previous)) => [previous.clone(), vec![previous[0]]].concat(),
+                (None, None) => panic!("invalid code {code}"),
+            };
+            output.extend(&entry);
+
+            if let Some(previous) = previous {
+                if table.len() < 4096 {
+                    table.push([previous, vec![entry[0]]].concat());
+                    if table.len() == 1 << size && size < 12 {
+                        size += 1;
+                    }
+                }
+            }
+            previous = Some(entry);
+        }
+    }
+
+    #[test]
+    fn computes_checksums() {
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+    }
+
+    #[test]
+    fn roundtrips_lzw() {
+        for min_code_size in [2, 3, 4, 8] {
+            let colors = 1u32 << min_code_size;
+            let random: Vec<u8> = (0..20_100u32)
+                .map(|i| ((i.wrapping_mul(2_654_435_761) >> 13) % colors) as u8)
+                .collect();
+            let runs: Vec<u8> = (0..20_100u32).map(|i| ((i / 30) % colors) as u8).collect();
+
+            // every length, so the end code sometimes ends exactly on a byte boundary.
+            for data in [&random, &runs] {
+                for len in 0..300 {
+                    let data = &data[..len * 67];
+                    assert_eq!(
+                        unlzw(&lzw(data, min_code_size), min_code_size),
+                        data,
+                        "{min_code_size} {len}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn encodes_png() {
+        let mut frame = Frame::new(30, 20, b'.', &PALETTE);
+        frame.set(2, 3, b'#');
+        let image = Image::from_frame(&frame, 4);
+        assert_eq!((image.width, image.height), (120, 80));
+        assert_eq!(image.pixels[3 * 4 * 120 + 2 * 4], Rgb(255, 0, 0));
+
+        let png = encode_png(&image);
+        assert!(png.starts_with(b"\x89PNG\r\n\x1a\n\x00\x00\x00\x0dIHDR"));
+        assert!(png.ends_with(b"IEND\xae\x42\x60\x82"));
+        // a mostly uniform image compresses well.
+        assert!(png.len() < 120 * 80 * 3 / 20);
+    }
+
+    #[test]
+    fn encodes_gif() {
+        let mut frame = Frame::new(4, 4, b'.', &PALETTE);
+        let first = Image::from_frame(&frame, 2);
+        frame.set(1, 1, b'#');
+        let second = Image::from_frame(&frame, 2);
+
+        let gif = encode_gif(&[first.clone(), first.clone(), second], 10).unwrap();
+        assert!(gif.starts_with(b"GIF89a\x08\x00\x08\x00"));
+        assert!(gif.ends_with(b"\x3b"));
+        // the unchanged frame extends the delay of the first one.
+        assert_eq!(
+            gif.windows(4).filter(|w| w == b"\x21\xf9\x04\x04").count(),
+            2
+        );
+        assert!(gif.windows(6).any(|w| w == b"\x21\xf9\x04\x04\x14\x00"));
+        // only the changed 2x2 pixels are stored for the last frame.
+        assert!(gif
+            .windows(9)
+            .any(|w| w == b"\x2c\x02\x00\x02\x00\x02\x00\x02\x00"));
+
+        assert!(encode_gif(&[], 10).is_err());
+    }
+
+    #[test]
+    fn parses_formats() {
+        assert_eq!("gif".parse(), Ok(Format::Gif));
+        assert_eq!("png".parse(), Ok(Format::Png));
+        assert!("jpg".parse::<Format>().is_err());
+    }
+}
@@ -0,0 +1,284 @@
+/// Selects and loads the input a solution runs against.
+use std::{
+    env,
+    fmt::Display,
+    io::{self, Read},
+    path::Path,
+};
+
+use crate::template::{encryption, examples::Manifest, params::RawParams, Day};
+
+/// Where the input of a solution comes from.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum InputSource {
+    /// The real puzzle input, `data/inputs/NN.txt`.
+    #[default]
+    Puzzle,
+    /// An arbitrary file, `--input <path>`.
+    File(String),
+    /// Standard input, `--stdin`.
+    Stdin,
+    /// An example by its one-based number, `--example [N]`.
+    Example(usize),
+}
+
+impl InputSource {
+    /// Parses the input source from the arguments passed to the current process.
+    pub fn from_args() -> Result<Self, String> {
+        let args: Vec<String> = env::args().skip(1).collect();
+        Self::parse(&args)
+    }
+
+    /// Parses `--input <path>`, `--stdin` or `--example [N]` from a list of arguments.
+    pub fn parse(args: &[String]) -> Result<Self, String> {
+        let mut sources = vec![];
+
+        for (i, arg) in args.iter().enumerate() {
+            match arg.as_str() {
+                "--input" => {
+                    let path = args.get(i + 1).ok_or("expected a path after `--input`.")?;
+                    sources.push(Self::File(path.clone()));
+                }
+                "--stdin" => sources.push(Self::Stdin),
+                "--example" => {
+                    let number = match args.get(i + 1) {
+                        Some(n) if !n.starts_with('-') => n
+                            .parse()
+                            .ok()
+                            .filter(|n| *n > 0)
+                            .ok_or(format!("invalid example number `{n}`."))?,
+                        _ => 1,
+                    };
+                    sources.push(Self::Example(number));
+                }
+                _ => {}
+            }
+        }
+
+        match sources.len() {
+            0 => Ok(Self::Puzzle),
+            1 => Ok(sources.remove(0)),
+            _ => Err("only one of `--input`, `--stdin` and `--example` can be used.".into()),
+        }
+    }
+
+    /// Arguments that select this source in a solution binary.
+    pub fn to_args(&self) -> Vec<String> {
+        match self {
+            Self::Puzzle => vec![],
+            Self::File(path) => vec!["--input".into(), path.clone()],
+            Self::Stdin => vec!["--stdin".into()],
+            Self::Example(number) => vec!["--example".into(), number.to_string()],
+        }
+    }
+
+    pub fn is_puzzle(&self) -> bool {
+        matches!(self, Self::Puzzle)
+    }
+
+    /// Loads the input and the parameters declared for it.
+    /// The input is normalised and rejected if it is empty or not a puzzle input, see [`check`].
+    pub fn read(&self, day: Day) -> Result<(String, RawParams), String> {
+        let (input, params) = self.read_raw(day)?;
+        let input = normalize(&input);
+
+        if let Err(problem) = check(&input) {
+            let hint = match self {
+                Self::Puzzle => format!(" Run `cargo download {day}` to fetch it."),
+                Self::Example(_) => format!(" Add the example to \"data/examples/{day}.txt\"."),
+                Self::File(_) | Self::Stdin => String::new(),
+            };
+            return Err(format!("{self} {problem}.{hint}"));
+        }
+
+        Ok((input, params))
+    }
+
+    fn read_raw(&self, day: Day) -> Result<(String, RawParams), String> {
+        match self {
+            Self::Puzzle => {
+                let path = format!("data/inputs/{day}.txt");
+                read_path(&path)
+                    .map(|input| (input, RawParams::default()))
+                    .map_err(|e| {
+                        format!(
+                            "could not open input file \"{path}\": {e}. Run `cargo download {day}` to fetch it."
+                        )
+                    })
+            }
+            Self::File(path) => read_path(path)
+                .map(|input| (input, RawParams::default()))
+                .map_err(|e| format!("could not open input file \"{path}\": {e}.")),
+            Self::Stdin => {
+                let mut input = String::new();
+                io::stdin()
+                    .read_to_string(&mut input)
+                    .map_err(|e| format!("could not read input from stdin: {e}."))?;
+                Ok((input, RawParams::default()))
+            }
+            Self::Example(number) => read_example(day, *number),
+        }
+    }
+}
+
+impl Display for InputSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Puzzle => write!(f, "puzzle input"),
+            Self::File(path) => write!(f, "\"{path}\""),
+            Self::Stdin => write!(f, "stdin"),
+            Self::Example(number) => write!(f, "example #{number}"),
+        }
+    }
+}
+
+/// Responses of the website that end up in an input file when downloading fails.
+const PLACEHOLDERS: [&str; 5] = [
+    "Puzzle inputs differ by user.",
+    "Please don't repeatedly request this endpoint before it unlocks!",
+    "404 Not Found",
+    "<!DOCTYPE html",
+    "<html",
+];
+
+/// Removes a byte order mark, converts CRLF line endings and removes blank lines at the end,
+/// so every input ends with exactly one newline like the real puzzle inputs do.
+pub fn normalize(input: &str) -> String {
+    let input = input.strip_prefix('\u{feff}').unwrap_or(input);
+    let mut normalized = input.replace("\r\n", "\n");
+
+    // keep trailing spaces of the last line, they may be part of a grid.
+    let content_end = normalized.trim_end().len();
+    let line_end = normalized[content_end..]
+        .find('\n')
+        .map_or(normalized.len(), |i| content_end + i);
+    normalized.truncate(line_end);
+
+    if !normalized.is_empty() {
+        normalized.push('\n');
+    }
+
+    normalized
+}
+
+/// Detects inputs that can not be puzzle inputs: empty files, e.g. left by `cargo scaffold`,
+/// and error pages of the website.
+pub fn check(input: &str) -> Result<(), String> {
+    if input.trim().is_empty() {
+        return Err("is empty".into());
+    }
+
+    if let Some(placeholder) = PLACEHOLDERS
+        .iter()
+        .find(|placeholder| input.trim_start().starts_with(*placeholder))
+    {
+        return Err(format!(
+            "is not a puzzle input, it starts with \"{placeholder}\""
+        ));
+    }
+
+    Ok(())
+}
+
+fn read_path(path: impl AsRef<Path>) -> Result<String, io::Error> {
+    encryption::read_to_string(&env::current_dir()?.join(path))
+}
+
+/// Examples are taken from the manifest if present, otherwise `NN.txt` is the only example.
+/// `NN-N.txt` is not used, as that is the name of the example for part N, see [`read_file_part`].
+///
+/// [`read_file_part`]: crate::template::read_file_part
+fn read_example(day: Day, number: usize) -> Result<(String, RawParams), String> {
+    if let Some(manifest) = Manifest::read(day)? {
+        let count = manifest.examples.len();
+        let example = manifest
+            .examples
+            .into_iter()
+            .nth(number - 1)
+            .ok_or(format!(
+                "day {day} has {count} example(s), there is no example #{number}."
+            ))?;
+        return Ok((example.input, example.params));
+    }
+
+    if number > 1 {
+        return Err(format!(
+            "day {day} has no example manifest, so \"data/examples/{day}.txt\" is its only example. \
+            Declare more examples in \"data/examples/{day}.json\"."
+        ));
+    }
+
+    let path = format!("data/examples/{day}.txt");
+    read_path(&path)
+        .map(|input| (input, RawParams::default()))
+        .map_err(|e| format!("could not open example file \"{path}\": {e}."))
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{check, normalize, InputSource};
+
+    fn parse(args: &[&str]) -> Result<InputSource, String> {
+        let args: Vec<String> = args.iter().map(|s| s.to_string()).collect();
+        InputSource::parse(&args)
+    }
+
+    #[test]
+    fn defaults_to_puzzle_input() {
+        assert_eq!(parse(&["--time"]), Ok(InputSource::Puzzle));
+    }
+
+    #[test]
+    fn parses_sources() {
+        assert_eq!(
+            parse(&["--input", "foo.txt"]),
+            Ok(InputSource::File("foo.txt".into()))
+        );
+        assert_eq!(parse(&["--stdin"]), Ok(InputSource::Stdin));
+        assert_eq!(parse(&["--example"]), Ok(InputSource::Example(1)));
+        assert_eq!(parse(&["--example", "--time"]), Ok(InputSource::Example(1)));
+        assert_eq!(parse(&["--example", "3"]), Ok(InputSource::Example(3)));
+    }
+
+    #[test]
+    fn roundtrips_args() {
+        for source in [
+            InputSource::Puzzle,
+            InputSource::File("foo.txt".into()),
+            InputSource::Stdin,
+            InputSource::Example(2),
+        ] {
+            let args = source.to_args();
+            assert_eq!(InputSource::parse(&args), Ok(source));
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_args() {
+        assert!(parse(&["--input"]).is_err());
+        assert!(parse(&["--example", "0"]).is_err());
+        assert!(parse(&["--example", "x"]).is_err());
+        assert!(parse(&["--stdin", "--example"]).is_err());
+    }
+
+    #[test]
+    fn normalizes_inputs() {
+        assert_eq!(normalize("\u{feff}1\r\n2\r\n"), "1\n2\n");
+        assert_eq!(normalize("1\n2"), "1\n2\n");
+        assert_eq!(normalize("1\n2\n\n  \n"), "1\n2\n");
+        assert_eq!(normalize("#.\n.# \n"), "#.\n.# \n");
+        assert_eq!(normalize("\n\n"), "");
+    }
+
+    #[test]
+    fn rejects_invalid_inputs() {
+        assert!(check("1\n2\n").is_ok());
+        assert_eq!(check(""), Err("is empty".into()));
+        assert_eq!(check(" \n"), Err("is empty".into()));
+        assert!(
+            check("Puzzle inputs differ by user.  Please log in to get your puzzle input.\n")
+                .is_err()
+        );
+        assert!(check("<!DOCTYPE html>\n<html lang=\"en-us\">").is_err());
+    }
+}
@@ -0,0 +1,314 @@
+/// A lexer for instructions hidden in noisy text, like day 03's `mul(2,4)` in corrupted memory.
+/// Tokens are declared as a name followed by delimited arguments, everything else is skipped:
+///
+/// ```ignore
+/// use advent_of_code::template::lexer::{Arg, Grammar};
+///
+/// let grammar = Grammar::new()
+///     .token(Op::Mul, "mul", [Arg::Number(1..=3), Arg::Number(1..=3)])
+///     .token(Op::Do, "do", []);
+///
+/// for token in grammar.tokens(input) {
+///     println!("{:?} {:?} at {:?}", token.kind, token.args, token.span);
+/// }
+/// ```
+///
+/// Tokens are produced lazily while scanning. At each position only the tokens whose name starts
+/// with the current byte are tried, and scanning resumes after the end of a match or one byte
+/// further after a failed match, so a failed `mul(` never hides a token that starts inside it.
+use std::ops::{Range, RangeInclusive};
+
+/// The pattern of a token argument.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Arg {
+    /// A decimal number with a number of digits in the range.
+    Number(RangeInclusive<usize>),
+    /// A decimal number with a number of digits in the range, optionally preceded by `-`.
+    Signed(RangeInclusive<usize>),
+}
+
+/// A recognised token and where it is in the input.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Token<K> {
+    pub kind: K,
+    pub args: Vec<i64>,
+    /// The byte range of the token, from the first byte of its name to its closing delimiter.
+    pub span: Range<usize>,
+}
+
+#[derive(Clone, Debug)]
+struct Rule<K> {
+    kind: K,
+    name: &'static str,
+    args: Vec<Arg>,
+}
+
+/// The tokens to recognise, see the module documentation.
+#[derive(Clone, Debug)]
+pub struct Grammar<K> {
+    rules: Vec<Rule<K>>,
+    /// The rules to try for each first byte of a name.
+    starts: Vec<Vec<usize>>,
+    open: &'static str,
+    separator: &'static str,
+    close: &'static str,
+}
+
+impl<K: Copy> Default for Grammar<K> {
+    fn default() -> Self {
+        Self {
+            rules: vec![],
+            starts: vec![vec![]; 256],
+            open: "(",
+            separator: ",",
+            close: ")",
+        }
+    }
+}
+
+impl<K: Copy> Grammar<K> {
+    /// A grammar without tokens, with arguments written as `(a,b)`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Declares a token. When several tokens match at the same position, the first declared wins.
+    ///
+    /// # Panics
+    /// If `name` is empty.
+    pub fn token(
+        mut self,
+        kind: K,
+        name: &'static str,
+        args: impl IntoIterator<Item = Arg>,
+    ) -> Self {
+        assert!(!name.is_empty(), "token names must not be empty.");
+
+        self.starts[usize::from(name.as_bytes()[0])].push(self.rules.len());
+        self.rules.push(Rule {
+            kind,
+            name,
+            args: args.into_iter().collect(),
+        });
+        self
+    }
+
+    /// Changes the delimiters around and between arguments, `(`, `,` and `)` by default.
+    pub fn with_delimiters(
+        mut self,
+        open: &'static str,
+        separator: &'static str,
+        close: &'static str,
+    ) -> Self {
+        self.open = open;
+        self.separator = separator;
+        self.close = close;
+        self
+    }
+
+    /// The tokens of `input`, in order.
+    pub fn tokens<'g, 'i>(&'g self, input: &'i str) -> Tokens<'g, 'i, K> {
+        Tokens {
+            grammar: self,
+            input: input.as_bytes(),
+            position: 0,
+        }
+    }
+
+    /// Matches `rule` at `start`, returning its arguments and end.
+    fn match_rule(&self, rule: &Rule<K>, input: &[u8], start: usize) -> Option<(Vec<i64>, usize)> {
+        let mut cursor = Cursor {
+            input,
+            position: start,
+        };
+
+        cursor.literal(rule.name)?;
+        cursor.literal(self.open)?;
+
+        let mut args = Vec::with_capacity(rule.args.len());
+        for (i, arg) in rule.args.iter().enumerate() {
+            if i > 0 {
+                cursor.literal(self.separator)?;
+            }
+            args.push(cursor.number(arg)?);
+        }
+
+        cursor.literal(self.close)?;
+        Some((args, cursor.position))
+    }
+}
+
+struct Cursor<'i> {
+    input: &'i [u8],
+    position: usize,
+}
+
+impl Cursor<'_> {
+    fn literal(&mut self, literal: &str) -> Option<()> {
+        let end = self.position + literal.len();
+        (self.input.get(self.position..end)? == literal.as_bytes()).then(|| self.position = end)
+    }
+
+    /// Numbers that do not fit in an `i64` do not match.
+    fn number(&mut self, arg: &Arg) -> Option<i64> {
+        let (digits, negative) = match arg {
+            Arg::Number(digits) => (digits, false),
+            Arg::Signed(digits) => (digits, self.literal("-").is_some()),
+        };
+
+        let start = self.position;
+        let mut value: i64 = 0;
+        while let Some(digit) = self.input.get(self.position).filter(|b| b.is_ascii_digit()) {
+            let digit = i64::from(digit - b'0');
+            value = value.checked_mul(10)?.checked_add(digit)?;
+            self.position += 1;
+        }
+
+        if !digits.contains(&(self.position - start)) {
+            return None;
+        }
+        Some(if negative { -value } else { value })
+    }
+}
+
+/// An iterator over the tokens of an input, see [`Grammar::tokens`].
+pub struct Tokens<'g, 'i, K> {
+    grammar: &'g Grammar<K>,
+    input: &'i [u8],
+    position: usize,
+}
+
+impl<K: Copy> Iterator for Tokens<'_, '_, K> {
+    type Item = Token<K>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(&byte) = self.input.get(self.position) {
+            let start = self.position;
+            self.position += 1;
+
+            for &index in &self.grammar.starts[usize::from(byte)] {
+                let rule = &self.grammar.rules[index];
+                if let Some((args, end)) = self.grammar.match_rule(rule, self.input, start) {
+                    self.position = end;
+                    return Some(Token {
+                        kind: rule.kind,
+                        args,
+                        span: start..end,
+                    });
+                }
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Arg, Grammar, Token};
+    use crate::template::property::{self, one_of, vec_of};
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    enum Op {
+        Mul,
+        Do,
+        Dont,
+    }
+
+    fn grammar() -> Grammar<Op> {
+        Grammar::new()
+            .token(Op::Mul, "mul", [Arg::Number(1..=3), Arg::Number(1..=3)])
+            .token(Op::Do, "do", [])
+            .token(Op::Dont, "don't", [])
+    }
+
+    fn kinds(grammar: &Grammar<Op>, input: &str) -> Vec<Op> {
+        grammar.tokens(input).map(|token| token.kind).collect()
+    }
+
+    #[test]
+    fn tokenizes_instructions() {
+        let input = "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
+        let tokens: Vec<Token<Op>> = grammar().tokens(input).collect();
+
+        assert_eq!(
+            tokens.iter().map(|t| t.kind).collect::<Vec<_>>(),
+            [Op::Mul, Op::Dont, Op::Mul, Op::Mul, Op::Do, Op::Mul]
+        );
+        assert_eq!(
+            tokens[0],
+            Token {
+                kind: Op::Mul,
+                args: vec![2, 4],
+                span: 1..9
+            }
+        );
+        assert_eq!(&input[tokens[1].span.clone()], "don't()");
+        assert_eq!(tokens[5].args, [8, 5]);
+    }
+
+    #[test]
+    fn rejects_malformed_tokens() {
+        let grammar = grammar();
+        for input in [
+            "mul(1234,5)",
+            "mul(4*",
+            "mul ( 2 , 4 )",
+            "mul(6,9!",
+            "mul(,1)",
+            "mul(1,2,3)",
+            "mul(-1,2)",
+            "do(1)",
+            "",
+        ] {
+            assert_eq!(kinds(&grammar, input), [], "{input}");
+        }
+
+        // a failed match does not hide a token that starts inside it.
+        let tokens: Vec<_> = grammar.tokens("mulmul(2,3)").collect();
+        assert_eq!(tokens[0].span, 3..11);
+        assert_eq!(kinds(&grammar, "mul(1,mul(2,3)"), [Op::Mul]);
+        assert_eq!(kinds(&grammar, "ädo()ö"), [Op::Do]);
+
+        let large = Grammar::new().token(Op::Mul, "mul", [Arg::Number(1..=30)]);
+        assert_eq!(kinds(&large, "mul(99999999999999999999)"), []);
+        assert_eq!(kinds(&large, "mul(9223372036854775807)"), [Op::Mul]);
+    }
+
+    #[test]
+    fn supports_delimiters_and_signs() {
+        let grammar = Grammar::new()
+            .token(Op::Mul, "jmp", [Arg::Signed(1..=2), Arg::Number(1..=1)])
+            .with_delimiters(" ", ", ", ";");
+
+        let args: Vec<_> = grammar
+            .tokens("jmp -3, 4; jmp 12, 0;jmp --1, 2; jmp 1,2;")
+            .map(|token| token.args)
+            .collect();
+        assert_eq!(args, [vec![-3, 4], vec![12, 0]]);
+    }
+
+    #[test]
+    fn spans_relex_to_themselves() {
+        let fragments = ["mul(", "do()", "don't()", "d", ",", ")", "1", "234", "x"];
+        let grammar = grammar();
+
+        property::check(&vec_of(one_of(&fragments), 0..=30), |fragments| {
+            let input = fragments.concat();
+            let mut end = 0;
+
+            for token in grammar.tokens(&input) {
+                if token.span.start < end {
+                    return Err(format!("overlapping token {token:?} in {input}"));
+                }
+                end = token.span.end;
+
+                let relexed: Vec<_> = grammar.tokens(&input[token.span.clone()]).collect();
+                if relexed.len() != 1 || relexed[0].args != token.args {
+                    return Err(format!("{token:?} relexes to {relexed:?} in {input}"));
+                }
+            }
+            Ok(())
+        });
+    }
+}
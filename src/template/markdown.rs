@@ -0,0 +1,338 @@
+/// Renders markdown, as saved by aoc-cli to `data/puzzles/NN.md`, for the terminal.
+/// Supports the subset used by puzzle descriptions: headings, paragraphs, lists,
+/// fenced code blocks, emphasis, inline code and links.
+use std::{env, process::Command};
+
+use itertools::Itertools;
+
+use crate::template::{ANSI_BOLD, ANSI_RESET};
+
+const ANSI_CODE: &str = "\x1b[36m";
+const ANSI_UNDERLINE: &str = "\x1b[4m";
+const ANSI_DIM: &str = "\x1b[2m";
+
+/// The width of the terminal, falling back to 80 columns.
+pub fn terminal_width() -> usize {
+    terminal_size().0
+}
+
+/// The columns and rows of the terminal, falling back to 80x24.
+/// `COLUMNS` and `LINES` take precedence over the size reported by `stty`.
+pub fn terminal_size() -> (usize, usize) {
+    let from_env = |key| env::var(key).ok().and_then(|value| value.parse().ok());
+
+    let reported = Command::new("stty")
+        .arg("size")
+        .stdin(std::process::Stdio::inherit())
+        .output()
+        .ok()
+        .and_then(|output| {
+            let size = String::from_utf8(output.stdout).ok()?;
+            let (rows, columns) = size.split_whitespace().collect_tuple()?;
+            Some((columns.parse().ok()?, rows.parse().ok()?))
+        });
+
+    (
+        from_env("COLUMNS")
+            .or(reported.map(|(columns, _)| columns))
+            .unwrap_or(80),
+        from_env("LINES")
+            .or(reported.map(|(_, rows)| rows))
+            .unwrap_or(24),
+    )
+}
+
+/// Renders `markdown` with ANSI styles, wrapping paragraphs to `width` columns.
+pub fn render(markdown: &str, width: usize) -> String {
+    let mut renderer = Renderer {
+        output: String::new(),
+        paragraph: String::new(),
+        prefix: ("", ""),
+        width,
+    };
+
+    let lines: Vec<&str> = markdown.lines().collect();
+    let mut in_code = false;
+    let mut i = 0;
+
+    while i < lines.len() {
+        let line = lines[i];
+        let trimmed = line.trim();
+        i += 1;
+
+        if trimmed.starts_with("```") {
+            renderer.flush();
+            in_code = !in_code;
+            continue;
+        }
+
+        if in_code {
+            renderer.push_line(&format!("    {ANSI_CODE}{line}{ANSI_RESET}"));
+            continue;
+        }
+
+        if trimmed.is_empty() {
+            renderer.flush();
+            renderer.blank();
+            continue;
+        }
+
+        // setext headings are underlined by the next line.
+        let is_underline =
+            |l: &str| l.len() >= 3 && (l.chars().all(|c| c == '-') || l.chars().all(|c| c == '='));
+        if renderer.paragraph.is_empty() && lines.get(i).is_some_and(|l| is_underline(l.trim())) {
+            renderer.heading(trimmed);
+            i += 1;
+            continue;
+        }
+
+        if let Some(heading) = trimmed.strip_prefix('#') {
+            renderer.flush();
+            renderer.heading(heading.trim_start_matches('#'));
+            continue;
+        }
+
+        if matches!(trimmed, "* * *" | "***" | "---" | "- - -") {
+            renderer.flush();
+            renderer.push_line(&format!("{ANSI_DIM}{}{ANSI_RESET}", "─".repeat(width)));
+            continue;
+        }
+
+        if let Some(item) = ["* ", "- ", "+ "]
+            .iter()
+            .find_map(|marker| trimmed.strip_prefix(marker))
+        {
+            renderer.flush();
+            renderer.prefix = ("  • ", "    ");
+            renderer.paragraph.push_str(item);
+            continue;
+        }
+
+        if !renderer.paragraph.is_empty() {
+            renderer.paragraph.push(' ');
+        }
+        renderer.paragraph.push_str(trimmed);
+    }
+
+    renderer.flush();
+    renderer.output.trim_end().to_string() + "\n"
+}
+
+struct Renderer {
+    output: String,
+    /// The text of the current paragraph or list item, joined to a single line.
+    paragraph: String,
+    /// Prefix of the first and of the following lines of the current paragraph.
+    prefix: (&'static str, &'static str),
+    width: usize,
+}
+
+impl Renderer {
+    fn push_line(&mut self, line: &str) {
+        self.output.push_str(line);
+        self.output.push('\n');
+    }
+
+    /// Collapses consecutive blank lines.
+    fn blank(&mut self) {
+        if !self.output.is_empty() && !self.output.ends_with("\n\n") {
+            self.output.push('\n');
+        }
+    }
+
+    fn heading(&mut self, text: &str) {
+        let text = inline(text.trim(), "");
+        self.push_line(&format!("{ANSI_BOLD}{text}{ANSI_RESET}"));
+        self.blank();
+    }
+
+    fn flush(&mut self) {
+        if self.paragraph.is_empty() {
+            return;
+        }
+
+        let text = inline(&self.paragraph, "");
+        let wrapped = wrap(&text, self.width, self.prefix.0, self.prefix.1);
+        self.output.push_str(&wrapped);
+        self.paragraph.clear();
+        self.prefix = ("", "");
+    }
+}
+
+/// Renders inline markup. `active` holds the styles of enclosing spans, which are
+/// re-applied after a nested span resets the terminal style.
+fn inline(text: &str, active: &str) -> String {
+    let mut output = String::new();
+    let mut rest = text;
+
+    while let Some(c) = rest.chars().next() {
+        let span = match c {
+            '\\' => rest.chars().nth(1).map(|escaped| {
+                output.push(escaped);
+                1 + escaped.len_utf8()
+            }),
+            '`' => styled(&mut output, rest, "`", ANSI_CODE, active),
+            '*' if rest.starts_with("**") => styled(&mut output, rest, "**", ANSI_BOLD, active),
+            '*' => styled(&mut output, rest, "*", ANSI_BOLD, active),
+            '[' => link(&mut output, rest, active),
+            _ => None,
+        };
+
+        let consumed = span.unwrap_or_else(|| {
+            output.push(c);
+            c.len_utf8()
+        });
+        rest = &rest[consumed..];
+    }
+
+    output
+}
+
+/// Renders a span enclosed by `delimiter`. Returns the number of consumed bytes.
+fn styled(
+    output: &mut String,
+    text: &str,
+    delimiter: &str,
+    style: &str,
+    active: &str,
+) -> Option<usize> {
+    let inner = &text[delimiter.len()..];
+    // like in markdown, spans have to hug their content: `2 * 3 * 4` is not emphasized.
+    if inner.starts_with(char::is_whitespace) {
+        return None;
+    }
+    let end = inner.find(delimiter).filter(|end| *end > 0)?;
+    let nested = format!("{active}{style}");
+
+    output.push_str(style);
+    output.push_str(&inline(&inner[..end], &nested));
+    output.push_str(ANSI_RESET);
+    output.push_str(active);
+
+    Some(delimiter.len() * 2 + end)
+}
+
+/// Renders the text of a `[text](url)` link.
+fn link(output: &mut String, text: &str, active: &str) -> Option<usize> {
+    let mut depth = 0;
+    let label_end = text
+        .char_indices()
+        .skip(1)
+        .find(|&(_, c)| match c {
+            '[' => {
+                depth += 1;
+                false
+            }
+            ']' if depth == 0 => true,
+            ']' => {
+                depth -= 1;
+                false
+            }
+            _ => false,
+        })?
+        .0;
+    // the url has to follow the label directly, otherwise the `[` is just text.
+    if !text[label_end + 1..].starts_with('(') {
+        return None;
+    }
+    let url_end = label_end + text[label_end..].find(')')?;
+    let nested = format!("{active}{ANSI_UNDERLINE}");
+
+    output.push_str(ANSI_UNDERLINE);
+    output.push_str(&inline(&text[1..label_end], &nested));
+    output.push_str(ANSI_RESET);
+    output.push_str(active);
+
+    Some(url_end + 1)
+}
+
+/// The number of visible characters, ignoring ANSI escape sequences.
+fn visible_len(text: &str) -> usize {
+    let mut len = 0;
+    let mut in_escape = false;
+
+    for c in text.chars() {
+        match c {
+            '\x1b' => in_escape = true,
+            'm' if in_escape => in_escape = false,
+            _ if !in_escape => len += 1,
+            _ => {}
+        }
+    }
+
+    len
+}
+
+/// Wraps `text` at word boundaries to lines of at most `width` visible characters.
+fn wrap(text: &str, width: usize, first_prefix: &str, prefix: &str) -> String {
+    let mut output = String::new();
+    let mut line = first_prefix.to_string();
+    let mut line_len = visible_len(first_prefix);
+    let mut line_start = line_len;
+
+    for word in text.split(' ').filter(|word| !word.is_empty()) {
+        let word_len = visible_len(word);
+
+        if line_len > line_start && line_len + 1 + word_len > width {
+            output.push_str(line.trim_end());
+            output.push('\n');
+            line = prefix.to_string();
+            line_len = visible_len(prefix);
+            line_start = line_len;
+        } else if line_len > line_start {
+            line.push(' ');
+            line_len += 1;
+        }
+
+        line.push_str(word);
+        line_len += word_len;
+    }
+
+    output.push_str(&line);
+    output.push('\n');
+    output
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{inline, render, visible_len, wrap, ANSI_CODE};
+    use crate::template::{ANSI_BOLD, ANSI_RESET};
+
+    #[test]
+    fn renders_inline_markup() {
+        assert_eq!(
+            inline("total `*142*`", ""),
+            format!("total {ANSI_CODE}{ANSI_BOLD}142{ANSI_RESET}{ANSI_CODE}{ANSI_RESET}")
+        );
+        assert_eq!(inline("\\--- Day 1 ---", ""), "--- Day 1 ---");
+        assert_eq!(visible_len(&inline("a [link](https://x.y) b", "")), 8);
+        assert_eq!(inline("2 * 3 * 4", ""), "2 * 3 * 4");
+        assert_eq!(
+            visible_len(&inline("[1, 2] and [x](https://x.y)", "")),
+            "[1, 2] and x".len()
+        );
+    }
+
+    #[test]
+    fn wraps_words() {
+        assert_eq!(
+            wrap("one two three four", 9, "  • ", "    "),
+            "  • one\n    two\n    three\n    four\n"
+        );
+        assert_eq!(wrap("one two three", 80, "", ""), "one two three\n");
+    }
+
+    #[test]
+    fn renders_blocks() {
+        let markdown =
+            "\\--- Day 1: Test ---\n----------\n\nSome\ntext.\n\n```\n1 2\n```\n\n* item";
+        let rendered = render(markdown, 80);
+
+        assert_eq!(
+            rendered,
+            format!(
+                "{ANSI_BOLD}--- Day 1: Test ---{ANSI_RESET}\n\nSome text.\n\n    {ANSI_CODE}1 2{ANSI_RESET}\n\n  • item\n"
+            )
+        );
+    }
+}
@@ -0,0 +1,186 @@
+/// Heap allocation tracking for solution parts.
+/// The `solution!` macro installs [`CountingAlloc`] as the global allocator when the `memory` feature is active.
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::fmt::Display;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Whether allocations are being counted in this build.
+pub const ENABLED: bool = cfg!(all(feature = "memory", not(feature = "dhat-heap")));
+
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+static TOTAL_BYTES: AtomicU64 = AtomicU64::new(0);
+static CURRENT_BYTES: AtomicU64 = AtomicU64::new(0);
+static PEAK_BYTES: AtomicU64 = AtomicU64::new(0);
+static BASELINE_BYTES: AtomicU64 = AtomicU64::new(0);
+
+/// A global allocator that forwards to [`System`] while counting allocations.
+pub struct CountingAlloc;
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            record_alloc(layout.size() as u64);
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            record_alloc(layout.size() as u64);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        CURRENT_BYTES.fetch_sub(layout.size() as u64, Ordering::Relaxed);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            CURRENT_BYTES.fetch_sub(layout.size() as u64, Ordering::Relaxed);
+            record_alloc(new_size as u64);
+        }
+        new_ptr
+    }
+}
+
+fn record_alloc(size: u64) {
+    ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+    TOTAL_BYTES.fetch_add(size, Ordering::Relaxed);
+    let current = CURRENT_BYTES.fetch_add(size, Ordering::Relaxed) + size;
+    PEAK_BYTES.fetch_max(current, Ordering::Relaxed);
+}
+
+/// Heap statistics collected while running a single solution part.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MemoryStats {
+    /// Number of allocations (including reallocations).
+    pub allocations: u64,
+    /// Sum of all bytes requested from the allocator.
+    pub total_bytes: u64,
+    /// Highest amount of live heap memory, relative to the start of the measurement.
+    pub peak_bytes: u64,
+}
+
+/// Resets the counters. Memory that is live at this point does not count towards the peak.
+pub fn reset() {
+    let current = CURRENT_BYTES.load(Ordering::Relaxed);
+    ALLOCATIONS.store(0, Ordering::Relaxed);
+    TOTAL_BYTES.store(0, Ordering::Relaxed);
+    PEAK_BYTES.store(current, Ordering::Relaxed);
+    BASELINE_BYTES.store(current, Ordering::Relaxed);
+}
+
+/// Reads the counters accumulated since the last [`reset`].
+pub fn snapshot() -> MemoryStats {
+    MemoryStats {
+        allocations: ALLOCATIONS.load(Ordering::Relaxed),
+        total_bytes: TOTAL_BYTES.load(Ordering::Relaxed),
+        peak_bytes: PEAK_BYTES
+            .load(Ordering::Relaxed)
+            .saturating_sub(BASELINE_BYTES.load(Ordering::Relaxed)),
+    }
+}
+
+/// Formats a byte count with a binary unit, e.g. `1.5 KiB`.
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    format!("{value:.1} {}", UNITS[unit])
+}
+
+/// Parses a byte count formatted by [`format_bytes`].
+pub fn parse_bytes(s: &str) -> Option<u64> {
+    let (value, unit) = s.trim().split_once(' ')?;
+    let multiplier: u64 = match unit {
+        "B" => 1,
+        "KiB" => 1 << 10,
+        "MiB" => 1 << 20,
+        "GiB" => 1 << 30,
+        "TiB" => 1 << 40,
+        _ => return None,
+    };
+
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    Some((value.parse::<f64>().ok()? * multiplier as f64).round() as u64)
+}
+
+impl Display for MemoryStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} peak, {} total, {} allocs",
+            format_bytes(self.peak_bytes),
+            format_bytes(self.total_bytes),
+            self.allocations
+        )
+    }
+}
+
+impl MemoryStats {
+    /// Parses statistics formatted by the [`Display`] implementation.
+    pub fn parse(s: &str) -> Option<Self> {
+        let mut parts = s.split(", ");
+        let peak_bytes = parse_bytes(parts.next()?.strip_suffix(" peak")?)?;
+        let total_bytes = parse_bytes(parts.next()?.strip_suffix(" total")?)?;
+        let allocations = parts.next()?.strip_suffix(" allocs")?.parse().ok()?;
+
+        Some(Self {
+            allocations,
+            total_bytes,
+            peak_bytes,
+        })
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{format_bytes, parse_bytes, MemoryStats};
+
+    #[test]
+    fn formats_bytes() {
+        assert_eq!(format_bytes(0), "0 B");
+        assert_eq!(format_bytes(1023), "1023 B");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(format_bytes(3 * 1024 * 1024), "3.0 MiB");
+    }
+
+    #[test]
+    fn parses_bytes() {
+        assert_eq!(parse_bytes("512 B"), Some(512));
+        assert_eq!(parse_bytes("1.5 KiB"), Some(1536));
+        assert_eq!(parse_bytes("3.0 MiB"), Some(3 * 1024 * 1024));
+        assert_eq!(parse_bytes("3.0 mb"), None);
+    }
+
+    #[test]
+    fn roundtrips_stats() {
+        let stats = MemoryStats {
+            allocations: 12,
+            total_bytes: 4096,
+            peak_bytes: 512,
+        };
+        assert_eq!(stats.to_string(), "512 B peak, 4.0 KiB total, 12 allocs");
+        assert_eq!(MemoryStats::parse(&stats.to_string()), Some(stats));
+    }
+}
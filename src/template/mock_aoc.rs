@@ -0,0 +1,374 @@
+/// An offline stand-in for adventofcode.com, used to test the aoc-cli integration end-to-end.
+/// The server serves inputs and puzzle pages and answers submissions with scripted verdicts.
+/// It is driven by the `mock-aoc` binary, which mimics the aoc-cli command-line and is selected
+/// by pointing `AOC_CLI` at it and `AOC_MOCK_URL` at the server.
+use std::{
+    collections::{HashMap, VecDeque},
+    io::{self, BufRead, BufReader, Read, Write},
+    net::{SocketAddr, TcpListener, TcpStream},
+    sync::{Arc, Mutex},
+    thread,
+};
+
+/// The verdict the server gives for a submitted answer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Response {
+    Correct,
+    TooLow,
+    TooHigh,
+    Incorrect,
+    TooRecent,
+    AlreadySolved,
+}
+
+impl Response {
+    /// The text of the page returned by the website.
+    pub fn text(self) -> &'static str {
+        match self {
+            Self::Correct => "That's the right answer! You are one gold star closer to finding the Chief Historian.",
+            Self::TooLow => "That's not the right answer; your answer is too low. Please wait one minute before trying again.",
+            Self::TooHigh => "That's not the right answer; your answer is too high. Please wait one minute before trying again.",
+            Self::Incorrect => "That's not the right answer. Please wait one minute before trying again.",
+            Self::TooRecent => "You gave an answer too recently; you have to wait after submitting an answer before trying again.",
+            Self::AlreadySolved => "You don't seem to be solving the right level. Did you already complete it?",
+        }
+    }
+}
+
+/// A request received by the server.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    /// The value of the `session` cookie.
+    pub session: Option<String>,
+    pub body: String,
+}
+
+#[derive(Default)]
+struct State {
+    inputs: HashMap<(u16, u8), String>,
+    puzzles: HashMap<(u16, u8), String>,
+    responses: HashMap<(u16, u8, u8), VecDeque<Response>>,
+    requests: Vec<Request>,
+}
+
+/// A mock server listening on a random local port. The server runs until the process exits.
+pub struct MockServer {
+    address: SocketAddr,
+    state: Arc<Mutex<State>>,
+}
+
+impl MockServer {
+    pub fn start() -> io::Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let address = listener.local_addr()?;
+        let state = Arc::new(Mutex::new(State::default()));
+
+        let server_state = Arc::clone(&state);
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                if let Err(e) = handle(stream, &server_state) {
+                    eprintln!("mock server: {e}");
+                }
+            }
+        });
+
+        Ok(Self { address, state })
+    }
+
+    /// The base url, to be passed to the `mock-aoc` binary via `AOC_MOCK_URL`.
+    pub fn url(&self) -> String {
+        format!("http://{}", self.address)
+    }
+
+    pub fn add_input(&self, year: u16, day: u8, input: &str) {
+        self.state().inputs.insert((year, day), input.into());
+    }
+
+    /// Adds the html of the `<main>` element of a puzzle page.
+    pub fn add_puzzle(&self, year: u16, day: u8, html: &str) {
+        self.state().puzzles.insert((year, day), html.into());
+    }
+
+    /// Queues the verdict for the next submission of a part. Unscripted submissions are incorrect.
+    pub fn add_response(&self, year: u16, day: u8, part: u8, response: Response) {
+        self.state()
+            .responses
+            .entry((year, day, part))
+            .or_default()
+            .push_back(response);
+    }
+
+    /// All requests received so far, in order.
+    pub fn requests(&self) -> Vec<Request> {
+        self.state().requests.clone()
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().unwrap()
+    }
+}
+
+fn read_request(stream: &mut TcpStream) -> io::Result<Request> {
+    let mut reader = BufReader::new(stream);
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_string();
+    let path = parts.next().unwrap_or_default().to_string();
+
+    let mut session = None;
+    let mut content_length = 0;
+
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+
+        match name.to_ascii_lowercase().as_str() {
+            "content-length" => content_length = value.trim().parse().unwrap_or(0),
+            "cookie" => {
+                session = value
+                    .split(';')
+                    .find_map(|cookie| cookie.trim().strip_prefix("session="))
+                    .map(String::from);
+            }
+            _ => {}
+        }
+    }
+
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body)?;
+
+    Ok(Request {
+        method,
+        path,
+        session,
+        body: String::from_utf8_lossy(&body).into(),
+    })
+}
+
+/// Parses `/{year}/day/{day}` followed by an optional suffix, e.g. `/input`.
+fn parse_path(path: &str) -> Option<(u16, u8, &str)> {
+    let mut segments = path.trim_start_matches('/').splitn(4, '/');
+    let year = segments.next()?.parse().ok()?;
+    if segments.next()? != "day" {
+        return None;
+    }
+    let day = segments.next()?.parse().ok()?;
+    Some((year, day, segments.next().unwrap_or("")))
+}
+
+fn form_value<'a>(body: &'a str, key: &str) -> Option<&'a str> {
+    body.split('&')
+        .find_map(|pair| pair.strip_prefix(key)?.strip_prefix('='))
+}
+
+fn page(main: &str) -> String {
+    format!("<!DOCTYPE html>\n<html><body><main>\n{main}\n</main></body></html>\n")
+}
+
+fn route(request: &Request, state: &mut State) -> (u16, String) {
+    if request.session.is_none() {
+        return (
+            400,
+            "Puzzle inputs differ by user.  Please log in to get your puzzle input.\n".into(),
+        );
+    }
+
+    let not_found = (404, "404 Not Found\n".to_string());
+
+    let Some((year, day, suffix)) = parse_path(&request.path) else {
+        return not_found;
+    };
+
+    match (request.method.as_str(), suffix) {
+        ("GET", "input") => state
+            .inputs
+            .get(&(year, day))
+            .map_or(not_found, |input| (200, input.clone())),
+        ("GET", "") => state
+            .puzzles
+            .get(&(year, day))
+            .map_or(not_found, |puzzle| (200, page(puzzle))),
+        ("POST", "answer") => {
+            let Some(part) = form_value(&request.body, "level").and_then(|l| l.parse().ok()) else {
+                return (400, "Bad Request\n".into());
+            };
+
+            let response = state
+                .responses
+                .get_mut(&(year, day, part))
+                .and_then(VecDeque::pop_front)
+                .unwrap_or(Response::Incorrect);
+
+            (
+                200,
+                page(&format!("<article><p>{}</p></article>", response.text())),
+            )
+        }
+        _ => not_found,
+    }
+}
+
+fn handle(mut stream: TcpStream, state: &Mutex<State>) -> io::Result<()> {
+    let request = read_request(&mut stream)?;
+
+    let (status, body) = {
+        let mut state = state.lock().unwrap();
+        state.requests.push(request.clone());
+        route(&request, &mut state)
+    };
+
+    write!(
+        stream,
+        "HTTP/1.1 {status} {}\r\nContent-Type: text/html\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        if status == 200 { "OK" } else { "Error" },
+        body.len()
+    )?;
+    stream.flush()
+}
+
+/// Sends a request to the mock server, returning the status code and the body.
+/// Used by the `mock-aoc` binary.
+pub fn send(
+    url: &str,
+    method: &str,
+    path: &str,
+    session: &str,
+    body: Option<&str>,
+) -> io::Result<(u16, String)> {
+    let address = url.strip_prefix("http://").unwrap_or(url);
+    let mut stream = TcpStream::connect(address)?;
+    let body = body.unwrap_or_default();
+
+    write!(
+        stream,
+        "{method} {path} HTTP/1.1\r\nHost: {address}\r\nCookie: session={session}\r\nContent-Type: application/x-www-form-urlencoded\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )?;
+    stream.flush()?;
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response)?;
+
+    let (head, body) = response.split_once("\r\n\r\n").unwrap_or((&response, ""));
+    let status = head
+        .split_whitespace()
+        .nth(1)
+        .and_then(|status| status.parse().ok())
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "malformed response"))?;
+
+    Ok((status, body.to_string()))
+}
+
+/// Converts the `<main>` element of a page to markdown, roughly like aoc-cli does.
+pub fn html_to_markdown(html: &str) -> String {
+    let main = html
+        .split_once("<main>")
+        .and_then(|(_, rest)| rest.split_once("</main>"))
+        .map_or(html, |(main, _)| main);
+
+    let mut markdown = main.to_string();
+    for (tag, replacement) in [
+        ("<pre><code>", "```\n"),
+        ("</code></pre>", "```\n\n"),
+        ("<h2>", "\\"),
+        ("</h2>", "\n----------\n\n"),
+        ("<p>", ""),
+        ("</p>", "\n\n"),
+        ("<li>", "* "),
+        ("</li>", "\n"),
+        ("<code>", "`"),
+        ("</code>", "`"),
+        ("<em>", "*"),
+        ("</em>", "*"),
+    ] {
+        markdown = markdown.replace(tag, replacement);
+    }
+
+    // drop all remaining tags, e.g. `<article>` or `<ul>`.
+    let mut result = String::with_capacity(markdown.len());
+    let mut in_tag = false;
+    for c in markdown.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => result.push(c),
+            _ => {}
+        }
+    }
+
+    result
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+        .trim()
+        .to_string()
+        + "\n"
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{html_to_markdown, parse_path, send, MockServer, Response};
+
+    #[test]
+    fn parses_paths() {
+        assert_eq!(parse_path("/2024/day/1/input"), Some((2024, 1, "input")));
+        assert_eq!(parse_path("/2024/day/25"), Some((2024, 25, "")));
+        assert_eq!(parse_path("/2024/leaderboard"), None);
+    }
+
+    #[test]
+    fn serves_inputs_and_verdicts() {
+        let server = MockServer::start().unwrap();
+        server.add_input(2024, 1, "3   4\n");
+        server.add_response(2024, 1, 1, Response::Correct);
+
+        let url = server.url();
+        assert_eq!(
+            send(&url, "GET", "/2024/day/1/input", "abc", None).unwrap(),
+            (200, "3   4\n".into())
+        );
+        assert_eq!(
+            send(&url, "GET", "/2024/day/2/input", "abc", None)
+                .unwrap()
+                .0,
+            404
+        );
+
+        let (_, body) = send(
+            &url,
+            "POST",
+            "/2024/day/1/answer",
+            "abc",
+            Some("level=1&answer=11"),
+        )
+        .unwrap();
+        assert!(body.contains(Response::Correct.text()));
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(requests[2].body, "level=1&answer=11");
+        assert_eq!(requests[2].session.as_deref(), Some("abc"));
+    }
+
+    #[test]
+    fn converts_html() {
+        assert_eq!(
+            html_to_markdown(
+                "<main><article><h2>--- Day 1: Test ---</h2><p>Sum is <code><em>11</em></code>.</p><pre><code>1 &lt; 2\n</code></pre></article></main>"
+            ),
+            "\\--- Day 1: Test ---\n----------\n\nSum is `*11*`.\n\n```\n1 < 2\n```\n"
+        );
+    }
+}
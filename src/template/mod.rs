@@ -1,27 +1,51 @@
-use std::{env, fs};
+use std::env;
 
 pub mod aoc_cli;
+pub mod bigint;
 pub mod commands;
+pub mod disk;
+pub mod encryption;
+pub mod examples;
+pub mod expression;
+pub mod fuzz;
+pub mod graph;
+pub mod image;
+pub mod input;
+pub mod lexer;
+pub mod markdown;
+pub mod memory;
+#[cfg(feature = "test_lib")]
+pub mod mock_aoc;
+pub mod multiset;
+pub mod params;
+pub mod pattern;
+pub mod perf;
+pub mod picture;
+pub mod property;
+pub mod puzzle;
 pub mod runner;
+pub mod visualize;
 
 pub use day::*;
 
 mod day;
 mod readme_benchmarks;
 mod run_multi;
+mod submissions;
 mod timings;
 
 pub const ANSI_ITALIC: &str = "\x1b[3m";
 pub const ANSI_BOLD: &str = "\x1b[1m";
 pub const ANSI_RESET: &str = "\x1b[0m";
 
-/// Helper function that reads a text file to a string.
+/// Helper function that reads a text file to a string. Line endings are normalised, see [`input::normalize`].
+/// Encrypted inputs (`NN.txt.enc`) are decrypted if the plain file is missing, see [`encryption`].
 #[must_use]
 pub fn read_file(folder: &str, day: Day) -> String {
     let cwd = env::current_dir().unwrap();
     let filepath = cwd.join("data").join(folder).join(format!("{day}.txt"));
-    let f = fs::read_to_string(filepath);
-    f.expect("could not open input file")
+    let f = encryption::read_to_string(&filepath);
+    input::normalize(&f.unwrap_or_else(|e| panic!("could not open input file: {e}")))
 }
 
 /// Helper function that reads a text file to string, appending a part suffix. E.g. like `01-2.txt`.
@@ -32,26 +56,50 @@ pub fn read_file_part(folder: &str, day: Day, part: u8) -> String {
         .join("data")
         .join(folder)
         .join(format!("{day}-{part}.txt"));
-    let f = fs::read_to_string(filepath);
-    f.expect("could not open input file")
+    let f = encryption::read_to_string(&filepath);
+    input::normalize(&f.unwrap_or_else(|e| panic!("could not open input file: {e}")))
 }
 
 /// Creates the constant `DAY` and sets up the input and runner for each part.
 ///
 /// The optional, second parameter (1 or 2) allows you to only run a single part of the solution.
+///
+/// Puzzles whose examples use different constants than the real input can declare a parameters type
+/// with `solution!(14, params: Size)`. Each part then receives the parameters as second argument,
+/// see [`params::Params`].
+///
+/// If the day has an example manifest (`data/examples/NN.json`), a test is generated for every example and part.
+///
+/// Started with `--fuzz`, the binary fuzzes its parts instead of solving the puzzle, see [`fuzz`].
 #[macro_export]
 macro_rules! solution {
     ($day:expr) => {
-        $crate::solution!(@impl $day, [part_one, 1] [part_two, 2]);
+        $crate::solution!(@impl $day, (), plain, [part_one, 1] [part_two, 2]);
     };
     ($day:expr, 1) => {
-        $crate::solution!(@impl $day, [part_one, 1]);
+        $crate::solution!(@impl $day, (), plain, [part_one, 1]);
     };
     ($day:expr, 2) => {
-        $crate::solution!(@impl $day, [part_two, 2]);
+        $crate::solution!(@impl $day, (), plain, [part_two, 2]);
+    };
+    ($day:expr, params: $params:ty) => {
+        $crate::solution!(@impl $day, $params, with_params, [part_one, 1] [part_two, 2]);
+    };
+    ($day:expr, 1, params: $params:ty) => {
+        $crate::solution!(@impl $day, $params, with_params, [part_one, 1]);
+    };
+    ($day:expr, 2, params: $params:ty) => {
+        $crate::solution!(@impl $day, $params, with_params, [part_two, 2]);
     };
 
-    (@impl $day:expr, $( [$func:expr, $part:expr] )*) => {
+    (@call plain, $func:path, $input:expr, $params:expr) => {
+        $func($input)
+    };
+    (@call with_params, $func:path, $input:expr, $params:expr) => {
+        $func($input, $params)
+    };
+
+    (@impl $day:expr, $params:ty, $kind:ident, $( [$func:ident, $part:expr] )*) => {
         /// The current day.
         const DAY: $crate::template::Day = $crate::day!($day);
 
@@ -59,10 +107,90 @@ macro_rules! solution {
         #[global_allocator]
         static ALLOC: dhat::Alloc = dhat::Alloc;
 
+        #[cfg(all(feature = "memory", not(feature = "dhat-heap")))]
+        #[global_allocator]
+        static ALLOC: $crate::template::memory::CountingAlloc = $crate::template::memory::CountingAlloc;
+
         fn main() {
+            use $crate::template::input::InputSource;
+            use $crate::template::params::{Params, RawParams};
             use $crate::template::runner::*;
-            let input = $crate::template::read_file("inputs", DAY);
-            $( run_part($func, &input, DAY, $part); )*
+
+            if std::env::args().any(|arg| arg == "--fuzz") {
+                // parameters are created for every run from those of the mutated example, overridden by `--param`.
+                let raw = RawParams::from_args()
+                    .and_then(|raw| <$params as Params>::from_params(&raw).map(|_| raw))
+                    .unwrap_or_else(|e| {
+                        eprintln!("Invalid parameters: {e}");
+                        std::process::exit(1);
+                    });
+
+                $crate::template::fuzz::run(DAY, &[$((
+                    $part,
+                    {
+                        let raw = raw.clone();
+                        std::sync::Arc::new(move |input: &str, example: &RawParams| {
+                            #[allow(unused_variables)]
+                            let params = <$params as Params>::from_params(&example.clone().merge(&raw)).unwrap();
+                            let _ = std::hint::black_box($crate::solution!(@call $kind, $func, input, &params));
+                        }) as $crate::template::fuzz::Target
+                    },
+                )),*]);
+            }
+
+            let (input, input_params) = InputSource::from_args()
+                .and_then(|source| source.read(DAY))
+                .unwrap_or_else(|e| {
+                    eprintln!("Error: {e}");
+                    std::process::exit(1);
+                });
+
+            // parameters passed on the command-line take precedence over those of an example.
+            #[allow(unused_variables)]
+            let params = RawParams::from_args()
+                .and_then(|raw| <$params as Params>::from_params(&input_params.merge(&raw)))
+                .unwrap_or_else(|e| {
+                    eprintln!("Invalid parameters: {e}");
+                    std::process::exit(1);
+                });
+
+            $( run_part(|input: &str| $crate::solution!(@call $kind, $func, input, &params), input.as_str(), DAY, $part); )*
+        }
+
+        #[cfg(test)]
+        mod example_tests {
+            $(
+                mod $func {
+                    // brings the parameters type into scope.
+                    #[allow(unused_imports)]
+                    use super::super::*;
+
+                    #[allow(unused_macros)]
+                    macro_rules! example {
+                        ($index:expr, $name:ident) => {
+                            #[test]
+                            fn $name() {
+                                use $crate::template::params::{Params, RawParams};
+
+                                $crate::template::examples::check(
+                                    super::super::DAY,
+                                    $index,
+                                    $part,
+                                    |input: &str, raw: &RawParams| {
+                                        #[allow(unused_variables)]
+                                        let params = <$params as Params>::from_params(raw)
+                                            .unwrap_or_else(|e| panic!("invalid example parameters: {e}"));
+                                        $crate::solution!(@call $kind, super::super::$func, input, &params)
+                                    },
+                                );
+                            }
+                        };
+                    }
+
+                    // generated by `build.rs` from the example manifest.
+                    include!(concat!(env!("OUT_DIR"), "/examples/", $day, ".rs"));
+                }
+            )*
         }
     };
 }
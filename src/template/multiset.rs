@@ -0,0 +1,462 @@
+/// Repeatedly applying a rule to every element of a counted multiset, e.g. stones that split or
+/// fish that spawn. Only the number of copies of each distinct element is tracked, so the size of
+/// the multiset may grow exponentially while the work per step stays proportional to the number
+/// of distinct elements:
+///
+/// ```ignore
+/// use advent_of_code::template::multiset::{Engine, Multiset};
+///
+/// let stones: Multiset<u64, u64> = input.split_whitespace().map(|s| s.parse().unwrap()).collect();
+/// let mut engine = Engine::new(|stone: &u64| if *stone == 0 { vec![1] } else { vec![stone * 2024] });
+/// let total = engine.run(&stones, 75).total();
+/// ```
+///
+/// The rule is evaluated once per distinct element and memoized. Counts are any [`Count`], pick
+/// `u128` or [`BigUint`] when `u64` is not enough.
+///
+/// Once the rule only produces elements that were seen before, the remaining steps are a fixed
+/// linear map on the counts of the `d` distinct elements. For many steps it is then applied by
+/// repeated squaring in `O(d³ log N)` instead of `O(N)`. This only pays off for small `d`: day 11
+/// closes at a few thousand stones, which is too many. Totals after that many steps rarely fit any
+/// integer, count with [`Modulo`] if the puzzle asks for a remainder.
+use std::{collections::HashMap, fmt::Display, hash::Hash};
+
+use crate::template::bigint::BigUint;
+
+/// The number of copies of an element. Arithmetic panics on overflow.
+pub trait Count: Clone {
+    fn zero() -> Self;
+    fn one() -> Self;
+    fn is_zero(&self) -> bool;
+    fn add(&self, other: &Self) -> Self;
+    fn mul(&self, other: &Self) -> Self;
+}
+
+macro_rules! impl_count {
+    ($($t:ty),*) => {
+        $(
+            impl Count for $t {
+                fn zero() -> Self {
+                    0
+                }
+
+                fn one() -> Self {
+                    1
+                }
+
+                fn is_zero(&self) -> bool {
+                    *self == 0
+                }
+
+                fn add(&self, other: &Self) -> Self {
+                    self.checked_add(*other).unwrap_or_else(|| {
+                        panic!("count overflowed {}, use a larger count type.", stringify!($t))
+                    })
+                }
+
+                fn mul(&self, other: &Self) -> Self {
+                    self.checked_mul(*other).unwrap_or_else(|| {
+                        panic!("count overflowed {}, use a larger count type.", stringify!($t))
+                    })
+                }
+            }
+        )*
+    };
+}
+
+impl_count!(u32, u64, u128, usize);
+
+impl Count for BigUint {
+    fn zero() -> Self {
+        Self::zero()
+    }
+
+    fn one() -> Self {
+        Self::one()
+    }
+
+    fn is_zero(&self) -> bool {
+        self.is_zero()
+    }
+
+    fn add(&self, other: &Self) -> Self {
+        self + other
+    }
+
+    fn mul(&self, other: &Self) -> Self {
+        self * other
+    }
+}
+
+/// Counts modulo `M`, for puzzles that ask for the remainder of a total too large for any integer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Modulo<const M: u64>(pub u64);
+
+impl<const M: u64> Count for Modulo<M> {
+    fn zero() -> Self {
+        Self(0)
+    }
+
+    fn one() -> Self {
+        Self(1 % M)
+    }
+
+    fn is_zero(&self) -> bool {
+        self.0 == 0
+    }
+
+    fn add(&self, other: &Self) -> Self {
+        Self(((u128::from(self.0) + u128::from(other.0)) % u128::from(M)) as u64)
+    }
+
+    fn mul(&self, other: &Self) -> Self {
+        Self((u128::from(self.0) * u128::from(other.0) % u128::from(M)) as u64)
+    }
+}
+
+impl<const M: u64> Display for Modulo<M> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// Elements with the number of times they occur.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Multiset<E: Hash + Eq, C> {
+    counts: HashMap<E, C>,
+}
+
+impl<E: Hash + Eq, C: Count> Default for Multiset<E, C> {
+    fn default() -> Self {
+        Self {
+            counts: HashMap::new(),
+        }
+    }
+}
+
+impl<E: Hash + Eq, C: Count> Multiset<E, C> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `count` copies of `element`.
+    pub fn insert(&mut self, element: E, count: C) {
+        if count.is_zero() {
+            return;
+        }
+
+        self.counts
+            .entry(element)
+            .and_modify(|existing| *existing = existing.add(&count))
+            .or_insert(count);
+    }
+
+    pub fn count(&self, element: &E) -> C {
+        self.counts.get(element).cloned().unwrap_or_else(C::zero)
+    }
+
+    /// The number of elements, counting copies.
+    pub fn total(&self) -> C {
+        self.counts
+            .values()
+            .fold(C::zero(), |total, count| total.add(count))
+    }
+
+    /// The number of distinct elements.
+    pub fn distinct(&self) -> usize {
+        self.counts.len()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&E, &C)> {
+        self.counts.iter()
+    }
+}
+
+impl<E: Hash + Eq, C: Count> FromIterator<E> for Multiset<E, C> {
+    fn from_iter<T: IntoIterator<Item = E>>(iter: T) -> Self {
+        let mut multiset = Self::new();
+        for element in iter {
+            multiset.insert(element, C::one());
+        }
+        multiset
+    }
+}
+
+impl<E: Hash + Eq, C: Count> FromIterator<(E, C)> for Multiset<E, C> {
+    fn from_iter<T: IntoIterator<Item = (E, C)>>(iter: T) -> Self {
+        let mut multiset = Self::new();
+        for (element, count) in iter {
+            multiset.insert(element, count);
+        }
+        multiset
+    }
+}
+
+/// Applies a rule that replaces every element with zero or more elements.
+///
+/// Distinct elements are numbered in the order they are seen and the rule's results are
+/// stored as such numbers, so a step only adds counts in a vector.
+pub struct Engine<E, R> {
+    rule: R,
+    indices: HashMap<E, usize>,
+    elements: Vec<E>,
+    expansions: Vec<Option<Vec<usize>>>,
+}
+
+impl<E: Hash + Eq + Clone, R: Fn(&E) -> Vec<E>> Engine<E, R> {
+    pub fn new(rule: R) -> Self {
+        Self {
+            rule,
+            indices: HashMap::new(),
+            elements: vec![],
+            expansions: vec![],
+        }
+    }
+
+    fn index(&mut self, element: &E) -> usize {
+        if let Some(&index) = self.indices.get(element) {
+            return index;
+        }
+
+        let index = self.elements.len();
+        self.indices.insert(element.clone(), index);
+        self.elements.push(element.clone());
+        self.expansions.push(None);
+        index
+    }
+
+    fn expansion(&mut self, index: usize) -> &[usize] {
+        if self.expansions[index].is_none() {
+            let results = (self.rule)(&self.elements[index]);
+            let expansion = results.iter().map(|result| self.index(result)).collect();
+            self.expansions[index] = Some(expansion);
+        }
+
+        self.expansions[index].as_deref().unwrap()
+    }
+
+    /// The elements an element is replaced with, evaluating the rule only once per element.
+    pub fn expand(&mut self, element: &E) -> Vec<E> {
+        let index = self.index(element);
+        self.expansion(index);
+
+        self.expansions[index]
+            .iter()
+            .flatten()
+            .map(|&i| self.elements[i].clone())
+            .collect()
+    }
+
+    /// The number of distinct elements seen so far, including the results of the rule.
+    pub fn seen(&self) -> usize {
+        self.elements.len()
+    }
+
+    /// Applies the rule `steps` times to every element of `multiset`.
+    pub fn run<C: Count>(&mut self, multiset: &Multiset<E, C>, steps: usize) -> Multiset<E, C> {
+        let mut counts: Vec<C> = vec![];
+        for (element, count) in multiset.iter() {
+            let index = self.index(element);
+            counts.resize(counts.len().max(index + 1), C::zero());
+            counts[index] = counts[index].add(count);
+        }
+
+        let mut remaining = steps;
+        while remaining > 0 {
+            let seen = self.elements.len();
+            counts = self.step(&counts);
+            remaining -= 1;
+
+            if self.elements.len() == seen && self.squaring_pays_off(remaining) && self.is_closed()
+            {
+                counts = self.power(&counts, remaining);
+                break;
+            }
+        }
+
+        counts
+            .into_iter()
+            .enumerate()
+            .filter(|(_, count)| !count.is_zero())
+            .map(|(index, count)| (self.elements[index].clone(), count))
+            .collect()
+    }
+
+    fn step<C: Count>(&mut self, counts: &[C]) -> Vec<C> {
+        let mut next: Vec<C> = vec![C::zero(); self.elements.len()];
+
+        for (index, count) in counts.iter().enumerate() {
+            if count.is_zero() {
+                continue;
+            }
+
+            for &result in self.expansion(index) {
+                if result >= next.len() {
+                    next.resize(result + 1, C::zero());
+                }
+                next[result] = next[result].add(count);
+            }
+        }
+
+        next
+    }
+
+    /// Whether squaring the transition is cheaper than stepping through the remaining steps.
+    fn squaring_pays_off(&self, remaining: usize) -> bool {
+        let distinct = self.elements.len() as u128;
+        let results: usize = self.expansions.iter().flatten().map(Vec::len).sum();
+        let squarings = u128::from(usize::BITS - remaining.leading_zeros());
+
+        2 * distinct.pow(3) * squarings < remaining as u128 * results.max(1) as u128
+    }
+
+    /// Whether the rule only produces elements that were seen before.
+    fn is_closed(&mut self) -> bool {
+        let seen = self.elements.len();
+        (0..seen).for_each(|index| {
+            self.expansion(index);
+        });
+
+        self.elements.len() == seen
+    }
+
+    /// Applies `steps` steps by repeated squaring of the transition between the distinct elements.
+    fn power<C: Count>(&mut self, counts: &[C], mut steps: usize) -> Vec<C> {
+        let distinct = self.elements.len();
+
+        let mut transition = vec![vec![C::zero(); distinct]; distinct];
+        for (index, row) in transition.iter_mut().enumerate() {
+            for &result in self.expansion(index) {
+                row[result] = row[result].add(&C::one());
+            }
+        }
+
+        let mut counts = counts.to_vec();
+        counts.resize(distinct, C::zero());
+
+        while steps > 0 {
+            if steps & 1 == 1 {
+                counts = multiply(std::slice::from_ref(&counts), &transition).remove(0);
+            }
+            steps >>= 1;
+            if steps > 0 {
+                transition = multiply(&transition, &transition);
+            }
+        }
+
+        counts
+    }
+}
+
+fn multiply<C: Count>(a: &[Vec<C>], b: &[Vec<C>]) -> Vec<Vec<C>> {
+    a.iter()
+        .map(|row| {
+            let mut product = vec![C::zero(); b[0].len()];
+            for (value, b_row) in row.iter().zip(b) {
+                if value.is_zero() {
+                    continue;
+                }
+
+                for (product, b_value) in product.iter_mut().zip(b_row) {
+                    if !b_value.is_zero() {
+                        *product = product.add(&value.mul(b_value));
+                    }
+                }
+            }
+            product
+        })
+        .collect()
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Count, Engine, Modulo, Multiset};
+
+    /// Every element splits into two copies of itself plus one.
+    fn split(n: &u32) -> Vec<u32> {
+        vec![n + 1, n + 1]
+    }
+
+    #[test]
+    fn counts_elements() {
+        let mut multiset: Multiset<char, u64> = "abca".chars().collect();
+        multiset.insert('d', 0);
+        multiset.insert('b', 3);
+
+        assert_eq!(multiset.count(&'a'), 2);
+        assert_eq!(multiset.count(&'b'), 4);
+        assert_eq!(multiset.count(&'d'), 0);
+        assert_eq!(multiset.distinct(), 3);
+        assert_eq!(multiset.total(), 7);
+    }
+
+    #[test]
+    fn runs_rules() {
+        let mut engine = Engine::new(split);
+        let start: Multiset<u32, u64> = [0, 0, 5].into_iter().collect();
+
+        let result = engine.run(&start, 10);
+        assert_eq!(result.count(&10), 2 << 10);
+        assert_eq!(result.count(&15), 1 << 10);
+        assert_eq!(result.distinct(), 2);
+        assert_eq!(engine.run(&start, 0), start);
+
+        assert_eq!(engine.expand(&3), vec![4, 4]);
+        assert_eq!(engine.seen(), 16);
+
+        // removing elements works too.
+        let mut engine = Engine::new(|n: &u32| if *n > 0 { vec![n - 1] } else { vec![] });
+        assert_eq!(engine.run(&start, 3).total(), 1);
+    }
+
+    #[test]
+    fn squares_closed_rules() {
+        type Mod = Modulo<1_000_000_007>;
+
+        // lanternfish: timers count down and spawn a new fish when they reset.
+        let fish = |timer: &u8| match timer {
+            0 => vec![6, 8],
+            timer => vec![timer - 1],
+        };
+        let start: Multiset<u8, Mod> = [3, 4, 3, 1, 2].into_iter().collect();
+
+        let mut timers = [0u64; 9];
+        for timer in [3, 4, 3, 1, 2] {
+            timers[timer] += 1;
+        }
+        for _ in 0..100_000 {
+            timers.rotate_left(1);
+            timers[6] = (timers[6] + timers[8]) % 1_000_000_007;
+        }
+        let expected = timers.iter().sum::<u64>() % 1_000_000_007;
+
+        assert_eq!(
+            Engine::new(fish).run(&start, 100_000).total(),
+            Modulo(expected)
+        );
+
+        let start: Multiset<u8, u64> = [3, 4, 3, 1, 2].into_iter().collect();
+        assert_eq!(Engine::new(fish).run(&start, 256).total(), 26984457539);
+
+        // far more steps than could be taken one at a time.
+        let split = |n: &u8| vec![(n + 1) % 3, (n + 1) % 3];
+        let start: Multiset<u8, Modulo<1_000>> = [0].into_iter().collect();
+        let result = Engine::new(split).run(&start, 1_000_000_000_000);
+        // 2^(10^12) mod 1000.
+        assert_eq!(result.count(&1), Modulo(376));
+        assert_eq!(result.distinct(), 1);
+    }
+
+    #[test]
+    fn uses_larger_counts() {
+        let start: Multiset<u32, u128> = [0].into_iter().collect();
+        assert_eq!(Engine::new(split).run(&start, 100).total(), 1 << 100);
+
+        let overflow = std::panic::catch_unwind(|| {
+            let start: Multiset<u32, u64> = [0].into_iter().collect();
+            Engine::new(split).run(&start, 64)
+        });
+        assert!(overflow.is_err());
+        assert_eq!(<u64 as Count>::one().add(&2), 3);
+    }
+}
@@ -0,0 +1,175 @@
+/// Puzzle parameters that differ between inputs, e.g. the grid size of day 14.
+/// Parameters are passed as raw key/value pairs (from an example manifest or `--param key=value`)
+/// and converted into a typed struct by the solution's [`Params`] implementation.
+use std::{collections::HashMap, env, str::FromStr};
+
+/// Untyped parameters as declared in a manifest or passed on the command-line.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RawParams(HashMap<String, String>);
+
+impl RawParams {
+    /// Parses a list of `key=value` pairs.
+    pub fn parse<'a>(pairs: impl IntoIterator<Item = &'a str>) -> Result<Self, String> {
+        pairs
+            .into_iter()
+            .map(|pair| {
+                pair.split_once('=')
+                    .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+                    .ok_or(format!(
+                        "expected parameter `{pair}` to have the form `key=value`."
+                    ))
+            })
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+
+    /// Collects all `--param key=value` arguments passed to the current process.
+    pub fn from_args() -> Result<Self, String> {
+        let args: Vec<String> = env::args().collect();
+
+        Self::parse(
+            args.windows(2)
+                .filter(|pair| pair[0] == "--param")
+                .map(|pair| pair[1].as_str()),
+        )
+    }
+
+    /// Returns the parsed value of `key`, or `None` if it was not provided.
+    pub fn get<T: FromStr>(&self, key: &str) -> Result<Option<T>, String> {
+        self.0
+            .get(key)
+            .map(|value| {
+                value
+                    .parse()
+                    .map_err(|_| format!("invalid value `{value}` for parameter `{key}`."))
+            })
+            .transpose()
+    }
+
+    /// Returns the parsed value of `key`, or `default` if it was not provided.
+    pub fn get_or<T: FromStr>(&self, key: &str, default: T) -> Result<T, String> {
+        Ok(self.get(key)?.unwrap_or(default))
+    }
+
+    /// Values provided in `other` take precedence over the values in `self`.
+    #[must_use]
+    pub fn merge(mut self, other: &Self) -> Self {
+        self.0
+            .extend(other.0.iter().map(|(k, v)| (k.clone(), v.clone())));
+        self
+    }
+
+    /// `--param key=value` arguments that pass these parameters to a solution, sorted by key.
+    pub fn to_args(&self) -> Vec<String> {
+        let mut pairs: Vec<_> = self.0.iter().collect();
+        pairs.sort();
+
+        pairs
+            .into_iter()
+            .flat_map(|(key, value)| ["--param".to_string(), format!("{key}={value}")])
+            .collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl From<HashMap<String, String>> for RawParams {
+    fn from(value: HashMap<String, String>) -> Self {
+        Self(value)
+    }
+}
+
+/// Typed puzzle parameters. Missing values should fall back to the values of the real puzzle input.
+///
+/// ```ignore
+/// struct Size {
+///     width: u64,
+///     height: u64,
+/// }
+///
+/// impl Params for Size {
+///     fn from_params(params: &RawParams) -> Result<Self, String> {
+///         Ok(Self {
+///             width: params.get_or("width", 101)?,
+///             height: params.get_or("height", 103)?,
+///         })
+///     }
+/// }
+///
+/// advent_of_code::solution!(14, params: Size);
+///
+/// pub fn part_one(input: &str, size: &Size) -> Option<u32> { /* ... */ }
+/// ```
+pub trait Params: Sized {
+    fn from_params(params: &RawParams) -> Result<Self, String>;
+}
+
+impl Params for () {
+    fn from_params(_: &RawParams) -> Result<Self, String> {
+        Ok(())
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Params, RawParams};
+
+    struct Size {
+        width: u64,
+        height: u64,
+    }
+
+    impl Params for Size {
+        fn from_params(params: &RawParams) -> Result<Self, String> {
+            Ok(Self {
+                width: params.get_or("width", 101)?,
+                height: params.get_or("height", 103)?,
+            })
+        }
+    }
+
+    #[test]
+    fn uses_defaults() {
+        let size = Size::from_params(&RawParams::default()).unwrap();
+        assert_eq!((size.width, size.height), (101, 103));
+    }
+
+    #[test]
+    fn parses_values() {
+        let params = RawParams::parse(["width=11", "height = 7"]).unwrap();
+        let size = Size::from_params(&params).unwrap();
+        assert_eq!((size.width, size.height), (11, 7));
+    }
+
+    #[test]
+    fn merges_values() {
+        let params = RawParams::parse(["width=11", "height=7"])
+            .unwrap()
+            .merge(&RawParams::parse(["height=9"]).unwrap());
+        let size = Size::from_params(&params).unwrap();
+        assert_eq!((size.width, size.height), (11, 9));
+    }
+
+    #[test]
+    fn formats_args() {
+        let params = RawParams::parse(["width=11", "height=7"]).unwrap();
+        assert_eq!(
+            params.to_args(),
+            vec!["--param", "height=7", "--param", "width=11"]
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn panics_for_malformed_pairs() {
+        RawParams::parse(["width"]).unwrap();
+    }
+
+    #[test]
+    #[should_panic]
+    fn panics_for_invalid_values() {
+        Size::from_params(&RawParams::parse(["width=wide"]).unwrap()).unwrap();
+    }
+}
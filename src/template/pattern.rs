@@ -0,0 +1,300 @@
+/// Searching a grid of characters for words and shapes, like day 04's word search. A pattern is a
+/// set of cells relative to its top-left corner, declared as a word or as a template:
+///
+/// ```ignore
+/// use advent_of_code::template::pattern::{Grid, Pattern, Symmetry};
+///
+/// let grid = Grid::new(input);
+/// let words = grid.count(&Pattern::lines("XMAS"));
+/// let crosses = grid.count(&Pattern::shape("M.S\n.A.\nM.S").orientations(Symmetry::Rotations));
+/// ```
+///
+/// Grids and patterns are compared byte by byte, which is fine for the ASCII grids of puzzles.
+use std::collections::HashSet;
+
+/// Template cells that match anything.
+pub const WILDCARD: u8 = b'.';
+
+/// The eight directions a line can go in, as `(dx, dy)`.
+pub const DIRECTIONS: [(isize, isize); 8] = [
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+];
+
+/// The orientations a pattern can appear in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Symmetry {
+    /// As declared.
+    None,
+    /// Rotated by multiples of 90 degrees.
+    Rotations,
+    /// Rotated and mirrored.
+    Reflections,
+}
+
+/// Bytes the grid must contain at offsets from the top-left corner of the pattern. Patterns are
+/// equal if they cover the same cells with the same bytes, in any order.
+#[derive(Clone, Debug)]
+pub struct Pattern {
+    cells: Vec<((usize, usize), u8)>,
+}
+
+impl PartialEq for Pattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.sorted_cells() == other.sorted_cells()
+    }
+}
+
+impl Eq for Pattern {}
+
+impl Pattern {
+    /// Cells at signed offsets, moved so the smallest offsets are zero. Order is kept.
+    fn normalized(cells: impl IntoIterator<Item = ((isize, isize), u8)>) -> Self {
+        let cells: Vec<_> = cells.into_iter().collect();
+        let min_x = cells.iter().map(|((x, _), _)| *x).min().unwrap_or(0);
+        let min_y = cells.iter().map(|((_, y), _)| *y).min().unwrap_or(0);
+
+        Self {
+            cells: cells
+                .into_iter()
+                .map(|((x, y), b)| (((x - min_x) as usize, (y - min_y) as usize), b))
+                .collect(),
+        }
+    }
+
+    fn sorted_cells(&self) -> Vec<((usize, usize), u8)> {
+        let mut cells = self.cells.clone();
+        cells.sort_unstable();
+        cells
+    }
+
+    fn signed_cells(&self) -> impl Iterator<Item = ((isize, isize), u8)> + '_ {
+        self.cells
+            .iter()
+            .map(|&((x, y), b)| ((x as isize, y as isize), b))
+    }
+
+    /// A word going in `direction`, one of [`DIRECTIONS`] or any other step.
+    pub fn line(word: &str, (dx, dy): (isize, isize)) -> Self {
+        Self::normalized(word.bytes().zip(0..).map(|(b, i)| ((i * dx, i * dy), b)))
+    }
+
+    /// A word from left to right.
+    pub fn word(word: &str) -> Self {
+        Self::line(word, (1, 0))
+    }
+
+    /// A word in all eight [`DIRECTIONS`]. Palindromes read the same both ways, so they give
+    /// four distinct patterns.
+    pub fn lines(word: &str) -> Vec<Self> {
+        distinct(
+            DIRECTIONS
+                .iter()
+                .map(|&direction| Self::line(word, direction)),
+        )
+    }
+
+    /// A template with one line per row, where [`WILDCARD`] matches anything.
+    pub fn shape(template: &str) -> Self {
+        Self::normalized(template.lines().zip(0..).flat_map(|(line, y)| {
+            line.bytes()
+                .zip(0..)
+                .filter(|&(b, _)| b != WILDCARD)
+                .map(move |(b, x)| ((x, y), b))
+        }))
+    }
+
+    /// Rotated by 90 degrees clockwise.
+    pub fn rotated(&self) -> Self {
+        Self::normalized(self.signed_cells().map(|((x, y), b)| ((-y, x), b)))
+    }
+
+    /// Mirrored left to right.
+    pub fn reflected(&self) -> Self {
+        Self::normalized(self.signed_cells().map(|((x, y), b)| ((-x, y), b)))
+    }
+
+    /// The distinct orientations of the pattern, starting with the pattern itself. Symmetric
+    /// patterns have fewer than the rotations or reflections, so each match is found once.
+    pub fn orientations(&self, symmetry: Symmetry) -> Vec<Self> {
+        let rotations =
+            |pattern: Self| std::iter::successors(Some(pattern), |p| Some(p.rotated())).take(4);
+
+        match symmetry {
+            Symmetry::None => vec![self.clone()],
+            Symmetry::Rotations => distinct(rotations(self.clone())),
+            Symmetry::Reflections => {
+                distinct(rotations(self.clone()).chain(rotations(self.reflected())))
+            }
+        }
+    }
+
+    /// The grid positions the pattern covers when its top-left corner is at `position`.
+    pub fn cells_at(&self, (x, y): (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.cells.iter().map(move |((dx, dy), _)| (x + dx, y + dy))
+    }
+}
+
+/// Patterns that are not equal to an earlier one.
+fn distinct(patterns: impl IntoIterator<Item = Pattern>) -> Vec<Pattern> {
+    let mut seen = HashSet::new();
+    patterns
+        .into_iter()
+        .filter(|pattern| seen.insert(pattern.sorted_cells()))
+        .collect()
+}
+
+/// A pattern found in a grid.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Match {
+    /// The index of the pattern in the searched patterns.
+    pub pattern: usize,
+    /// The position of the top-left corner of the pattern.
+    pub position: (usize, usize),
+}
+
+/// Rows of bytes. Rows may have different lengths.
+#[derive(Clone, Debug)]
+pub struct Grid<'a> {
+    rows: Vec<&'a [u8]>,
+    width: usize,
+}
+
+impl<'a> Grid<'a> {
+    pub fn new(input: &'a str) -> Self {
+        let rows: Vec<_> = input.lines().map(str::as_bytes).collect();
+        let width = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+        Self { rows, width }
+    }
+
+    pub fn get(&self, (x, y): (usize, usize)) -> Option<u8> {
+        self.rows.get(y)?.get(x).copied()
+    }
+
+    fn matches(&self, pattern: &Pattern, (x, y): (usize, usize)) -> bool {
+        pattern
+            .cells
+            .iter()
+            .all(|&((dx, dy), b)| self.get((x + dx, y + dy)) == Some(b))
+    }
+
+    /// Every position where any of `patterns` matches, row by row.
+    pub fn find<'p>(&'p self, patterns: &'p [Pattern]) -> impl Iterator<Item = Match> + 'p {
+        (0..self.rows.len())
+            .flat_map(move |y| (0..self.width).map(move |x| (x, y)))
+            .flat_map(move |position| {
+                patterns
+                    .iter()
+                    .enumerate()
+                    .filter(move |(_, pattern)| self.matches(pattern, position))
+                    .map(move |(pattern, _)| Match { pattern, position })
+            })
+    }
+
+    pub fn count(&self, patterns: &[Pattern]) -> usize {
+        self.find(patterns).count()
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Grid, Match, Pattern, Symmetry, DIRECTIONS};
+    use crate::template::property::{self, grid};
+
+    const EXAMPLE: &str = "MMMSXXMASM\nMSAMXMSMSA\nAMXSXMAAMM\nMSAMASMSMX\nXMASAMXAMM\n\
+                           XXAMMXXAMA\nSMSMSASXSS\nSAXAMASAAA\nMAMMMXMMMM\nMXMXAXMASX";
+
+    /// Counts words by walking from every cell in every direction.
+    fn reference(input: &str, word: &str) -> usize {
+        let rows: Vec<&[u8]> = input.lines().map(str::as_bytes).collect();
+        let at = |x: isize, y: isize| {
+            let row = rows.get(usize::try_from(y).ok()?)?;
+            row.get(usize::try_from(x).ok()?).copied()
+        };
+
+        let mut count = 0;
+        for y in 0..rows.len() as isize {
+            for x in 0..rows.iter().map(|r| r.len()).max().unwrap_or(0) as isize {
+                for (dx, dy) in DIRECTIONS {
+                    let found = word
+                        .bytes()
+                        .zip(0..)
+                        .all(|(b, i)| at(x + i * dx, y + i * dy) == Some(b));
+                    count += usize::from(found);
+                }
+            }
+        }
+        count
+    }
+
+    #[test]
+    fn finds_words() {
+        let grid = Grid::new(EXAMPLE);
+        let patterns = Pattern::lines("XMAS");
+        assert_eq!(patterns.len(), 8);
+        assert_eq!(grid.count(&patterns), 18);
+
+        // the first match in reading order goes down and right from the first row.
+        let first = grid.find(&patterns).next().unwrap();
+        assert_eq!(
+            first,
+            Match {
+                pattern: 1,
+                position: (4, 0)
+            }
+        );
+        let cells: Vec<_> = patterns[1].cells_at(first.position).collect();
+        assert_eq!(cells, [(4, 0), (5, 1), (6, 2), (7, 3)]);
+
+        assert_eq!(Pattern::lines("ABA").len(), 4);
+        assert_eq!(Pattern::lines("A").len(), 1);
+        assert_eq!(Grid::new("ABA\nB\nA").count(&Pattern::lines("ABA")), 2);
+    }
+
+    #[test]
+    fn finds_shapes() {
+        let grid = Grid::new(EXAMPLE);
+        let cross = Pattern::shape("M.S\n.A.\nM.S");
+        assert_eq!(grid.count(&cross.orientations(Symmetry::None)), 2);
+        assert_eq!(grid.count(&cross.orientations(Symmetry::Rotations)), 9);
+        assert_eq!(cross.orientations(Symmetry::Reflections).len(), 4);
+
+        let ell = Pattern::shape("X.\nXX");
+        assert_eq!(ell.rotated(), Pattern::shape("XX\nX."));
+        assert_eq!(ell.reflected(), Pattern::shape(".X\nXX"));
+        assert_eq!(ell.orientations(Symmetry::Rotations).len(), 4);
+
+        let ell = Pattern::shape("A..\nBCD");
+        assert_eq!(ell.orientations(Symmetry::Reflections).len(), 8);
+        assert_eq!(
+            Pattern::word("AB")
+                .orientations(Symmetry::Reflections)
+                .len(),
+            4
+        );
+    }
+
+    #[test]
+    fn handles_ragged_grids() {
+        let grid = Grid::new("XMAS\nM\nAXMAS\nS");
+        assert_eq!(grid.get((4, 2)), Some(b'S'));
+        assert_eq!(grid.get((4, 0)), None);
+        assert_eq!(grid.count(&Pattern::lines("XMAS")), 3);
+        assert_eq!(Grid::new("").count(&Pattern::lines("XMAS")), 0);
+    }
+
+    #[test]
+    fn matches_reference_on_random_grids() {
+        property::assert_equivalent(
+            &grid(1..=8, 1..=8, "XMAS"),
+            |grid| reference(&grid.to_string(), "XMAS"),
+            |grid| Grid::new(&grid.to_string()).count(&Pattern::lines("XMAS")),
+        );
+    }
+}
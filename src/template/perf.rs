@@ -0,0 +1,290 @@
+/// Hardware performance counters for solution parts.
+/// Counters are read via `perf_event_open` on Linux when the `perf` feature is active.
+/// On other platforms, or when the kernel refuses access, [`Counters::open`] returns an error
+/// and the runner falls back to wall-clock measurements only.
+use std::fmt::Display;
+use std::io;
+
+/// Whether hardware counters are compiled into this build.
+pub const ENABLED: bool = cfg!(all(feature = "perf", target_os = "linux"));
+
+/// Counter values for a single execution of a solution part.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CounterStats {
+    /// Instructions retired.
+    pub instructions: u64,
+    /// CPU cycles.
+    pub cycles: u64,
+    /// Last level cache misses.
+    pub cache_misses: u64,
+}
+
+impl CounterStats {
+    /// Divides every counter by `n`, e.g. to get per-iteration values of a benchmark.
+    #[must_use]
+    pub fn per_iteration(self, n: u64) -> Self {
+        let n = n.max(1);
+        Self {
+            instructions: self.instructions / n,
+            cycles: self.cycles / n,
+            cache_misses: self.cache_misses / n,
+        }
+    }
+
+    /// Parses counters formatted by the [`Display`] implementation.
+    pub fn parse(s: &str) -> Option<Self> {
+        let mut parts = s.split(", ");
+        let instructions = parts.next()?.strip_suffix(" instructions")?.parse().ok()?;
+        let cycles = parts.next()?.strip_suffix(" cycles")?.parse().ok()?;
+        let cache_misses = parts.next()?.strip_suffix(" cache misses")?.parse().ok()?;
+
+        Some(Self {
+            instructions,
+            cycles,
+            cache_misses,
+        })
+    }
+}
+
+impl Display for CounterStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} instructions, {} cycles, {} cache misses",
+            self.instructions, self.cycles, self.cache_misses
+        )
+    }
+}
+
+#[cfg(all(feature = "perf", target_os = "linux"))]
+mod sys {
+    use std::{
+        io,
+        os::fd::{AsRawFd, FromRawFd, OwnedFd},
+    };
+
+    const PERF_TYPE_HARDWARE: u32 = 0;
+    const PERF_COUNT_HW_CPU_CYCLES: u64 = 0;
+    const PERF_COUNT_HW_INSTRUCTIONS: u64 = 1;
+    const PERF_COUNT_HW_CACHE_MISSES: u64 = 3;
+
+    const FLAG_DISABLED: u64 = 1 << 0;
+    const FLAG_EXCLUDE_KERNEL: u64 = 1 << 5;
+    const FLAG_EXCLUDE_HV: u64 = 1 << 6;
+
+    const PERF_FLAG_FD_CLOEXEC: libc::c_ulong = 1 << 3;
+
+    const PERF_EVENT_IOC_ENABLE: libc::c_ulong = 0x2400;
+    const PERF_EVENT_IOC_DISABLE: libc::c_ulong = 0x2401;
+    const PERF_EVENT_IOC_RESET: libc::c_ulong = 0x2403;
+
+    /// `struct perf_event_attr` as of `PERF_ATTR_SIZE_VER0`.
+    #[repr(C)]
+    #[derive(Default)]
+    struct PerfEventAttr {
+        kind: u32,
+        size: u32,
+        config: u64,
+        sample_period: u64,
+        sample_type: u64,
+        read_format: u64,
+        flags: u64,
+        wakeup_events: u32,
+        bp_type: u32,
+        config1: u64,
+    }
+
+    pub struct Counter(OwnedFd);
+
+    impl Counter {
+        fn open(config: u64) -> io::Result<Self> {
+            let attr = PerfEventAttr {
+                kind: PERF_TYPE_HARDWARE,
+                #[allow(clippy::cast_possible_truncation)]
+                size: std::mem::size_of::<PerfEventAttr>() as u32,
+                config,
+                flags: FLAG_DISABLED | FLAG_EXCLUDE_KERNEL | FLAG_EXCLUDE_HV,
+                ..Default::default()
+            };
+
+            // SAFETY: `attr` is a valid, fully initialised `perf_event_attr` that outlives the call.
+            let fd = unsafe {
+                libc::syscall(
+                    libc::SYS_perf_event_open,
+                    &attr as *const PerfEventAttr,
+                    0,
+                    -1,
+                    -1,
+                    PERF_FLAG_FD_CLOEXEC,
+                )
+            };
+
+            if fd < 0 {
+                return Err(io::Error::last_os_error());
+            }
+
+            // SAFETY: the syscall returned a new file descriptor that nothing else owns.
+            #[allow(clippy::cast_possible_truncation)]
+            Ok(Self(unsafe { OwnedFd::from_raw_fd(fd as i32) }))
+        }
+
+        pub fn cycles() -> io::Result<Self> {
+            Self::open(PERF_COUNT_HW_CPU_CYCLES)
+        }
+
+        pub fn instructions() -> io::Result<Self> {
+            Self::open(PERF_COUNT_HW_INSTRUCTIONS)
+        }
+
+        pub fn cache_misses() -> io::Result<Self> {
+            Self::open(PERF_COUNT_HW_CACHE_MISSES)
+        }
+
+        fn ioctl(&self, request: libc::c_ulong) {
+            // SAFETY: the descriptor is a perf event and the requests take no argument.
+            unsafe {
+                libc::ioctl(self.0.as_raw_fd(), request as _, 0);
+            }
+        }
+
+        pub fn reset(&self) {
+            self.ioctl(PERF_EVENT_IOC_RESET);
+        }
+
+        pub fn enable(&self) {
+            self.ioctl(PERF_EVENT_IOC_ENABLE);
+        }
+
+        pub fn disable(&self) {
+            self.ioctl(PERF_EVENT_IOC_DISABLE);
+        }
+
+        pub fn read(&self) -> io::Result<u64> {
+            let mut value = 0u64;
+            // SAFETY: `value` is a valid buffer of exactly 8 bytes.
+            let read = unsafe {
+                libc::read(
+                    self.0.as_raw_fd(),
+                    std::ptr::addr_of_mut!(value).cast(),
+                    std::mem::size_of::<u64>(),
+                )
+            };
+
+            if read < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(value)
+        }
+    }
+}
+
+/// A set of hardware counters measuring the current thread.
+pub struct Counters {
+    #[cfg(all(feature = "perf", target_os = "linux"))]
+    counters: [sys::Counter; 3],
+}
+
+impl Counters {
+    /// Opens the instruction, cycle and cache miss counters.
+    /// Fails if the platform or the kernel (e.g. via `perf_event_paranoid`) does not permit access.
+    pub fn open() -> io::Result<Self> {
+        #[cfg(all(feature = "perf", target_os = "linux"))]
+        {
+            Ok(Self {
+                counters: [
+                    sys::Counter::instructions()?,
+                    sys::Counter::cycles()?,
+                    sys::Counter::cache_misses()?,
+                ],
+            })
+        }
+
+        #[cfg(not(all(feature = "perf", target_os = "linux")))]
+        {
+            Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "hardware counters require linux and the `perf` feature",
+            ))
+        }
+    }
+
+    /// Resets all counters to zero. Counting has to be started with [`Counters::resume`].
+    pub fn reset(&self) {
+        #[cfg(all(feature = "perf", target_os = "linux"))]
+        for counter in &self.counters {
+            counter.disable();
+            counter.reset();
+        }
+    }
+
+    /// Starts or continues counting.
+    pub fn resume(&self) {
+        #[cfg(all(feature = "perf", target_os = "linux"))]
+        for counter in &self.counters {
+            counter.enable();
+        }
+    }
+
+    /// Stops counting, keeping the accumulated values.
+    pub fn pause(&self) {
+        #[cfg(all(feature = "perf", target_os = "linux"))]
+        for counter in &self.counters {
+            counter.disable();
+        }
+    }
+
+    /// Reads the values accumulated since the last [`Counters::reset`].
+    pub fn read(&self) -> io::Result<CounterStats> {
+        #[cfg(all(feature = "perf", target_os = "linux"))]
+        {
+            let [instructions, cycles, cache_misses] = &self.counters;
+
+            Ok(CounterStats {
+                instructions: instructions.read()?,
+                cycles: cycles.read()?,
+                cache_misses: cache_misses.read()?,
+            })
+        }
+
+        #[cfg(not(all(feature = "perf", target_os = "linux")))]
+        {
+            Ok(CounterStats::default())
+        }
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::CounterStats;
+
+    #[test]
+    fn roundtrips_stats() {
+        let stats = CounterStats {
+            instructions: 1_000_000,
+            cycles: 400_000,
+            cache_misses: 12,
+        };
+        assert_eq!(
+            stats.to_string(),
+            "1000000 instructions, 400000 cycles, 12 cache misses"
+        );
+        assert_eq!(CounterStats::parse(&stats.to_string()), Some(stats));
+    }
+
+    #[test]
+    fn divides_per_iteration() {
+        let stats = CounterStats {
+            instructions: 1000,
+            cycles: 500,
+            cache_misses: 10,
+        };
+        assert_eq!(
+            stats.per_iteration(10),
+            CounterStats {
+                instructions: 100,
+                cycles: 50,
+                cache_misses: 1,
+            }
+        );
+    }
+}
@@ -0,0 +1,240 @@
+/// Metrics for how much a set of points looks like a picture rather than noise, for puzzles where
+/// points move until they briefly form an image.
+///
+/// Points moving with constant velocities on a wrapping `width` x `height` grid repeat after at most
+/// `width * height` steps, so [`find_picture`] scores every step of that period and returns the one
+/// that stands out the most:
+///
+/// ```ignore
+/// use advent_of_code::template::picture::{self, Metric};
+///
+/// let detection = picture::find_picture(width * height, Metric::Variance, |step| {
+///     (positions_at(step), width, height)
+/// });
+/// ```
+///
+/// All metrics are lower for more picture-like sets of points.
+use std::collections::HashSet;
+
+/// How far below the average score of a period the picture has to be, in standard deviations,
+/// to count as a [clear](Detection::is_clear) detection.
+pub const CLEAR_Z_SCORE: f64 = -4.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Metric {
+    /// The sum of the variances of the x and y coordinates: pictures are compact.
+    Variance,
+    /// The Shannon entropy of the number of points per 4x4 block: pictures are ordered.
+    Entropy,
+    /// The size of the largest 8-connected group of points, negated: pictures are connected.
+    LargestComponent,
+    /// The product of the number of points per quadrant: pictures are rarely centred.
+    SafetyFactor,
+}
+
+impl Metric {
+    pub fn score(self, points: &[(usize, usize)], width: usize, height: usize) -> f64 {
+        match self {
+            Self::Variance => {
+                let (x, y) = variance(points);
+                x + y
+            }
+            Self::Entropy => entropy(points, width, height, 4),
+            Self::LargestComponent => -(largest_component(points) as f64),
+            Self::SafetyFactor => safety_factor(points, width, height) as f64,
+        }
+    }
+}
+
+/// The variance of the x and y coordinates.
+pub fn variance(points: &[(usize, usize)]) -> (f64, f64) {
+    if points.is_empty() {
+        return (0.0, 0.0);
+    }
+
+    let n = points.len() as f64;
+    let (mut sum, mut squares) = ((0.0, 0.0), (0.0, 0.0));
+
+    for &(x, y) in points {
+        let (x, y) = (x as f64, y as f64);
+        sum = (sum.0 + x, sum.1 + y);
+        squares = (squares.0 + x * x, squares.1 + y * y);
+    }
+
+    (
+        squares.0 / n - (sum.0 / n).powi(2),
+        squares.1 / n - (sum.1 / n).powi(2),
+    )
+}
+
+/// The Shannon entropy in bits of how the points are spread over `block` x `block` squares.
+pub fn entropy(points: &[(usize, usize)], width: usize, height: usize, block: usize) -> f64 {
+    let columns = width.div_ceil(block);
+    let mut counts = vec![0usize; columns * height.div_ceil(block)];
+
+    for &(x, y) in points {
+        counts[y / block * columns + x / block] += 1;
+    }
+
+    let n = points.len() as f64;
+    counts
+        .into_iter()
+        .filter(|&count| count > 0)
+        .map(|count| {
+            let p = count as f64 / n;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// The number of points in the largest group of points that touch, including diagonally.
+/// Duplicate points are counted once.
+pub fn largest_component(points: &[(usize, usize)]) -> usize {
+    let mut remaining: HashSet<(usize, usize)> = points.iter().copied().collect();
+    let mut largest = 0;
+
+    while let Some(&start) = remaining.iter().next() {
+        remaining.remove(&start);
+        let mut stack = vec![start];
+        let mut size = 0;
+
+        while let Some((x, y)) = stack.pop() {
+            size += 1;
+
+            for dy in -1..=1 {
+                for dx in -1..=1 {
+                    let neighbour = (x.checked_add_signed(dx), y.checked_add_signed(dy));
+                    if let (Some(nx), Some(ny)) = neighbour {
+                        if remaining.remove(&(nx, ny)) {
+                            stack.push((nx, ny));
+                        }
+                    }
+                }
+            }
+        }
+
+        largest = largest.max(size);
+    }
+
+    largest
+}
+
+/// The product of the number of points in each quadrant. Points on the middle row or column
+/// of an odd-sized grid do not belong to any quadrant.
+pub fn safety_factor(points: &[(usize, usize)], width: usize, height: usize) -> u64 {
+    points
+        .iter()
+        .filter(|&&(x, y)| {
+            !(width % 2 == 1 && x == width / 2 || height % 2 == 1 && y == height / 2)
+        })
+        .fold([0u64; 4], |mut quadrants, &(x, y)| {
+            let quadrant = usize::from(x < width / 2) * 2 + usize::from(y < height / 2);
+            quadrants[quadrant] += 1;
+            quadrants
+        })
+        .into_iter()
+        .product()
+}
+
+/// The step of a period that looks most like a picture.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Detection {
+    pub step: usize,
+    pub score: f64,
+    /// How many standard deviations the score is from the average score of the period.
+    pub z_score: f64,
+}
+
+impl Detection {
+    /// Whether the step stands out from the rest of the period, rather than merely being
+    /// the least noisy of all noisy steps.
+    pub fn is_clear(&self) -> bool {
+        self.z_score <= CLEAR_Z_SCORE
+    }
+}
+
+/// Scores the points of every step in `1..=period` and returns the lowest scoring step,
+/// the earliest one on ties. `points_at` returns the points of a step and the grid size.
+pub fn find_picture(
+    period: usize,
+    metric: Metric,
+    mut points_at: impl FnMut(usize) -> (Vec<(usize, usize)>, usize, usize),
+) -> Option<Detection> {
+    let scores: Vec<f64> = (1..=period)
+        .map(|step| {
+            let (points, width, height) = points_at(step);
+            metric.score(&points, width, height)
+        })
+        .collect();
+
+    let (index, &score) = scores
+        .iter()
+        .enumerate()
+        .min_by(|a, b| a.1.total_cmp(b.1))?;
+
+    let n = scores.len() as f64;
+    let mean = scores.iter().sum::<f64>() / n;
+    let deviation = (scores.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / n).sqrt();
+
+    Some(Detection {
+        step: index + 1,
+        score,
+        z_score: if deviation > 0.0 {
+            (score - mean) / deviation
+        } else {
+            0.0
+        },
+    })
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{entropy, find_picture, largest_component, safety_factor, variance, Metric};
+
+    /// A 7x7 square at step 7, scattered otherwise.
+    fn points_at(step: usize) -> (Vec<(usize, usize)>, usize, usize) {
+        let points = (0..49)
+            .map(|i| {
+                let (x, y) = (10 + i % 7, 10 + i / 7);
+                let (vx, vy) = (i * 7 + 1, i * 13 + 3);
+                (
+                    (x + vx * (step + 60 - 7)) % 60,
+                    (y + vy * (step + 60 - 7)) % 60,
+                )
+            })
+            .collect();
+        (points, 60, 60)
+    }
+
+    #[test]
+    fn computes_metrics() {
+        let square = [(0, 0), (1, 0), (0, 1), (1, 1)];
+        assert_eq!(variance(&square), (0.25, 0.25));
+        assert_eq!(
+            largest_component(&[(0, 0), (1, 1), (5, 5), (2, 2), (0, 0)]),
+            3
+        );
+        assert_eq!(
+            safety_factor(&[(0, 0), (4, 0), (0, 4), (4, 4), (2, 0)], 5, 5),
+            1
+        );
+        assert_eq!(safety_factor(&[(0, 0)], 4, 4), 0);
+
+        assert_eq!(entropy(&square, 8, 8, 4), 0.0);
+        assert_eq!(entropy(&[(0, 0), (4, 0), (0, 4), (4, 4)], 8, 8, 4), 2.0);
+    }
+
+    #[test]
+    fn finds_pictures() {
+        for metric in [Metric::Variance, Metric::Entropy, Metric::LargestComponent] {
+            let detection = find_picture(60, metric, points_at).unwrap();
+            assert_eq!(detection.step, 7, "{metric:?}");
+            assert!(detection.is_clear(), "{metric:?}: {detection:?}");
+        }
+
+        let detection = find_picture(3, Metric::Variance, |_| (vec![(0, 0)], 1, 1)).unwrap();
+        assert_eq!(detection.step, 1);
+        assert!(!detection.is_clear());
+        assert_eq!(find_picture(0, Metric::Variance, points_at), None);
+    }
+}
@@ -0,0 +1,603 @@
+/// Property-based testing: checks that a property holds for randomly generated puzzle inputs,
+/// e.g. that a brute-force reference and an optimised solution agree.
+/// Failing inputs are shrunk to a minimal counterexample before they are reported.
+///
+/// ```ignore
+/// use advent_of_code::template::property::{self, grid};
+///
+/// #[test]
+/// fn optimised_walk_matches_brute_force() {
+///     property::assert_equivalent(&grid(1..=8, 1..=8, ".#"), |grid| brute_force(&grid.to_string()), |grid| part_two(&grid.to_string()));
+/// }
+/// ```
+///
+/// Runs are seeded randomly. The seed is part of the failure message, set `AOC_PROPERTY_SEED` to reproduce a run.
+use std::{
+    env,
+    fmt::{Debug, Display},
+    ops::RangeInclusive,
+    panic::{self, AssertUnwindSafe},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// A small, seedable pseudo-random number generator (SplitMix64).
+#[derive(Clone, Debug)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A uniformly distributed value in `range`.
+    pub fn range(&mut self, range: &RangeInclusive<i64>) -> i64 {
+        let span = (i128::from(*range.end()) - i128::from(*range.start()) + 1) as u128;
+        let offset = u128::from(self.next_u64()) % span;
+        (i128::from(*range.start()) + offset as i128) as i64
+    }
+
+    /// A uniformly distributed index below `len`.
+    pub fn index(&mut self, len: usize) -> usize {
+        (self.next_u64() % len as u64) as usize
+    }
+
+    fn len(&mut self, range: &RangeInclusive<usize>) -> usize {
+        range.start() + self.index(range.end() - range.start() + 1)
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Generates random values and simpler variants of a value.
+pub trait Strategy {
+    type Value: Clone + Debug;
+
+    fn generate(&self, rng: &mut Rng) -> Self::Value;
+
+    /// Simpler candidates for a failing value, most promising first.
+    fn shrink(&self, _value: &Self::Value) -> Vec<Self::Value> {
+        vec![]
+    }
+
+    /// Only generates values matching `predicate`, e.g. to exclude degenerate inputs.
+    fn filter<F: Fn(&Self::Value) -> bool>(self, predicate: F) -> Filter<Self, F>
+    where
+        Self: Sized,
+    {
+        Filter {
+            strategy: self,
+            predicate,
+        }
+    }
+}
+
+/// Integers in a range, shrinking towards zero (or the bound closest to it).
+#[derive(Clone)]
+pub struct Int(RangeInclusive<i64>);
+
+pub fn int(range: RangeInclusive<i64>) -> Int {
+    assert!(!range.is_empty(), "empty range");
+    Int(range)
+}
+
+impl Strategy for Int {
+    type Value = i64;
+
+    fn generate(&self, rng: &mut Rng) -> i64 {
+        rng.range(&self.0)
+    }
+
+    fn shrink(&self, value: &i64) -> Vec<i64> {
+        let target = 0.clamp(*self.0.start(), *self.0.end());
+
+        // jump to the target first, then approach the value in halving steps.
+        let mut candidates = vec![];
+        let mut delta = value - target;
+        while delta != 0 {
+            candidates.push(value - delta);
+            delta /= 2;
+        }
+        candidates
+    }
+}
+
+/// One of a fixed set of values, shrinking towards the first one.
+#[derive(Clone)]
+pub struct OneOf<T>(Vec<T>);
+
+pub fn one_of<T: Clone + Debug + PartialEq>(choices: &[T]) -> OneOf<T> {
+    assert!(!choices.is_empty(), "no choices");
+    OneOf(choices.to_vec())
+}
+
+impl<T: Clone + Debug + PartialEq> Strategy for OneOf<T> {
+    type Value = T;
+
+    fn generate(&self, rng: &mut Rng) -> T {
+        self.0[rng.index(self.0.len())].clone()
+    }
+
+    fn shrink(&self, value: &T) -> Vec<T> {
+        let index = self
+            .0
+            .iter()
+            .position(|choice| choice == value)
+            .unwrap_or(0);
+        self.0[..index].to_vec()
+    }
+}
+
+/// Lists of values, shrinking by removing elements and by shrinking single elements.
+#[derive(Clone)]
+pub struct VecOf<S> {
+    element: S,
+    len: RangeInclusive<usize>,
+}
+
+pub fn vec_of<S: Strategy>(element: S, len: RangeInclusive<usize>) -> VecOf<S> {
+    VecOf { element, len }
+}
+
+/// A list of numbers, e.g. one number per line.
+pub fn numbers(range: RangeInclusive<i64>, len: RangeInclusive<usize>) -> VecOf<Int> {
+    vec_of(int(range), len)
+}
+
+/// A list of blocks, e.g. separated by blank lines in the input. See [`blocks_to_input`].
+pub fn blocks<S: Strategy>(block: S, count: RangeInclusive<usize>) -> VecOf<S> {
+    vec_of(block, count)
+}
+
+impl<S: Strategy> Strategy for VecOf<S> {
+    type Value = Vec<S::Value>;
+
+    fn generate(&self, rng: &mut Rng) -> Self::Value {
+        let len = rng.len(&self.len);
+        (0..len).map(|_| self.element.generate(rng)).collect()
+    }
+
+    fn shrink(&self, value: &Self::Value) -> Vec<Self::Value> {
+        let mut candidates = vec![];
+
+        if value.len() > *self.len.start() {
+            candidates.push(value[..*self.len.start()].to_vec());
+            candidates.push(value[..(value.len() / 2).max(*self.len.start())].to_vec());
+            candidates.extend((0..value.len()).map(|i| {
+                let mut candidate = value.clone();
+                candidate.remove(i);
+                candidate
+            }));
+        }
+
+        for (i, element) in value.iter().enumerate() {
+            candidates.extend(self.element.shrink(element).into_iter().map(|shrunk| {
+                let mut candidate = value.clone();
+                candidate[i] = shrunk;
+                candidate
+            }));
+        }
+
+        candidates.retain(|candidate| candidate.len() >= *self.len.start());
+        candidates
+    }
+}
+
+/// A rectangular grid of characters, displayed one row per line.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Grid {
+    pub cells: Vec<Vec<char>>,
+}
+
+impl Grid {
+    pub fn width(&self) -> usize {
+        self.cells.first().map_or(0, Vec::len)
+    }
+
+    pub fn height(&self) -> usize {
+        self.cells.len()
+    }
+}
+
+impl Display for Grid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in &self.cells {
+            writeln!(f, "{}", row.iter().collect::<String>())?;
+        }
+        Ok(())
+    }
+}
+
+impl Debug for Grid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Grid {}x{}:", self.width(), self.height())?;
+        write!(f, "{self}")
+    }
+}
+
+/// Grids with cells from `cells`, shrinking by removing rows and columns and by replacing
+/// cells with the first character of `cells`.
+#[derive(Clone)]
+pub struct GridOf {
+    width: RangeInclusive<usize>,
+    height: RangeInclusive<usize>,
+    cells: Vec<char>,
+}
+
+pub fn grid(width: RangeInclusive<usize>, height: RangeInclusive<usize>, cells: &str) -> GridOf {
+    assert!(!cells.is_empty(), "no cells");
+    GridOf {
+        width,
+        height,
+        cells: cells.chars().collect(),
+    }
+}
+
+impl Strategy for GridOf {
+    type Value = Grid;
+
+    fn generate(&self, rng: &mut Rng) -> Grid {
+        let width = rng.len(&self.width);
+        let height = rng.len(&self.height);
+        Grid {
+            cells: (0..height)
+                .map(|_| {
+                    (0..width)
+                        .map(|_| self.cells[rng.index(self.cells.len())])
+                        .collect()
+                })
+                .collect(),
+        }
+    }
+
+    fn shrink(&self, grid: &Grid) -> Vec<Grid> {
+        let mut candidates = vec![];
+
+        if grid.height() > *self.height.start() {
+            for row in [grid.height() - 1, 0] {
+                let mut candidate = grid.clone();
+                candidate.cells.remove(row);
+                candidates.push(candidate);
+            }
+        }
+
+        if grid.width() > *self.width.start() {
+            for column in [grid.width() - 1, 0] {
+                let mut candidate = grid.clone();
+                for row in &mut candidate.cells {
+                    row.remove(column);
+                }
+                candidates.push(candidate);
+            }
+        }
+
+        let simplest = self.cells[0];
+        for (y, row) in grid.cells.iter().enumerate() {
+            for (x, cell) in row.iter().enumerate() {
+                if *cell != simplest {
+                    let mut candidate = grid.clone();
+                    candidate.cells[y][x] = simplest;
+                    candidates.push(candidate);
+                }
+            }
+        }
+
+        candidates
+    }
+}
+
+macro_rules! impl_tuple_strategy {
+    ($($name:ident $index:tt),+) => {
+        impl<$($name: Strategy),+> Strategy for ($($name,)+) {
+            type Value = ($($name::Value,)+);
+
+            fn generate(&self, rng: &mut Rng) -> Self::Value {
+                ($(self.$index.generate(rng),)+)
+            }
+
+            fn shrink(&self, value: &Self::Value) -> Vec<Self::Value> {
+                let mut candidates = vec![];
+                $(
+                    for shrunk in self.$index.shrink(&value.$index) {
+                        let mut candidate = value.clone();
+                        candidate.$index = shrunk;
+                        candidates.push(candidate);
+                    }
+                )+
+                candidates
+            }
+        }
+    };
+}
+
+impl_tuple_strategy!(A 0, B 1);
+impl_tuple_strategy!(A 0, B 1, C 2);
+impl_tuple_strategy!(A 0, B 1, C 2, D 3);
+impl_tuple_strategy!(A 0, B 1, C 2, D 3, E 4);
+impl_tuple_strategy!(A 0, B 1, C 2, D 3, E 4, F 5);
+
+/// See [`Strategy::filter`].
+#[derive(Clone)]
+pub struct Filter<S, F> {
+    strategy: S,
+    predicate: F,
+}
+
+impl<S: Strategy, F: Fn(&S::Value) -> bool> Strategy for Filter<S, F> {
+    type Value = S::Value;
+
+    fn generate(&self, rng: &mut Rng) -> S::Value {
+        for _ in 0..10_000 {
+            let value = self.strategy.generate(rng);
+            if (self.predicate)(&value) {
+                return value;
+            }
+        }
+        panic!("filter rejected 10000 values in a row, loosen the filter or adjust the strategy.");
+    }
+
+    fn shrink(&self, value: &S::Value) -> Vec<S::Value> {
+        let mut candidates = self.strategy.shrink(value);
+        candidates.retain(|candidate| (self.predicate)(candidate));
+        candidates
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Formats values one per line, e.g. a list of numbers.
+pub fn lines_to_input<T: Display>(values: &[T]) -> String {
+    values.iter().map(|value| format!("{value}\n")).collect()
+}
+
+/// Joins blocks with blank lines.
+pub fn blocks_to_input<T: AsRef<str>>(blocks: &[T]) -> String {
+    let blocks: Vec<&str> = blocks
+        .iter()
+        .map(|block| block.as_ref().trim_end())
+        .collect();
+    blocks.join("\n\n") + "\n"
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[derive(Clone, Debug)]
+pub struct Config {
+    /// The number of random values to check.
+    pub cases: usize,
+    /// The seed of the run. Defaults to `AOC_PROPERTY_SEED`, or a random seed.
+    pub seed: Option<u64>,
+    /// The maximum number of property evaluations spent on shrinking.
+    pub max_shrink_steps: usize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            cases: 100,
+            seed: None,
+            max_shrink_steps: 2_000,
+        }
+    }
+}
+
+/// A failing value and why it failed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Failure<T> {
+    pub seed: u64,
+    pub value: T,
+    pub error: String,
+    pub shrink_steps: usize,
+}
+
+fn get_seed(config: &Config) -> u64 {
+    config
+        .seed
+        .or_else(|| env::var("AOC_PROPERTY_SEED").ok()?.parse().ok())
+        .unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |duration| duration.as_nanos() as u64)
+        })
+}
+
+/// Evaluates the property, treating panics as failures.
+fn evaluate<T>(property: &impl Fn(&T) -> Result<(), String>, value: &T) -> Result<(), String> {
+    panic::catch_unwind(AssertUnwindSafe(|| property(value))).unwrap_or_else(|payload| {
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(ToString::to_string)
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic".into());
+        Err(format!("panicked: {message}"))
+    })
+}
+
+/// Runs the property against `config.cases` random values and returns the shrunk
+/// counterexample if it fails for any of them.
+pub fn find_failure<S: Strategy>(
+    config: &Config,
+    strategy: &S,
+    property: impl Fn(&S::Value) -> Result<(), String>,
+) -> Option<Failure<S::Value>> {
+    let seed = get_seed(config);
+    let mut rng = Rng::new(seed);
+
+    for _ in 0..config.cases {
+        let value = strategy.generate(&mut rng);
+
+        if let Err(error) = evaluate(&property, &value) {
+            let mut failure = Failure {
+                seed,
+                value,
+                error,
+                shrink_steps: 0,
+            };
+
+            // greedily move to the first simpler candidate that still fails.
+            let mut steps = 0;
+            'shrink: while steps < config.max_shrink_steps {
+                for candidate in strategy.shrink(&failure.value) {
+                    steps += 1;
+                    if let Err(error) = evaluate(&property, &candidate) {
+                        failure.value = candidate;
+                        failure.error = error;
+                        failure.shrink_steps += 1;
+                        continue 'shrink;
+                    }
+                    if steps >= config.max_shrink_steps {
+                        break;
+                    }
+                }
+                break;
+            }
+
+            return Some(failure);
+        }
+    }
+
+    None
+}
+
+/// Panics with a minimal counterexample if the property fails for a generated value.
+pub fn check<S: Strategy>(strategy: &S, property: impl Fn(&S::Value) -> Result<(), String>) {
+    check_with(&Config::default(), strategy, property);
+}
+
+/// Like [`check`], with a custom configuration.
+pub fn check_with<S: Strategy>(
+    config: &Config,
+    strategy: &S,
+    property: impl Fn(&S::Value) -> Result<(), String>,
+) {
+    if let Some(failure) = find_failure(config, strategy, property) {
+        panic!(
+            "property failed: {}\nminimal counterexample (after {} shrink step(s)):\n{:#?}\nrerun with AOC_PROPERTY_SEED={}",
+            failure.error, failure.shrink_steps, failure.value, failure.seed
+        );
+    }
+}
+
+/// Checks that a reference implementation and an optimised one return the same result for every generated value.
+pub fn assert_equivalent<S: Strategy, T: PartialEq + Debug>(
+    strategy: &S,
+    reference: impl Fn(&S::Value) -> T,
+    optimised: impl Fn(&S::Value) -> T,
+) {
+    check(strategy, |value| {
+        let expected = reference(value);
+        let actual = optimised(value);
+
+        if expected == actual {
+            Ok(())
+        } else {
+            Err(format!(
+                "reference returned {expected:?}, optimised returned {actual:?}"
+            ))
+        }
+    });
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{
+        blocks_to_input, find_failure, grid, int, lines_to_input, numbers, vec_of, Config, Rng,
+        Strategy,
+    };
+
+    fn config() -> Config {
+        Config {
+            seed: Some(42),
+            ..Config::default()
+        }
+    }
+
+    #[test]
+    fn generates_within_bounds() {
+        let mut rng = Rng::new(1);
+        for _ in 0..1000 {
+            let value = int(-3..=5).generate(&mut rng);
+            assert!((-3..=5).contains(&value));
+        }
+
+        let grid = grid(2..=4, 1..=3, ".#").generate(&mut rng);
+        assert!((2..=4).contains(&grid.width()) && (1..=3).contains(&grid.height()));
+    }
+
+    #[test]
+    fn shrinks_within_bounds() {
+        let strategy = vec_of(int(0..=9), 5..=20);
+        let candidates = strategy.shrink(&vec![9; 10]);
+
+        assert!(!candidates.is_empty());
+        assert!(candidates.iter().all(|candidate| candidate.len() >= 5));
+    }
+
+    #[test]
+    fn shrinks_numbers() {
+        let failure = find_failure(
+            &config(),
+            &numbers(0..=1000, 0..=20),
+            |numbers| match numbers.iter().any(|n| *n >= 500) {
+                true => Err("too large".into()),
+                false => Ok(()),
+            },
+        )
+        .unwrap();
+
+        assert_eq!(failure.value, vec![500]);
+    }
+
+    #[test]
+    fn shrinks_grids() {
+        let failure = find_failure(&config(), &grid(1..=10, 1..=10, ".#"), |grid| {
+            match grid.to_string().matches('#').count() >= 2 {
+                true => Err("two walls".into()),
+                false => Ok(()),
+            }
+        })
+        .unwrap();
+
+        assert_eq!(failure.value.to_string().matches('#').count(), 2);
+        // two walls on a diagonal can not be shrunk any further.
+        assert!(failure.value.width() * failure.value.height() <= 4);
+    }
+
+    #[test]
+    fn treats_panics_as_failures() {
+        let failure = find_failure(&config(), &int(1..=100), |n| {
+            assert!(*n < 10, "n is {n}");
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(failure.value, 10);
+        assert!(failure.error.contains("n is 10"));
+    }
+
+    #[test]
+    fn passes_valid_properties() {
+        assert!(
+            find_failure(&config(), &int(1..=100).filter(|n| n % 2 == 0), |n| {
+                match n % 2 {
+                    0 => Ok(()),
+                    _ => Err("odd".into()),
+                }
+            })
+            .is_none()
+        );
+    }
+
+    #[test]
+    fn formats_inputs() {
+        assert_eq!(lines_to_input(&[1, 2]), "1\n2\n");
+        assert_eq!(blocks_to_input(&["a\nb\n", "c"]), "a\nb\n\nc\n");
+    }
+}
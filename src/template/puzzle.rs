@@ -0,0 +1,229 @@
+//! Extracts information from puzzle descriptions downloaded to `data/puzzles/NN.md`.
+//! Descriptions may contain raw HTML (`<pre><code>`, `<code><em>`) or its markdown equivalent
+//! (fenced code blocks, `` `*11*` ``), both are recognised.
+
+/// Example input and answers guessed from a puzzle description.
+/// These are heuristics and should always be reviewed.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Guess {
+    /// The largest code block of part one.
+    pub example: Option<String>,
+    /// The last emphasized number of part one.
+    pub part_one: Option<u64>,
+    /// The last emphasized number of part two, if part two refers to the example of part one.
+    pub part_two: Option<u64>,
+}
+
+const CODE_BLOCKS: [(&str, &str); 2] = [("<pre><code>", "</code></pre>"), ("```", "```")];
+
+const EMPHASIS: [(&str, &str); 5] = [
+    ("<code><em>", "</em></code>"),
+    ("<em><code>", "</code></em>"),
+    ("`*", "*`"),
+    ("*`", "`*"),
+    ("**", "**"),
+];
+
+const PART_TWO_HEADING: &str = "--- Part Two ---";
+
+/// Extracts the puzzle title, e.g. `Historian Hysteria` from `--- Day 1: Historian Hysteria ---`.
+pub fn title(description: &str) -> Option<String> {
+    description.lines().find_map(|line| {
+        let line = strip_tags(line);
+        let line = line.trim_start_matches(['#', '\\', ' ']);
+        let heading = line.strip_prefix("--- Day ")?.strip_suffix(" ---")?;
+        let (_, title) = heading.split_once(": ")?;
+        Some(title.trim().to_string())
+    })
+}
+
+/// The number of parts solved, going by the answers the website adds to a saved description.
+pub fn solved_parts(description: &str) -> u8 {
+    let solved = description.matches("Your puzzle answer was").count();
+    u8::try_from(solved.min(2)).unwrap_or(2)
+}
+
+/// The text of a single part, starting at its heading. Returns `None` for a part that is not
+/// in the description, e.g. part two before part one was solved.
+pub fn part(description: &str, part: u8) -> Option<&str> {
+    let part_two_start = description
+        .find(PART_TWO_HEADING)
+        .map(|i| description[..i].rfind('\n').map_or(0, |line| line + 1));
+
+    match (part, part_two_start) {
+        (1, Some(i)) => Some(&description[..i]),
+        (1, None) => Some(description),
+        (2, Some(i)) => Some(&description[i..]),
+        _ => None,
+    }
+}
+
+/// Guesses the example input and the expected answers of both parts.
+pub fn guess(description: &str) -> Guess {
+    let (part_one, part_two) = match description.find(PART_TWO_HEADING) {
+        Some(i) => description.split_at(i),
+        None => (description, ""),
+    };
+
+    let example = code_blocks(part_one).max_by_key(String::len);
+
+    // part two only shares the example if it does not introduce a new multi-line one.
+    let part_two_answer = if code_blocks(part_two).any(|block| block.contains('\n')) {
+        None
+    } else {
+        last_emphasized_number(part_two)
+    };
+
+    Guess {
+        part_one: example.as_ref().and(last_emphasized_number(part_one)),
+        part_two: example.as_ref().and(part_two_answer),
+        example,
+    }
+}
+
+/// Yields the contents of all delimited spans, in any order.
+fn delimited<'a>(text: &'a str, open: &'a str, close: &'a str) -> Vec<(usize, &'a str)> {
+    let mut spans = vec![];
+    let mut offset = 0;
+
+    while let Some(start) = text[offset..].find(open) {
+        let content_start = offset + start + open.len();
+        let Some(end) = text[content_start..].find(close) else {
+            break;
+        };
+        spans.push((offset + start, &text[content_start..content_start + end]));
+        offset = content_start + end + close.len();
+    }
+
+    spans
+}
+
+fn code_blocks(text: &str) -> impl Iterator<Item = String> + '_ {
+    CODE_BLOCKS
+        .iter()
+        .flat_map(|(open, close)| {
+            delimited(text, open, close)
+                .into_iter()
+                .map(move |(_, block)| match (*open, block.split_once('\n')) {
+                    // drop the info string of fenced code blocks, e.g. ```text
+                    ("```", Some((_, rest))) => rest,
+                    _ => block,
+                })
+        })
+        .map(|block| {
+            decode_entities(&strip_tags(block))
+                .trim_end_matches('\n')
+                .to_string()
+        })
+        .filter(|block| !block.trim().is_empty())
+}
+
+fn last_emphasized_number(text: &str) -> Option<u64> {
+    EMPHASIS
+        .iter()
+        .flat_map(|(open, close)| delimited(text, open, close))
+        .filter_map(|(position, value)| Some((position, value.trim().parse().ok()?)))
+        .max_by_key(|(position, _)| *position)
+        .map(|(_, value)| value)
+}
+
+fn strip_tags(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut in_tag = false;
+
+    for c in text.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => result.push(c),
+            _ => {}
+        }
+    }
+
+    result
+}
+
+fn decode_entities(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{guess, part, solved_parts, title, Guess};
+
+    #[test]
+    fn parses_titles() {
+        let description = "\\--- Day 12: Garden Groups ---\n----------\n\nWhy not search for the Chief Historian near the gardener?";
+        assert_eq!(title(description), Some("Garden Groups".into()));
+        assert_eq!(
+            title("<h2>--- Day 3: Mull It Over ---</h2>"),
+            Some("Mull It Over".into())
+        );
+        assert_eq!(title("no heading"), None);
+    }
+
+    #[test]
+    fn guesses_from_html() {
+        let description =
+            "<p>For example:</p>\n<pre><code>#####\n3   4\n<em>2</em>   5\n</code></pre>\n\
+            <p>A <code>1</code> and a <code>3</code>.</p>\n\
+            <p>The total distance is <code><em>11</em></code>.</p>\n\
+            <h2 id=\"part2\">--- Part Two ---</h2>\n\
+            <p>So, for these example lists, the similarity score is <code><em>31</em></code>.</p>";
+
+        assert_eq!(
+            guess(description),
+            Guess {
+                example: Some("#####\n3   4\n2   5".into()),
+                part_one: Some(11),
+                part_two: Some(31),
+            }
+        );
+    }
+
+    #[test]
+    fn guesses_from_markdown() {
+        let description = "For example:\n\n```\nxmul(2,4)&amp;mul[3,7]\n```\n\n\
+            Adding up the results produces `*161*`.\n\n\
+            \\--- Part Two ---\n----------\n\n\
+            For example:\n\n```\nxmul(2,4)do()\ndon't()mul(5,5)\n```\n\n\
+            This time, the sum of the results is `*48*`.";
+
+        assert_eq!(
+            guess(description),
+            Guess {
+                example: Some("xmul(2,4)&mul[3,7]".into()),
+                part_one: Some(161),
+                part_two: None,
+            }
+        );
+    }
+
+    #[test]
+    fn guesses_nothing_without_example() {
+        assert_eq!(guess("The answer is `*42*`."), Guess::default());
+    }
+
+    #[test]
+    fn splits_parts() {
+        let description = "\\--- Day 1: Test ---\none\n\n\\--- Part Two ---\ntwo";
+        assert_eq!(part(description, 1), Some("\\--- Day 1: Test ---\none\n\n"));
+        assert_eq!(part(description, 2), Some("\\--- Part Two ---\ntwo"));
+        assert_eq!(part("only one", 2), None);
+    }
+
+    #[test]
+    fn counts_solved_parts() {
+        assert_eq!(solved_parts("\\--- Day 1: Test ---"), 0);
+        assert_eq!(
+            solved_parts(
+                "Your puzzle answer was `11`.\n\\--- Part Two ---\nYour puzzle answer was `31`."
+            ),
+            2
+        );
+    }
+}
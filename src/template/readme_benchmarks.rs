@@ -2,6 +2,7 @@
 /// The approach taken is similar to how `aoc-readme-stars` handles this.
 use std::{fs, io};
 
+use crate::template::memory::{format_bytes, MemoryStats};
 use crate::template::timings::Timings;
 use crate::template::Day;
 
@@ -52,26 +53,51 @@ fn locate_table(readme: &str) -> Result<TablePosition, Error> {
     Ok(TablePosition { pos_start, pos_end })
 }
 
+fn format_memory_cell(memory: Option<MemoryStats>) -> String {
+    match memory {
+        Some(memory) => format!(
+            "`{}` ({} allocs)",
+            format_bytes(memory.peak_bytes),
+            memory.allocations
+        ),
+        None => "-".into(),
+    }
+}
+
 fn construct_table(prefix: &str, timings: Timings, total_millis: f64) -> String {
     let header = format!("{prefix} Benchmarks");
+    // memory columns are only shown once heap statistics have been stored via `--memory`.
+    let has_memory = timings.has_memory();
 
-    let mut lines: Vec<String> = vec![
-        MARKER.into(),
-        header,
-        String::new(),
-        "| Day | Part 1 | Part 2 |".into(),
-        "| :---: | :---: | :---:  |".into(),
-    ];
+    let mut lines: Vec<String> = vec![MARKER.into(), header, String::new()];
+
+    if has_memory {
+        lines.push("| Day | Part 1 | Part 2 | Part 1 (peak heap) | Part 2 (peak heap) |".into());
+        lines.push("| :---: | :---: | :---:  | :---: | :---: |".into());
+    } else {
+        lines.push("| Day | Part 1 | Part 2 |".into());
+        lines.push("| :---: | :---: | :---:  |".into());
+    }
 
     for timing in timings.data {
         let path = get_path_for_bin(timing.day);
-        lines.push(format!(
+        let mut line = format!(
             "| [Day {}]({}) | `{}` | `{}` |",
             timing.day.into_inner(),
             path,
             timing.part_1.unwrap_or_else(|| "-".into()),
             timing.part_2.unwrap_or_else(|| "-".into())
-        ));
+        );
+
+        if has_memory {
+            line.push_str(&format!(
+                " {} | {} |",
+                format_memory_cell(timing.part_1_memory),
+                format_memory_cell(timing.part_2_memory)
+            ));
+        }
+
+        lines.push(line);
     }
 
     lines.push(String::new());
@@ -100,7 +126,9 @@ pub fn update(timings: Timings) -> Result<(), Error> {
 #[cfg(feature = "test_lib")]
 mod tests {
     use super::{update_content, MARKER};
-    use crate::{day, template::timings::Timing, template::timings::Timings};
+    use crate::{
+        day, template::memory::MemoryStats, template::timings::Timing, template::timings::Timings,
+    };
 
     fn get_mock_timings() -> Timings {
         Timings {
@@ -110,18 +138,30 @@ mod tests {
                     part_1: Some("10ms".into()),
                     part_2: Some("20ms".into()),
                     total_nanos: 3e+10,
+                    part_1_memory: None,
+                    part_2_memory: None,
+                    part_1_counters: None,
+                    part_2_counters: None,
                 },
                 Timing {
                     day: day!(2),
                     part_1: Some("30ms".into()),
                     part_2: Some("40ms".into()),
                     total_nanos: 7e+10,
+                    part_1_memory: None,
+                    part_2_memory: None,
+                    part_1_counters: None,
+                    part_2_counters: None,
                 },
                 Timing {
                     day: day!(4),
                     part_1: Some("40ms".into()),
                     part_2: Some("50ms".into()),
                     total_nanos: 9e+10,
+                    part_1_memory: None,
+                    part_2_memory: None,
+                    part_1_counters: None,
+                    part_2_counters: None,
                 },
             ],
         }
@@ -180,4 +220,30 @@ mod tests {
         .join("\n");
         assert_eq!(s, expected);
     }
+
+    #[test]
+    fn format_benchmarks_with_memory() {
+        let mut timings = get_mock_timings();
+        timings.data[0].part_1_memory = Some(MemoryStats {
+            allocations: 3,
+            total_bytes: 4096,
+            peak_bytes: 1536,
+        });
+
+        let mut s = format!("{}\n{}", MARKER, MARKER);
+        update_content(&mut s, timings, 190.0).unwrap();
+
+        assert_eq!(
+            s.contains("| Day | Part 1 | Part 2 | Part 1 (peak heap) | Part 2 (peak heap) |"),
+            true
+        );
+        assert_eq!(
+            s.contains("| [Day 1](./src/bin/01.rs) | `10ms` | `20ms` | `1.5 KiB` (3 allocs) | - |"),
+            true
+        );
+        assert_eq!(
+            s.contains("| [Day 2](./src/bin/02.rs) | `30ms` | `40ms` | - | - |"),
+            true
+        );
+    }
 }
@@ -7,7 +7,13 @@ use super::{
     timings::{Timing, Timings},
 };
 
-pub fn run_multi(days_to_run: &HashSet<Day>, is_release: bool, is_timed: bool) -> Option<Timings> {
+pub fn run_multi(
+    days_to_run: &HashSet<Day>,
+    is_release: bool,
+    is_timed: bool,
+    is_memory: bool,
+    is_counters: bool,
+) -> Option<Timings> {
     let mut timings: Vec<Timing> = Vec::with_capacity(days_to_run.len());
 
     let mut need_space = false;
@@ -24,7 +30,9 @@ pub fn run_multi(days_to_run: &HashSet<Day>, is_release: bool, is_timed: bool) -
             println!("{ANSI_BOLD}Day {day}{ANSI_RESET}");
             println!("------");
 
-            let output = child_commands::run_solution(day, is_timed, is_release).unwrap();
+            let output =
+                child_commands::run_solution(day, is_timed, is_release, is_memory, is_counters)
+                    .unwrap();
 
             if output.is_empty() {
                 println!("Not solved.");
@@ -68,7 +76,7 @@ pub fn get_path_for_bin(day: Day) -> String {
 /// This module encapsulates interaction with these binaries, both invoking them as well as parsing the timing output.
 pub mod child_commands {
     use super::{get_path_for_bin, Error};
-    use crate::template::Day;
+    use crate::template::{memory::MemoryStats, perf::CounterStats, Day};
     use std::{
         io::{BufRead, BufReader},
         path::Path,
@@ -77,7 +85,13 @@ pub mod child_commands {
     };
 
     /// Run the solution bin for a given day
-    pub fn run_solution(day: Day, is_timed: bool, is_release: bool) -> Result<Vec<String>, Error> {
+    pub fn run_solution(
+        day: Day,
+        is_timed: bool,
+        is_release: bool,
+        is_memory: bool,
+        is_counters: bool,
+    ) -> Result<Vec<String>, Error> {
         // skip command invocation for days that have not been scaffolded yet.
         if !Path::new(&get_path_for_bin(day)).exists() {
             return Ok(vec![]);
@@ -90,6 +104,18 @@ pub mod child_commands {
             args.push("--release");
         }
 
+        let features = [(is_memory, "memory"), (is_counters, "perf")]
+            .into_iter()
+            .filter_map(|(enabled, feature)| enabled.then_some(feature))
+            .collect::<Vec<_>>()
+            .join(",");
+
+        if !features.is_empty() {
+            // `memory` installs the counting allocator, `perf` reads hardware counters.
+            args.push("--features");
+            args.push(&features);
+        }
+
         if is_timed {
             // mirror `--time` flag to child invocations.
             args.push("--");
@@ -134,6 +160,10 @@ pub mod child_commands {
             part_1: None,
             part_2: None,
             total_nanos: 0_f64,
+            part_1_memory: None,
+            part_2_memory: None,
+            part_1_counters: None,
+            part_2_counters: None,
         };
 
         output
@@ -149,13 +179,17 @@ pub mod child_commands {
                 };
 
                 let part = l.split(':').next()?;
-                Some((part, timing_str, nanos))
+                Some((part, timing_str, nanos, parse_memory(l), parse_counters(l)))
             })
-            .for_each(|(part, timing_str, nanos)| {
+            .for_each(|(part, timing_str, nanos, memory, counters)| {
                 if part.contains("Part 1") {
                     timings.part_1 = Some(timing_str.into());
+                    timings.part_1_memory = memory;
+                    timings.part_1_counters = counters;
                 } else if part.contains("Part 2") {
                     timings.part_2 = Some(timing_str.into());
+                    timings.part_2_memory = memory;
+                    timings.part_2_counters = counters;
                 }
 
                 timings.total_nanos += nanos;
@@ -174,7 +208,7 @@ pub mod child_commands {
             .split(" samples)")
             .next()?
             .split('(')
-            .last()?
+            .next_back()?
             .split('@')
             .next()?
             .trim();
@@ -189,6 +223,22 @@ pub mod child_commands {
         Some((str_timing, parsed_timing))
     }
 
+    /// Memory stats and counters are appended after the timing, e.g.
+    /// `(1.0ms @ 10 samples) [1.0 KiB peak, 2.0 KiB total, 3 allocs] <100 instructions, 50 cycles, 1 cache misses>`.
+    fn parse_appendix<'a>(line: &'a str, open: &str, close: char) -> Option<&'a str> {
+        let (_, appendix) = line.rsplit_once(" samples)")?;
+        let (_, rest) = appendix.split_once(open)?;
+        Some(rest.split_once(close)?.0)
+    }
+
+    fn parse_memory(line: &str) -> Option<MemoryStats> {
+        MemoryStats::parse(parse_appendix(line, " [", ']')?)
+    }
+
+    fn parse_counters(line: &str) -> Option<CounterStats> {
+        CounterStats::parse(parse_appendix(line, " <", '>')?)
+    }
+
     /// copied from: https://github.com/rust-lang/rust/blob/1.64.0/library/std/src/macros.rs#L328-L333
     #[cfg(feature = "test_lib")]
     macro_rules! assert_approx_eq {
@@ -253,5 +303,42 @@ pub mod child_commands {
             assert_eq!(res.part_1.is_none(), true);
             assert_eq!(res.part_2.is_none(), true);
         }
+
+        #[test]
+        fn parses_memory_stats() {
+            let res = parse_exec_time(
+                &[
+                    "Part 1: 0 (74.13ns @ 100000 samples) [512 B peak, 1.5 KiB total, 3 allocs]"
+                        .into(),
+                    "Part 2: 10 (74.13ms @ 99999 samples)".into(),
+                    "".into(),
+                ],
+                day!(1),
+            );
+            assert_eq!(res.part_1.unwrap(), "74.13ns");
+            let memory = res.part_1_memory.unwrap();
+            assert_eq!(memory.peak_bytes, 512);
+            assert_eq!(memory.total_bytes, 1536);
+            assert_eq!(memory.allocations, 3);
+            assert_eq!(res.part_2_memory.is_none(), true);
+        }
+
+        #[test]
+        fn parses_counters() {
+            let res = parse_exec_time(
+                &[
+                    "Part 1: 0 (74.13ns @ 100000 samples) [512 B peak, 1.5 KiB total, 3 allocs] <1000 instructions, 500 cycles, 2 cache misses>".into(),
+                    "Part 2: <1 instructions, 1 cycles, 1 cache misses> (74.13ms @ 99999 samples)".into(),
+                    "".into(),
+                ],
+                day!(1),
+            );
+            assert_eq!(res.part_1_memory.unwrap().allocations, 3);
+            let counters = res.part_1_counters.unwrap();
+            assert_eq!(counters.instructions, 1000);
+            assert_eq!(counters.cycles, 500);
+            assert_eq!(counters.cache_misses, 2);
+            assert_eq!(res.part_2_counters.is_none(), true);
+        }
     }
 }
@@ -6,30 +6,92 @@ use std::process::Output;
 use std::time::{Duration, Instant};
 use std::{cmp, env, process};
 
+use crate::template::memory::{self, MemoryStats};
+use crate::template::perf::{self, CounterStats, Counters};
+use crate::template::submissions::{Submission, Submissions, Verdict};
+use crate::template::visualize;
 use crate::template::ANSI_BOLD;
 use crate::template::{aoc_cli, Day, ANSI_ITALIC, ANSI_RESET};
 
-pub fn run_part<I: Clone, T: Display>(func: impl Fn(I) -> Option<T>, input: I, day: Day, part: u8) {
+/// The return value of a solution part: either an [`Option`] or a [`Result`] of a displayable answer.
+pub trait PartResult {
+    type Answer: Display;
+
+    /// The answer, if the part produced one.
+    fn answer(&self) -> Option<&Self::Answer>;
+
+    /// A description of why the part failed, if it did.
+    fn error(&self) -> Option<String> {
+        None
+    }
+}
+
+impl<T: Display> PartResult for Option<T> {
+    type Answer = T;
+
+    fn answer(&self) -> Option<&T> {
+        self.as_ref()
+    }
+}
+
+impl<T: Display, E: Display> PartResult for Result<T, E> {
+    type Answer = T;
+
+    fn answer(&self) -> Option<&T> {
+        self.as_ref().ok()
+    }
+
+    fn error(&self) -> Option<String> {
+        self.as_ref().err().map(ToString::to_string)
+    }
+}
+
+pub fn run_part<I: Clone, R: PartResult>(func: impl Fn(I) -> R, input: I, day: Day, part: u8) {
     let part_str = format!("Part {part}");
 
-    let (result, duration, samples) =
-        run_timed(func, input, |result| print_result(result, &part_str, ""));
+    let (result, duration, samples, memory, counters) = run_timed(func, input, |result| {
+        print_result(result.answer(), &part_str, "");
+    });
+
+    // plays or renders the frames recorded by the part when started with `--visualize` or `--render`.
+    visualize::finish_recording(day, part);
+
+    let mut stats_str = format_duration(&duration, samples);
+    if let Some(memory) = memory {
+        stats_str.push_str(&format_memory(&memory));
+    }
+    if let Some(counters) = counters {
+        stats_str.push_str(&format_counters(&counters));
+    }
+
+    print_result(result.answer(), &part_str, &stats_str);
 
-    print_result(&result, &part_str, &format_duration(&duration, samples));
+    if let Some(error) = result.error() {
+        eprintln!("{part_str}: {error}");
+    }
 
-    if let Some(result) = result {
-        submit_result(result, day, part);
+    if let Some(answer) = result.answer() {
+        if let Some(Ok(output)) = submit_result(answer, day, part) {
+            record_submission(answer, day, part, &output);
+        }
     }
 }
 
 /// Run a solution part. The behavior differs depending on whether we are running a release or debug build:
 ///  1. in debug, the function is executed once.
 ///  2. in release, the function is benched (approx. 1 second of execution time or 10 samples, whatever take longer.)
+///
+/// When built with the `memory` feature, heap statistics are collected for the first execution.
+/// When built with the `perf` feature, hardware counters are averaged over the benchmark iterations.
 fn run_timed<I: Clone, T>(
     func: impl Fn(I) -> T,
     input: I,
     hook: impl Fn(&T),
-) -> (T, Duration, u128) {
+) -> (T, Duration, u128, Option<MemoryStats>, Option<CounterStats>) {
+    if memory::ENABLED {
+        memory::reset();
+    }
+
     let timer = Instant::now();
     let result = {
         let input = input.clone();
@@ -40,21 +102,28 @@ fn run_timed<I: Clone, T>(
         func(input)
     };
     let base_time = timer.elapsed();
+    let memory = memory::ENABLED.then(memory::snapshot);
 
     hook(&result);
 
     let run = if std::env::args().any(|x| x == "--time") {
         bench(func, input, &base_time)
     } else {
-        (base_time, 1)
+        (base_time, 1, None)
     };
 
-    (result, run.0, run.1)
+    (result, run.0, run.1, memory, run.2)
 }
 
-fn bench<I: Clone, T>(func: impl Fn(I) -> T, input: I, base_time: &Duration) -> (Duration, u128) {
+fn bench<I: Clone, T>(
+    func: impl Fn(I) -> T,
+    input: I,
+    base_time: &Duration,
+) -> (Duration, u128, Option<CounterStats>) {
     let mut stdout = stdout();
 
+    let counters = open_counters();
+
     print!(" > {ANSI_ITALIC}benching{ANSI_RESET}");
     let _ = stdout.flush();
 
@@ -63,21 +132,52 @@ fn bench<I: Clone, T>(func: impl Fn(I) -> T, input: I, base_time: &Duration) ->
 
     let mut timers: Vec<Duration> = vec![];
 
+    if let Some(counters) = &counters {
+        counters.reset();
+    }
+
     for _ in 0..bench_iterations {
         // need a clone here to make the borrow checker happy.
         let cloned = input.clone();
         let timer = Instant::now();
+        if let Some(counters) = &counters {
+            counters.resume();
+        }
         black_box(func(black_box(cloned)));
+        if let Some(counters) = &counters {
+            counters.pause();
+        }
         timers.push(timer.elapsed());
     }
 
+    #[allow(clippy::cast_possible_truncation)]
+    let counter_stats = counters
+        .and_then(|counters| counters.read().ok())
+        .map(|stats| stats.per_iteration(bench_iterations as u64));
+
     (
         #[allow(clippy::cast_possible_truncation)]
         Duration::from_nanos(average_duration(&timers) as u64),
         bench_iterations,
+        counter_stats,
     )
 }
 
+/// Open hardware counters if they are compiled in. Falls back to wall-clock only if they are unavailable.
+fn open_counters() -> Option<Counters> {
+    if !perf::ENABLED {
+        return None;
+    }
+
+    match Counters::open() {
+        Ok(counters) => Some(counters),
+        Err(e) => {
+            eprintln!("Hardware counters unavailable ({e}), falling back to wall-clock timings.");
+            None
+        }
+    }
+}
+
 fn average_duration(numbers: &[Duration]) -> u128 {
     numbers
         .iter()
@@ -94,7 +194,15 @@ fn format_duration(duration: &Duration, samples: u128) -> String {
     }
 }
 
-fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str) {
+fn format_memory(memory: &MemoryStats) -> String {
+    format!(" [{memory}]")
+}
+
+fn format_counters(counters: &CounterStats) -> String {
+    format!(" <{counters}>")
+}
+
+fn print_result<T: Display>(result: Option<&T>, part: &str, duration_str: &str) {
     let is_intermediate_result = duration_str.is_empty();
 
     match result {
@@ -129,6 +237,20 @@ fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str)
     }
 }
 
+/// Adds a submission to the log in `data/submissions.json`, which `cargo status` reads.
+fn record_submission<T: Display>(answer: T, day: Day, part: u8, output: &Output) {
+    let submission = Submission {
+        day,
+        part,
+        answer: answer.to_string(),
+        verdict: Verdict::from_output(&String::from_utf8_lossy(&output.stdout)),
+    };
+
+    if let Err(e) = Submissions::record(submission) {
+        eprintln!("Failed to record submission: {e}");
+    }
+}
+
 /// Parse the arguments passed to `solve` and try to submit one part of the solution if:
 ///  1. we are in `--release` mode.
 ///  2. aoc-cli is installed.
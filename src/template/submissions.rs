@@ -0,0 +1,261 @@
+use std::{collections::HashMap, fs, io::Error, str::FromStr};
+use tinyjson::JsonValue;
+
+use crate::template::Day;
+
+static SUBMISSIONS_FILE_PATH: &str = "./data/submissions.json";
+
+/// The response of the website to a submitted answer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    Incorrect,
+    /// E.g. when submitting too quickly, or for an already solved part.
+    Unknown,
+}
+
+impl Verdict {
+    /// Interprets the output of `aoc submit`.
+    pub fn from_output(output: &str) -> Self {
+        if output.contains("That's the right answer") {
+            Self::Correct
+        } else if output.contains("That's not the right answer") {
+            Self::Incorrect
+        } else {
+            Self::Unknown
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Correct => "correct",
+            Self::Incorrect => "incorrect",
+            Self::Unknown => "unknown",
+        }
+    }
+}
+
+impl FromStr for Verdict {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "correct" => Ok(Self::Correct),
+            "incorrect" => Ok(Self::Incorrect),
+            "unknown" => Ok(Self::Unknown),
+            _ => Err(format!("unknown verdict `{s}`.")),
+        }
+    }
+}
+
+/// A single answer submitted via `cargo solve NN --submit N`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Submission {
+    pub day: Day,
+    pub part: u8,
+    pub answer: String,
+    pub verdict: Verdict,
+}
+
+/// Log of all submitted answers, in submission order.
+/// Can be serialized from / to JSON.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Submissions {
+    pub data: Vec<Submission>,
+}
+
+impl Submissions {
+    /// Dehydrate submissions to a JSON file.
+    pub fn store_file(&self) -> Result<(), Error> {
+        let json = JsonValue::from(self.clone());
+        let mut file = fs::File::create(SUBMISSIONS_FILE_PATH)?;
+        json.format_to(&mut file)
+    }
+
+    /// Rehydrate submissions from a JSON file. If not present, returns an empty log.
+    pub fn read_from_file() -> Self {
+        fs::read_to_string(SUBMISSIONS_FILE_PATH)
+            .map_err(|x| x.to_string())
+            .and_then(Submissions::try_from)
+            .unwrap_or_default()
+    }
+
+    /// Appends a submission to the log file.
+    pub fn record(submission: Submission) -> Result<(), Error> {
+        let mut submissions = Self::read_from_file();
+        submissions.data.push(submission);
+        submissions.store_file()
+    }
+
+    /// The answer that was accepted for a part, if any.
+    pub fn correct_answer(&self, day: Day, part: u8) -> Option<&str> {
+        self.data
+            .iter()
+            .find(|s| s.day == day && s.part == part && s.verdict == Verdict::Correct)
+            .map(|s| s.answer.as_str())
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<Submissions> for JsonValue {
+    fn from(value: Submissions) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert(
+            "data".into(),
+            JsonValue::Array(value.data.iter().map(JsonValue::from).collect()),
+        );
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<String> for Submissions {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let json = JsonValue::from_str(&value).or(Err("not valid JSON file."))?;
+
+        let json_data = json
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("expected JSON document to be an object.")?
+            .get("data")
+            .ok_or("expected JSON document to have key `data`.")?
+            .get::<Vec<JsonValue>>()
+            .ok_or("expected `json.data` to be an array.")?;
+
+        Ok(Submissions {
+            data: json_data
+                .iter()
+                .map(Submission::try_from)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<&Submission> for JsonValue {
+    fn from(value: &Submission) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert("day".into(), JsonValue::String(value.day.to_string()));
+        map.insert("part".into(), JsonValue::Number(f64::from(value.part)));
+        map.insert("answer".into(), JsonValue::String(value.answer.clone()));
+        map.insert(
+            "verdict".into(),
+            JsonValue::String(value.verdict.as_str().into()),
+        );
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<&JsonValue> for Submission {
+    type Error = String;
+
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let json = value
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("Expected submission to be a JSON object.")?;
+
+        let day = json
+            .get("day")
+            .and_then(|v| v.get::<String>())
+            .and_then(|day| Day::from_str(day).ok())
+            .ok_or("Expected submission.day to be a Day struct.")?;
+
+        let part = json
+            .get("part")
+            .and_then(|v| v.get::<f64>())
+            .filter(|part| **part == 1.0 || **part == 2.0)
+            .map(|part| if *part == 1.0 { 1 } else { 2 })
+            .ok_or("Expected submission.part to be 1 or 2.")?;
+
+        let answer = json
+            .get("answer")
+            .and_then(|v| v.get::<String>())
+            .ok_or("Expected submission.answer to be a string.")?
+            .clone();
+
+        let verdict = json
+            .get("verdict")
+            .and_then(|v| v.get::<String>())
+            .ok_or("Expected submission.verdict to be a string.")?
+            .parse()?;
+
+        Ok(Submission {
+            day,
+            part,
+            answer,
+            verdict,
+        })
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Submission, Submissions, Verdict};
+    use crate::day;
+    use tinyjson::JsonValue;
+
+    fn get_mock_submissions() -> Submissions {
+        Submissions {
+            data: vec![
+                Submission {
+                    day: day!(1),
+                    part: 1,
+                    answer: "12".into(),
+                    verdict: Verdict::Incorrect,
+                },
+                Submission {
+                    day: day!(1),
+                    part: 1,
+                    answer: "11".into(),
+                    verdict: Verdict::Correct,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn roundtrips_json() {
+        let submissions = get_mock_submissions();
+        let json = JsonValue::from(submissions.clone()).stringify().unwrap();
+        assert_eq!(Submissions::try_from(json), Ok(submissions));
+    }
+
+    #[test]
+    fn finds_correct_answers() {
+        let submissions = get_mock_submissions();
+        assert_eq!(submissions.correct_answer(day!(1), 1), Some("11"));
+        assert_eq!(submissions.correct_answer(day!(1), 2), None);
+    }
+
+    #[test]
+    fn parses_verdicts() {
+        assert_eq!(
+            Verdict::from_output("That's the right answer! You are one gold star closer."),
+            Verdict::Correct
+        );
+        assert_eq!(
+            Verdict::from_output("That's not the right answer; your answer is too low."),
+            Verdict::Incorrect
+        );
+        assert_eq!(
+            Verdict::from_output("You gave an answer too recently"),
+            Verdict::Unknown
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn panics_for_invalid_parts() {
+        Submissions::try_from(
+            r#"{ "data": [{ "day": "01", "part": 3, "answer": "1", "verdict": "correct" }] }"#
+                .to_string(),
+        )
+        .unwrap();
+    }
+}
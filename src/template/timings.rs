@@ -1,7 +1,7 @@
 use std::{collections::HashMap, fs, io::Error, str::FromStr};
 use tinyjson::JsonValue;
 
-use crate::template::Day;
+use crate::template::{memory::MemoryStats, perf::CounterStats, Day};
 
 static TIMINGS_FILE_PATH: &str = "./data/timings.json";
 
@@ -12,6 +12,10 @@ pub struct Timing {
     pub part_1: Option<String>,
     pub part_2: Option<String>,
     pub total_nanos: f64,
+    pub part_1_memory: Option<MemoryStats>,
+    pub part_2_memory: Option<MemoryStats>,
+    pub part_1_counters: Option<CounterStats>,
+    pub part_2_counters: Option<CounterStats>,
 }
 
 /// Represents benchmark times for a set of days.
@@ -51,7 +55,7 @@ impl Timings {
             }
         }
 
-        data.sort_unstable_by(|a, b| a.day.cmp(&b.day));
+        data.sort_unstable_by_key(|a| a.day);
         Timings { data }
     }
 
@@ -65,6 +69,13 @@ impl Timings {
             .iter()
             .any(|t| t.day == day && t.part_1.is_some() && t.part_2.is_some())
     }
+
+    /// Whether any of the timings carries heap statistics.
+    pub fn has_memory(&self) -> bool {
+        self.data
+            .iter()
+            .any(|t| t.part_1_memory.is_some() || t.part_2_memory.is_some())
+    }
 }
 
 /* -------------------------------------------------------------------------- */
@@ -133,6 +144,22 @@ impl From<&Timing> for JsonValue {
             },
         );
 
+        if let Some(memory) = value.part_1_memory {
+            map.insert("part_1_memory".into(), JsonValue::from(memory));
+        }
+
+        if let Some(memory) = value.part_2_memory {
+            map.insert("part_2_memory".into(), JsonValue::from(memory));
+        }
+
+        if let Some(counters) = value.part_1_counters {
+            map.insert("part_1_counters".into(), JsonValue::from(counters));
+        }
+
+        if let Some(counters) = value.part_2_counters {
+            map.insert("part_2_counters".into(), JsonValue::from(counters));
+        }
+
         JsonValue::Object(map)
     }
 }
@@ -166,11 +193,129 @@ impl TryFrom<&JsonValue> for Timing {
             .and_then(|v| v.get::<f64>().copied())
             .ok_or("Expected timing.total_nanos to be a number.")?;
 
+        // memory stats and counters are optional to stay compatible with timings stored without them.
+        let part_1_memory = json
+            .get("part_1_memory")
+            .map(MemoryStats::try_from)
+            .transpose()?;
+
+        let part_2_memory = json
+            .get("part_2_memory")
+            .map(MemoryStats::try_from)
+            .transpose()?;
+
+        let part_1_counters = json
+            .get("part_1_counters")
+            .map(CounterStats::try_from)
+            .transpose()?;
+
+        let part_2_counters = json
+            .get("part_2_counters")
+            .map(CounterStats::try_from)
+            .transpose()?;
+
         Ok(Timing {
             day,
             part_1: part_1.cloned(),
             part_2: part_2.cloned(),
             total_nanos,
+            part_1_memory,
+            part_2_memory,
+            part_1_counters,
+            part_2_counters,
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<MemoryStats> for JsonValue {
+    #[allow(clippy::cast_precision_loss)]
+    fn from(value: MemoryStats) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert(
+            "allocations".into(),
+            JsonValue::Number(value.allocations as f64),
+        );
+        map.insert(
+            "total_bytes".into(),
+            JsonValue::Number(value.total_bytes as f64),
+        );
+        map.insert(
+            "peak_bytes".into(),
+            JsonValue::Number(value.peak_bytes as f64),
+        );
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<&JsonValue> for MemoryStats {
+    type Error = String;
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let json = value
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("Expected memory stats to be a JSON object.")?;
+
+        let field = |key: &str| {
+            json.get(key)
+                .and_then(|v| v.get::<f64>().copied())
+                .map(|v| v as u64)
+                .ok_or(format!("Expected memory.{key} to be a number."))
+        };
+
+        Ok(MemoryStats {
+            allocations: field("allocations")?,
+            total_bytes: field("total_bytes")?,
+            peak_bytes: field("peak_bytes")?,
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<CounterStats> for JsonValue {
+    #[allow(clippy::cast_precision_loss)]
+    fn from(value: CounterStats) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert(
+            "instructions".into(),
+            JsonValue::Number(value.instructions as f64),
+        );
+        map.insert("cycles".into(), JsonValue::Number(value.cycles as f64));
+        map.insert(
+            "cache_misses".into(),
+            JsonValue::Number(value.cache_misses as f64),
+        );
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<&JsonValue> for CounterStats {
+    type Error = String;
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let json = value
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("Expected counters to be a JSON object.")?;
+
+        let field = |key: &str| {
+            json.get(key)
+                .and_then(|v| v.get::<f64>().copied())
+                .map(|v| v as u64)
+                .ok_or(format!("Expected counters.{key} to be a number."))
+        };
+
+        Ok(CounterStats {
+            instructions: field("instructions")?,
+            cycles: field("cycles")?,
+            cache_misses: field("cache_misses")?,
         })
     }
 }
@@ -191,18 +336,30 @@ mod tests {
                     part_1: Some("10ms".into()),
                     part_2: Some("20ms".into()),
                     total_nanos: 3e+10,
+                    part_1_memory: None,
+                    part_2_memory: None,
+                    part_1_counters: None,
+                    part_2_counters: None,
                 },
                 Timing {
                     day: day!(2),
                     part_1: Some("30ms".into()),
                     part_2: Some("40ms".into()),
                     total_nanos: 7e+10,
+                    part_1_memory: None,
+                    part_2_memory: None,
+                    part_1_counters: None,
+                    part_2_counters: None,
                 },
                 Timing {
                     day: day!(4),
                     part_1: Some("40ms".into()),
                     part_2: None,
                     total_nanos: 4e+10,
+                    part_1_memory: None,
+                    part_2_memory: None,
+                    part_1_counters: None,
+                    part_2_counters: None,
                 },
             ],
         }
@@ -223,6 +380,17 @@ mod tests {
             assert_eq!(timing.total_nanos, 1_000_000_000_f64);
         }
 
+        #[test]
+        fn handles_memory_and_counters() {
+            let json = r#"{ "data": [{ "day": "01", "part_1": "1ms", "part_2": null, "total_nanos": 1000000000, "part_1_memory": { "allocations": 3, "total_bytes": 2048, "peak_bytes": 1024 }, "part_1_counters": { "instructions": 1000, "cycles": 500, "cache_misses": 2 } }] }"#.to_string();
+            let timings = Timings::try_from(json).unwrap();
+            let timing = timings.data.first().unwrap();
+            assert_eq!(timing.part_1_memory.unwrap().peak_bytes, 1024);
+            assert_eq!(timing.part_2_memory, None);
+            assert_eq!(timing.part_1_counters.unwrap().instructions, 1000);
+            assert_eq!(timing.part_2_counters, None);
+        }
+
         #[test]
         fn handles_empty_timings() {
             let json = r#"{ "data": [] }"#.to_string();
@@ -282,10 +450,14 @@ mod tests {
                     part_1: Some("1ms".into()),
                     part_2: Some("2ms".into()),
                     total_nanos: 3_000_000_000_f64,
+                    part_1_memory: None,
+                    part_2_memory: None,
+                    part_1_counters: None,
+                    part_2_counters: None,
                 }],
             };
 
-            assert_eq!(timings.is_day_complete(&day!(1)), true);
+            assert_eq!(timings.is_day_complete(day!(1)), true);
         }
 
         #[test]
@@ -296,10 +468,14 @@ mod tests {
                     part_1: Some("1ms".into()),
                     part_2: None,
                     total_nanos: 1_000_000_000_f64,
+                    part_1_memory: None,
+                    part_2_memory: None,
+                    part_1_counters: None,
+                    part_2_counters: None,
                 }],
             };
 
-            assert_eq!(timings.is_day_complete(&day!(1)), false);
+            assert_eq!(timings.is_day_complete(day!(1)), false);
         }
 
         #[test]
@@ -310,10 +486,14 @@ mod tests {
                     part_1: None,
                     part_2: None,
                     total_nanos: 0.0,
+                    part_1_memory: None,
+                    part_2_memory: None,
+                    part_1_counters: None,
+                    part_2_counters: None,
                 }],
             };
 
-            assert_eq!(timings.is_day_complete(&day!(1)), false);
+            assert_eq!(timings.is_day_complete(day!(1)), false);
         }
     }
 
@@ -334,6 +514,10 @@ mod tests {
                     part_1: None,
                     part_2: None,
                     total_nanos: 0_f64,
+                    part_1_memory: None,
+                    part_2_memory: None,
+                    part_1_counters: None,
+                    part_2_counters: None,
                 }],
             };
             let merged = timings.merge(&other);
@@ -354,6 +538,10 @@ mod tests {
                     part_1: None,
                     part_2: None,
                     total_nanos: 0_f64,
+                    part_1_memory: None,
+                    part_2_memory: None,
+                    part_1_counters: None,
+                    part_2_counters: None,
                 }],
             };
             let merged = timings.merge(&other);
@@ -0,0 +1,776 @@
+/// Terminal playback of grid simulations. Solutions record frames while they run and the runner
+/// plays them back when the solution is started with `--visualize`, or exports them as images
+/// with `--render <png|gif>`, see [`image`](crate::template::image):
+///
+/// ```ignore
+/// use advent_of_code::template::visualize::{self, Frame, Palette, Rgb, Style};
+///
+/// const PALETTE: Palette = Palette::new(Rgb(15, 15, 35), &[
+///     (b'#', Style::new('█', Rgb(120, 120, 140))),
+///     (b'o', Style::new('●', Rgb(0, 200, 0))),
+/// ]);
+///
+/// visualize::record(|| Frame::new(width, height, b'.', &PALETTE).with_caption(format!("step {i}")));
+/// ```
+///
+/// Recording is a no-op unless either was requested, so the frame closure does not cost
+/// anything in regular runs. Long simulations are sampled down to [`MAX_FRAMES`] frames.
+use std::{
+    env,
+    io::{self, IsTerminal, Read, Write},
+    process::{Command, Stdio},
+    sync::{Mutex, OnceLock},
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::template::{
+    image::{self, Format, DEFAULT_SCALE},
+    markdown::terminal_size,
+    Day, ANSI_BOLD, ANSI_RESET,
+};
+
+/// The maximum number of frames kept of a recording.
+pub const MAX_FRAMES: usize = 2000;
+
+/// The playback speed if `--fps` is not passed.
+pub const DEFAULT_FPS: f64 = 10.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+/// How a cell type is drawn.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Style {
+    pub glyph: char,
+    pub color: Rgb,
+    pub background: Option<Rgb>,
+}
+
+impl Style {
+    pub const fn new(glyph: char, color: Rgb) -> Self {
+        Self {
+            glyph,
+            color,
+            background: None,
+        }
+    }
+
+    /// Highlights the cell with a background colour, e.g. for overlays.
+    #[must_use]
+    pub const fn on(mut self, background: Rgb) -> Self {
+        self.background = Some(background);
+        self
+    }
+}
+
+/// Styles of the cell types of a grid. Cell types are bytes, usually the characters of the puzzle input.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Palette {
+    pub background: Rgb,
+    pub styles: &'static [(u8, Style)],
+}
+
+impl Palette {
+    pub const fn new(background: Rgb, styles: &'static [(u8, Style)]) -> Self {
+        Self { background, styles }
+    }
+
+    /// The colour of a cell type in images: the background of its style, or its colour.
+    /// Cells drawn as spaces are filled with the background of the palette.
+    pub fn fill(&self, kind: u8) -> Rgb {
+        let style = self.style(kind);
+        match (style.background, style.glyph) {
+            (Some(background), _) => background,
+            (None, ' ') => self.background,
+            (None, _) => style.color,
+        }
+    }
+
+    /// The style of a cell type. Types without a style are drawn as their character in grey.
+    pub fn style(&self, kind: u8) -> Style {
+        self.styles.iter().find(|(k, _)| *k == kind).map_or(
+            Style::new(char::from(kind), Rgb(190, 190, 190)),
+            |(_, style)| *style,
+        )
+    }
+}
+
+/// A snapshot of a grid.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Frame {
+    width: usize,
+    height: usize,
+    cells: Vec<u8>,
+    palette: &'static Palette,
+    caption: String,
+}
+
+impl Frame {
+    /// A grid filled with cells of type `fill`.
+    pub fn new(width: usize, height: usize, fill: u8, palette: &'static Palette) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![fill; width * height],
+            palette,
+            caption: String::new(),
+        }
+    }
+
+    /// A grid with one cell per character of `rows`, e.g. the puzzle input.
+    /// Shorter rows are padded with spaces.
+    pub fn from_rows(rows: &str, palette: &'static Palette) -> Self {
+        let width = rows.lines().map(str::len).max().unwrap_or(0);
+        let mut frame = Self::new(width, rows.lines().count(), b' ', palette);
+
+        for (y, row) in rows.lines().enumerate() {
+            for (x, kind) in row.bytes().enumerate() {
+                frame.set(x, y, kind);
+            }
+        }
+
+        frame
+    }
+
+    #[must_use]
+    pub fn with_caption(mut self, caption: impl Into<String>) -> Self {
+        self.caption = caption.into();
+        self
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn caption(&self) -> &str {
+        &self.caption
+    }
+
+    pub fn palette(&self) -> &'static Palette {
+        self.palette
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<u8> {
+        (x < self.width && y < self.height).then(|| self.cells[y * self.width + x])
+    }
+
+    /// Sets the type of a cell. Cells outside of the grid are ignored.
+    pub fn set(&mut self, x: usize, y: usize, kind: u8) {
+        if x < self.width && y < self.height {
+            self.cells[y * self.width + x] = kind;
+        }
+    }
+
+    /// Draws cells of type `kind` on top of the grid, e.g. a path or the positions of robots.
+    pub fn overlay(&mut self, positions: impl IntoIterator<Item = (usize, usize)>, kind: u8) {
+        for (x, y) in positions {
+            self.set(x, y, kind);
+        }
+    }
+
+    /// Renders the grid with ANSI colours, cropped to `max_width` x `max_height` cells.
+    pub fn render(&self, max_width: usize, max_height: usize) -> String {
+        let mut output = String::new();
+
+        for y in 0..self.height.min(max_height) {
+            let mut current = None;
+
+            for x in 0..self.width.min(max_width) {
+                let style = self.palette.style(self.cells[y * self.width + x]);
+                let colors = (style.color, style.background);
+
+                if current != Some(colors) {
+                    output.push_str(ANSI_RESET);
+                    output.push_str(&ansi_color(38, style.color));
+                    if let Some(background) = style.background {
+                        output.push_str(&ansi_color(48, background));
+                    }
+                    current = Some(colors);
+                }
+
+                output.push(style.glyph);
+            }
+
+            output.push_str(ANSI_RESET);
+            output.push('\n');
+        }
+
+        output
+    }
+}
+
+fn ansi_color(layer: u8, Rgb(r, g, b): Rgb) -> String {
+    format!("\x1b[{layer};2;{r};{g};{b}m")
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// How the frames of a run are shown: played back in the terminal with `--visualize`,
+/// exported with `--render <png|gif>`, or both.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Options {
+    pub play: bool,
+    pub render: Option<Format>,
+    /// The edge length of a cell in rendered images, `--scale <n>`.
+    pub scale: usize,
+    /// The playback speed, `--fps <n>`. Also used for animated GIFs.
+    pub fps: f64,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            play: false,
+            render: None,
+            scale: DEFAULT_SCALE,
+            fps: DEFAULT_FPS,
+        }
+    }
+}
+
+impl Options {
+    /// Parses the options from the arguments passed to the current process, `None` if neither
+    /// `--visualize` nor `--render` was passed.
+    pub fn from_args() -> Option<&'static Self> {
+        static OPTIONS: OnceLock<Option<Options>> = OnceLock::new();
+        OPTIONS
+            .get_or_init(|| {
+                let args: Vec<String> = env::args().skip(1).collect();
+                Self::parse(&args)
+            })
+            .as_ref()
+    }
+
+    /// Parses `--visualize`, `--render <png|gif>`, `--scale <n>` and `--fps <n>`.
+    /// Invalid values fall back to the defaults, they are validated by `cargo solve`.
+    pub fn parse(args: &[String]) -> Option<Self> {
+        let mut options = Self::default();
+
+        for (i, arg) in args.iter().enumerate() {
+            let value = args.get(i + 1);
+
+            match arg.as_str() {
+                "--visualize" => options.play = true,
+                "--render" => options.render = value.and_then(|value| value.parse().ok()),
+                "--scale" => {
+                    if let Some(scale) = value.and_then(|value| value.parse().ok()) {
+                        options.scale = scale;
+                    }
+                }
+                "--fps" => {
+                    if let Some(fps) = value.and_then(|value| value.parse().ok()) {
+                        options.fps = fps;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        (options.play || options.render.is_some()).then_some(options)
+    }
+
+    /// Arguments that select these options in a solution binary.
+    pub fn to_args(&self) -> Vec<String> {
+        let mut args = vec![];
+
+        if self.play {
+            args.push("--visualize".into());
+        }
+
+        if let Some(format) = self.render {
+            args.extend([
+                "--render".into(),
+                format.to_string(),
+                "--scale".into(),
+                self.scale.to_string(),
+            ]);
+        }
+
+        args.extend(["--fps".into(), self.fps.to_string()]);
+        args
+    }
+}
+
+/// Whether frames are recorded, i.e. `--visualize` or `--render` was passed.
+pub fn enabled() -> bool {
+    Options::from_args().is_some()
+}
+
+/// Frames of a simulation. Once full, every other frame is dropped and only every
+/// `stride`-th of the following frames is kept.
+#[derive(Debug)]
+pub struct Recording {
+    frames: Vec<Frame>,
+    stride: usize,
+    skipped: usize,
+}
+
+impl Recording {
+    pub fn new() -> Self {
+        Self {
+            frames: vec![],
+            stride: 1,
+            skipped: 0,
+        }
+    }
+
+    pub fn push(&mut self, frame: impl FnOnce() -> Frame) {
+        self.skipped += 1;
+        if self.skipped < self.stride {
+            return;
+        }
+
+        self.push_key_frame(frame);
+    }
+
+    /// Adds a frame regardless of the sampling, e.g. the final state of a simulation.
+    pub fn push_key_frame(&mut self, frame: impl FnOnce() -> Frame) {
+        self.skipped = 0;
+        self.frames.push(frame());
+
+        if self.frames.len() > MAX_FRAMES {
+            let mut i = 0;
+            self.frames.retain(|_| {
+                i += 1;
+                i % 2 == 1
+            });
+            self.stride *= 2;
+        }
+    }
+
+    pub fn into_frames(self) -> Vec<Frame> {
+        self.frames
+    }
+}
+
+impl Default for Recording {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+static RECORDING: Mutex<Option<Recording>> = Mutex::new(None);
+
+/// Adds a frame to the recording of the running part. `frame` is only called when the
+/// visualization is enabled and the frame is kept.
+pub fn record(frame: impl FnOnce() -> Frame) {
+    if !enabled() {
+        return;
+    }
+
+    let mut recording = RECORDING.lock().unwrap();
+    recording.get_or_insert_with(Recording::new).push(frame);
+}
+
+/// Like [`record`], but the frame is always kept, e.g. the final state of a simulation.
+pub fn record_key_frame(frame: impl FnOnce() -> Frame) {
+    if !enabled() {
+        return;
+    }
+
+    let mut recording = RECORDING.lock().unwrap();
+    recording
+        .get_or_insert_with(Recording::new)
+        .push_key_frame(frame);
+}
+
+/// Takes the frames recorded so far.
+pub fn take_recording() -> Vec<Frame> {
+    RECORDING
+        .lock()
+        .unwrap()
+        .take()
+        .map(Recording::into_frames)
+        .unwrap_or_default()
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Key {
+    TogglePause,
+    Next,
+    Previous,
+    Faster,
+    Slower,
+    Reverse,
+    First,
+    Last,
+    Quit,
+}
+
+/// Parses key presses, including the escape sequences of the arrow keys.
+pub fn parse_keys(bytes: &[u8]) -> Vec<Key> {
+    let mut keys = vec![];
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let key = match bytes[i..] {
+            [0x1b, b'[', b'C', ..] => Some(Key::Next),
+            [0x1b, b'[', b'D', ..] => Some(Key::Previous),
+            [0x1b, b'[', b'A', ..] => Some(Key::Faster),
+            [0x1b, b'[', b'B', ..] => Some(Key::Slower),
+            [b' ', ..] => Some(Key::TogglePause),
+            [b'l' | b'.', ..] => Some(Key::Next),
+            [b'h' | b',', ..] => Some(Key::Previous),
+            [b'+' | b'=', ..] => Some(Key::Faster),
+            [b'-', ..] => Some(Key::Slower),
+            [b'r', ..] => Some(Key::Reverse),
+            [b'g', ..] => Some(Key::First),
+            [b'G', ..] => Some(Key::Last),
+            [b'q' | 0x03, ..] => Some(Key::Quit),
+            _ => None,
+        };
+
+        i += if bytes[i] == 0x1b && bytes.len() >= i + 3 {
+            3
+        } else {
+            1
+        };
+        keys.extend(key);
+    }
+
+    keys
+}
+
+/// Playback state: the shown frame, direction and speed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Player {
+    pub index: usize,
+    pub len: usize,
+    pub playing: bool,
+    pub backwards: bool,
+    pub fps: f64,
+}
+
+impl Player {
+    pub fn new(len: usize, fps: f64) -> Self {
+        Self {
+            index: 0,
+            len,
+            playing: true,
+            backwards: false,
+            fps,
+        }
+    }
+
+    /// Applies a key press. Returns `false` if the playback should stop.
+    pub fn handle(&mut self, key: Key) -> bool {
+        match key {
+            Key::TogglePause => self.playing = !self.playing,
+            Key::Next => {
+                self.playing = false;
+                self.index = (self.index + 1).min(self.len - 1);
+            }
+            Key::Previous => {
+                self.playing = false;
+                self.index = self.index.saturating_sub(1);
+            }
+            Key::Faster => self.fps = (self.fps * 2.0).min(1000.0),
+            Key::Slower => self.fps = (self.fps / 2.0).max(0.25),
+            Key::Reverse => self.backwards = !self.backwards,
+            Key::First => self.index = 0,
+            Key::Last => self.index = self.len - 1,
+            Key::Quit => return false,
+        }
+        true
+    }
+
+    /// Advances the playback by one frame. Playback pauses at either end.
+    pub fn tick(&mut self) {
+        if !self.playing {
+            return;
+        }
+
+        let next = if self.backwards {
+            self.index.checked_sub(1)
+        } else {
+            Some(self.index + 1).filter(|next| *next < self.len)
+        };
+
+        match next {
+            Some(next) => self.index = next,
+            None => self.playing = false,
+        }
+    }
+
+    pub fn at_end(&self) -> bool {
+        self.index + 1 == self.len
+    }
+}
+
+/// Puts the terminal into non-canonical mode without echo, so key presses can be read
+/// one by one. The previous settings are restored on drop.
+struct RawTerminal {
+    settings: String,
+}
+
+impl RawTerminal {
+    fn enable() -> Option<Self> {
+        let settings = stty(&["-g"])?;
+        stty(&["-icanon", "-echo", "min", "0", "time", "0"])?;
+        Some(Self {
+            settings: settings.trim().to_string(),
+        })
+    }
+}
+
+impl Drop for RawTerminal {
+    fn drop(&mut self) {
+        stty(&[&self.settings]);
+    }
+}
+
+fn stty(args: &[&str]) -> Option<String> {
+    let output = Command::new("stty")
+        .args(args)
+        .stdin(Stdio::inherit())
+        .output()
+        .ok()?;
+
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+fn draw(frame: &Frame, player: &Player, interactive: bool) {
+    let (columns, rows) = terminal_size();
+
+    let mut screen = String::from("\x1b[H");
+    for line in frame.render(columns, rows.saturating_sub(3)).lines() {
+        screen.push_str(line);
+        screen.push_str("\x1b[K\n");
+    }
+
+    screen.push_str(&format!(
+        "{ANSI_BOLD}{}{ANSI_RESET}\x1b[K\n",
+        frame.caption()
+    ));
+    screen.push_str(&format!(
+        "frame {}/{} · {} fps{}{}",
+        player.index + 1,
+        player.len,
+        player.fps,
+        match (player.playing, player.backwards) {
+            (false, _) => " · paused",
+            (true, true) => " · backwards",
+            (true, false) => "",
+        },
+        if interactive {
+            " · [space] pause [←/→] step [↑/↓] speed [r] reverse [q] quit"
+        } else {
+            ""
+        }
+    ));
+    screen.push_str("\x1b[K\x1b[J");
+
+    let mut stdout = io::stdout();
+    let _ = stdout.write_all(screen.as_bytes());
+    let _ = stdout.flush();
+}
+
+/// Plays frames in the terminal. Without a terminal, only the last frame is printed.
+pub fn play(frames: &[Frame], fps: f64) {
+    let Some(last) = frames.last() else {
+        return;
+    };
+
+    if !io::stdout().is_terminal() {
+        println!();
+        print!("{}", last.render(usize::MAX, usize::MAX));
+        println!("{}", last.caption());
+        return;
+    }
+
+    let terminal = io::stdin()
+        .is_terminal()
+        .then(RawTerminal::enable)
+        .flatten();
+    let interactive = terminal.is_some();
+
+    let mut player = Player::new(frames.len(), fps);
+    let mut stdin = io::stdin();
+    let mut buffer = [0; 64];
+
+    // hide the cursor and clear the screen.
+    print!("\x1b[?25l\x1b[2J");
+
+    'playback: loop {
+        draw(&frames[player.index], &player, interactive);
+
+        if !interactive && !player.playing {
+            break;
+        }
+
+        let deadline = Instant::now() + Duration::from_secs_f64(1.0 / player.fps);
+        while Instant::now() < deadline || !player.playing {
+            if interactive {
+                let read = stdin.read(&mut buffer).unwrap_or(0);
+                let keys = parse_keys(&buffer[..read]);
+                for key in &keys {
+                    if !player.handle(*key) {
+                        break 'playback;
+                    }
+                }
+                if !keys.is_empty() {
+                    continue 'playback;
+                }
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+
+        player.tick();
+        if !interactive && player.at_end() {
+            draw(&frames[player.index], &player, interactive);
+            break;
+        }
+    }
+
+    // show the cursor again.
+    println!("\x1b[?25h");
+    drop(terminal);
+}
+
+/// Plays back and exports the frames recorded by the running part, as requested by the
+/// arguments. Called by the runner after each part.
+pub fn finish_recording(day: Day, part: u8) {
+    let Some(options) = Options::from_args() else {
+        return;
+    };
+
+    let frames = take_recording();
+
+    if let (Some(format), false) = (options.render, frames.is_empty()) {
+        match image::export(day, part, &frames, format, options.scale, options.fps) {
+            Ok(path) => eprintln!(
+                "Part {part}: rendered {} frame(s) to {}",
+                frames.len(),
+                path.display()
+            ),
+            Err(e) => eprintln!("Part {part}: failed to render frames: {e}"),
+        }
+    }
+
+    if options.play {
+        play(&frames, options.fps);
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{
+        parse_keys, Frame, Key, Options, Palette, Player, Recording, Rgb, Style, MAX_FRAMES,
+    };
+    use crate::template::image::Format;
+
+    const PALETTE: Palette = Palette::new(
+        Rgb(0, 0, 0),
+        &[(b'#', Style::new('█', Rgb(255, 255, 255)).on(Rgb(1, 2, 3)))],
+    );
+
+    #[test]
+    fn parses_options() {
+        let args = |s: &str| s.split(' ').map(String::from).collect::<Vec<_>>();
+
+        assert_eq!(Options::parse(&args("--example 2")), None);
+        assert_eq!(
+            Options::parse(&args("--render gif --scale 2 --fps 5")),
+            Some(Options {
+                play: false,
+                render: Some(Format::Gif),
+                scale: 2,
+                fps: 5.0
+            })
+        );
+
+        let options = Options {
+            play: true,
+            render: Some(Format::Png),
+            ..Options::default()
+        };
+        assert_eq!(Options::parse(&options.to_args()), Some(options));
+    }
+
+    #[test]
+    fn builds_frames() {
+        let mut frame = Frame::from_rows("#.\n.", &PALETTE);
+        assert_eq!((frame.width(), frame.height()), (2, 2));
+        assert_eq!(frame.get(1, 1), Some(b' '));
+
+        frame.overlay([(1, 1), (5, 5)], b'o');
+        assert_eq!(frame.get(1, 1), Some(b'o'));
+        assert_eq!(frame.get(5, 5), None);
+    }
+
+    #[test]
+    fn renders_frames() {
+        let frame = Frame::from_rows("#.\n##", &PALETTE);
+        let rendered = frame.render(1, 10);
+
+        assert_eq!(
+            rendered,
+            "\x1b[0m\x1b[38;2;255;255;255m\x1b[48;2;1;2;3m█\x1b[0m\n\
+             \x1b[0m\x1b[38;2;255;255;255m\x1b[48;2;1;2;3m█\x1b[0m\n"
+        );
+        assert!(frame.render(10, 10).contains("\x1b[38;2;190;190;190m."));
+    }
+
+    #[test]
+    fn samples_long_recordings() {
+        let mut recording = Recording::new();
+        let mut calls = 0;
+
+        for i in 0..MAX_FRAMES * 3 {
+            recording.push(|| {
+                calls += 1;
+                Frame::new(1, 1, b'.', &PALETTE).with_caption(i.to_string())
+            });
+        }
+
+        recording.push_key_frame(|| Frame::new(1, 1, b'.', &PALETTE).with_caption("last"));
+
+        let frames = recording.into_frames();
+        assert_eq!(frames.last().unwrap().caption(), "last");
+        assert!(frames.len() <= MAX_FRAMES + 1);
+        assert!(frames.len() >= MAX_FRAMES / 2);
+        assert!(calls < MAX_FRAMES * 2);
+        assert_eq!(frames[0].caption(), "0");
+    }
+
+    #[test]
+    fn parses_keys() {
+        assert_eq!(
+            parse_keys(b" \x1b[C\x1b[Dq"),
+            vec![Key::TogglePause, Key::Next, Key::Previous, Key::Quit]
+        );
+    }
+
+    #[test]
+    fn steps_through_frames() {
+        let mut player = Player::new(3, 10.0);
+        player.tick();
+        player.tick();
+        player.tick();
+        assert_eq!(player.index, 2);
+        assert!(!player.playing);
+
+        player.handle(Key::Reverse);
+        player.handle(Key::TogglePause);
+        player.tick();
+        assert_eq!(player.index, 1);
+
+        player.handle(Key::Previous);
+        player.handle(Key::Previous);
+        assert_eq!((player.index, player.playing), (0, false));
+
+        player.handle(Key::Faster);
+        assert_eq!(player.fps, 20.0);
+        assert!(!player.handle(Key::Quit));
+    }
+}
@@ -0,0 +1,186 @@
+/// End-to-end tests of the aoc-cli integration against the offline mock server.
+/// `AOC_CLI` points the integration at the `mock-aoc` binary, which talks to the server.
+use std::{
+    env, fs,
+    path::PathBuf,
+    sync::{Mutex, MutexGuard},
+};
+
+use advent_of_code::{
+    day,
+    template::{
+        aoc_cli::{self, AocCommandError},
+        mock_aoc::{MockServer, Response},
+        read_file,
+    },
+};
+
+const PUZZLE: &str = "<article><h2>--- Day 1: Historian Hysteria ---</h2><p>For example:</p><pre><code>3   4\n4   3\n</code></pre><p>The total is <code><em>11</em></code>.</p></article>";
+
+/// The tests change the working directory and environment of the process, so they run one at a time.
+static LOCK: Mutex<()> = Mutex::new(());
+
+struct Context {
+    server: MockServer,
+    dir: PathBuf,
+    _guard: MutexGuard<'static, ()>,
+}
+
+impl Drop for Context {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
+
+fn setup(name: &str) -> Context {
+    let guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+
+    let dir = env::temp_dir().join(format!("aoc-cli-{name}-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(dir.join("data/inputs")).unwrap();
+    fs::create_dir_all(dir.join("data/puzzles")).unwrap();
+    env::set_current_dir(&dir).unwrap();
+
+    let server = MockServer::start().unwrap();
+    env::set_var("AOC_CLI", env!("CARGO_BIN_EXE_mock-aoc"));
+    env::set_var("AOC_MOCK_URL", server.url());
+    env::set_var("ADVENT_OF_CODE_SESSION", "test-session");
+    env::set_var("AOC_YEAR", "2024");
+    env::remove_var("AOC_INPUT_KEY");
+
+    Context {
+        server,
+        dir,
+        _guard: guard,
+    }
+}
+
+#[test]
+fn finds_command() {
+    let _context = setup("check");
+    assert!(aoc_cli::check().is_ok());
+}
+
+#[test]
+fn downloads_input_and_puzzle() {
+    let context = setup("download");
+    context.server.add_input(2024, 1, "3   4\n4   3\n");
+    context.server.add_puzzle(2024, 1, PUZZLE);
+
+    aoc_cli::download(day!(1)).unwrap();
+
+    assert_eq!(
+        fs::read_to_string("data/inputs/01.txt").unwrap(),
+        "3   4\n4   3\n"
+    );
+    let puzzle = fs::read_to_string("data/puzzles/01.md").unwrap();
+    assert!(puzzle.starts_with("\\--- Day 1: Historian Hysteria ---"));
+    assert!(puzzle.contains("`*11*`"));
+
+    let paths: Vec<String> = context
+        .server
+        .requests()
+        .into_iter()
+        .map(|request| {
+            assert_eq!(request.session.as_deref(), Some("test-session"));
+            format!("{} {}", request.method, request.path)
+        })
+        .collect();
+    assert_eq!(paths, ["GET /2024/day/1/input", "GET /2024/day/1"]);
+}
+
+#[test]
+fn downloads_puzzle_only() {
+    let context = setup("download_puzzle");
+    context.server.add_puzzle(2024, 7, PUZZLE);
+
+    aoc_cli::download_puzzle(day!(7)).unwrap();
+
+    assert!(fs::metadata("data/puzzles/07.md").is_ok());
+    assert!(fs::metadata("data/inputs/07.txt").is_err());
+    assert_eq!(context.server.requests().len(), 1);
+}
+
+#[test]
+fn fails_for_locked_days() {
+    let _context = setup("locked");
+
+    assert!(matches!(
+        aoc_cli::download(day!(25)),
+        Err(AocCommandError::BadExitStatus(_))
+    ));
+}
+
+#[test]
+fn fails_without_year() {
+    let context = setup("year");
+    env::remove_var("AOC_YEAR");
+    context.server.add_input(2024, 1, "1\n");
+
+    assert!(aoc_cli::download(day!(1)).is_err());
+    assert!(context.server.requests().is_empty());
+}
+
+#[test]
+fn reads_puzzle() {
+    let context = setup("read");
+    context.server.add_puzzle(2024, 1, PUZZLE);
+
+    aoc_cli::read(day!(1)).unwrap();
+
+    assert!(fs::read_to_string("data/puzzles/01.md")
+        .unwrap()
+        .contains("For example:"));
+}
+
+#[test]
+fn submits_with_scripted_verdicts() {
+    let context = setup("submit");
+    context.server.add_response(2024, 3, 2, Response::TooLow);
+    context.server.add_response(2024, 3, 2, Response::Correct);
+
+    let first = aoc_cli::submit(day!(3), 2, "47").unwrap();
+    let second = aoc_cli::submit(day!(3), 2, "48").unwrap();
+
+    assert!(String::from_utf8_lossy(&first.stdout).contains("your answer is too low"));
+    assert!(String::from_utf8_lossy(&second.stdout).contains("That's the right answer"));
+
+    let bodies: Vec<String> = context
+        .server
+        .requests()
+        .into_iter()
+        .map(|request| format!("{} {} {}", request.method, request.path, request.body))
+        .collect();
+    assert_eq!(
+        bodies,
+        [
+            "POST /2024/day/3/answer level=2&answer=47",
+            "POST /2024/day/3/answer level=2&answer=48"
+        ]
+    );
+}
+
+#[test]
+fn encrypts_downloaded_inputs() {
+    let context = setup("encrypt");
+    env::set_var("AOC_INPUT_KEY", "test-key");
+    context.server.add_input(2024, 1, "3   4\n4   3\n");
+    context.server.add_puzzle(2024, 1, PUZZLE);
+
+    aoc_cli::download(day!(1)).unwrap();
+    env::remove_var("AOC_INPUT_KEY");
+
+    let encrypted = fs::read("data/inputs/01.txt.enc").unwrap();
+    assert!(!String::from_utf8_lossy(&encrypted).contains("3   4"));
+
+    fs::remove_file("data/inputs/01.txt").unwrap();
+    assert!(std::panic::catch_unwind(|| read_file("inputs", day!(1))).is_err());
+
+    env::set_var("AOC_INPUT_KEY", "test-key");
+    assert_eq!(read_file("inputs", day!(1)), "3   4\n4   3\n");
+
+    // an empty input, as created by `cargo scaffold`, does not hide the encrypted one.
+    fs::write("data/inputs/01.txt", "").unwrap();
+    assert_eq!(read_file("inputs", day!(1)), "3   4\n4   3\n");
+    env::remove_var("AOC_INPUT_KEY");
+}
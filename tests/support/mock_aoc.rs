@@ -0,0 +1,136 @@
+/// Mimics the aoc-cli command-line against the mock server in `src/template/mock_aoc.rs`.
+/// Supports the subset of aoc-cli used by the template: `download`, `read` and `submit`.
+use std::{env, fs, path::Path, process::ExitCode};
+
+use advent_of_code::template::mock_aoc::{html_to_markdown, send};
+
+#[derive(Default)]
+struct Args {
+    year: Option<u16>,
+    day: Option<u8>,
+    overwrite: bool,
+    input_only: bool,
+    puzzle_only: bool,
+    input_file: Option<String>,
+    puzzle_file: Option<String>,
+    positional: Vec<String>,
+}
+
+fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Args, String> {
+    let mut parsed = Args::default();
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().ok_or(format!("missing value for `{name}`"));
+
+        match arg.as_str() {
+            "--year" => {
+                let year = value("--year")?;
+                parsed.year = Some(year.parse().map_err(|_| format!("invalid year `{year}`"))?);
+            }
+            "--day" => {
+                let day = value("--day")?;
+                parsed.day = Some(day.parse().map_err(|_| format!("invalid day `{day}`"))?);
+            }
+            "--input-file" => parsed.input_file = Some(value("--input-file")?),
+            "--puzzle-file" => parsed.puzzle_file = Some(value("--puzzle-file")?),
+            "--overwrite" => parsed.overwrite = true,
+            "--input-only" => parsed.input_only = true,
+            "--puzzle-only" => parsed.puzzle_only = true,
+            "--description-only" => {}
+            "-V" => parsed.positional.push(arg),
+            flag if flag.starts_with('-') => return Err(format!("unknown flag `{flag}`")),
+            _ => parsed.positional.push(arg),
+        }
+    }
+
+    Ok(parsed)
+}
+
+fn write_file(path: &str, contents: &str, overwrite: bool) -> Result<(), String> {
+    if Path::new(path).exists() && !overwrite {
+        return Err(format!("file \"{path}\" already exists, use --overwrite"));
+    }
+    fs::write(path, contents).map_err(|e| format!("could not write \"{path}\": {e}"))
+}
+
+fn get(url: &str, path: &str, session: &str) -> Result<String, String> {
+    match send(url, "GET", path, session, None).map_err(|e| e.to_string())? {
+        (200, body) => Ok(body),
+        (status, body) => Err(format!("server returned {status}: {}", body.trim())),
+    }
+}
+
+fn run() -> Result<(), String> {
+    let args = parse_args(env::args().skip(1))?;
+
+    if args.positional.first().map(String::as_str) == Some("-V") {
+        println!("aoc-cli (mock)");
+        return Ok(());
+    }
+
+    let url = env::var("AOC_MOCK_URL").map_err(|_| "`AOC_MOCK_URL` is not set")?;
+    let session = env::var("ADVENT_OF_CODE_SESSION").unwrap_or_else(|_| "mock-session".into());
+
+    // unlike aoc-cli, the year is never inferred so tests notice if it is not passed.
+    let year = args.year.ok_or("missing `--year`")?;
+    let day = args.day.ok_or("missing `--day`")?;
+    let page_path = format!("/{year}/day/{day}");
+
+    match args.positional.first().map(String::as_str) {
+        Some("download") => {
+            if !args.puzzle_only {
+                let input = get(&url, &format!("{page_path}/input"), &session)?;
+                let path = args.input_file.as_deref().unwrap_or("input");
+                write_file(path, &input, args.overwrite)?;
+            }
+            if !args.input_only {
+                let puzzle = html_to_markdown(&get(&url, &page_path, &session)?);
+                let path = args.puzzle_file.as_deref().unwrap_or("puzzle.md");
+                write_file(path, &puzzle, args.overwrite)?;
+            }
+            Ok(())
+        }
+        Some("read") => {
+            let puzzle = html_to_markdown(&get(&url, &page_path, &session)?);
+            if let Some(path) = &args.puzzle_file {
+                write_file(path, &puzzle, true)?;
+            }
+            print!("{puzzle}");
+            Ok(())
+        }
+        Some("submit") => {
+            let [_, part, answer] = args.positional.as_slice() else {
+                return Err("usage: submit <part> <answer>".into());
+            };
+            let body = format!("level={part}&answer={answer}");
+            match send(
+                &url,
+                "POST",
+                &format!("{page_path}/answer"),
+                &session,
+                Some(&body),
+            )
+            .map_err(|e| e.to_string())?
+            {
+                (200, body) => {
+                    print!("{}", html_to_markdown(&body));
+                    Ok(())
+                }
+                (status, body) => Err(format!("server returned {status}: {}", body.trim())),
+            }
+        }
+        Some(command) => Err(format!("unknown command `{command}`")),
+        None => Err("missing command".into()),
+    }
+}
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("mock-aoc: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}